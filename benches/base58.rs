@@ -0,0 +1,102 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tetanus::utils::{decode_from_string, encode_to_string, EncodeType};
+
+/// `EncodeType` is not `Copy`, so a fresh value is built for every call
+fn encode_type(name: &str) -> EncodeType {
+    match name {
+        "Sha256x2" => EncodeType::Sha256x2,
+        "PubKey" => EncodeType::PubKey,
+        _ => EncodeType::K1,
+    }
+}
+
+/// The buffer sizes each encoding actually sees: a 65 byte K1 signature, a 33
+/// byte compressed public key and a 32 byte private key
+const FIXED_INPUTS: [(&str, usize); 3] = [("K1", 65), ("PubKey", 33), ("Sha256x2", 32)];
+
+fn buffer(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 31 + 7) as u8).collect()
+}
+
+fn encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode");
+
+    for (name, len) in FIXED_INPUTS {
+        let input = buffer(len);
+        group.throughput(Throughput::Bytes(len as u64));
+
+        group.bench_with_input(BenchmarkId::new("encode_to_string", name), &input, |b, input| {
+            b.iter(|| encode_to_string(black_box(input.clone()), Some(encode_type(name))))
+        });
+        group.bench_with_input(BenchmarkId::new("bs58", name), &input, |b, input| {
+            b.iter(|| bs58::encode(black_box(input)).into_string())
+        });
+    }
+
+    group.finish();
+}
+
+fn decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+
+    for (name, len) in FIXED_INPUTS {
+        let encoded = encode_to_string(buffer(len), Some(encode_type(name)));
+        group.throughput(Throughput::Bytes(len as u64));
+
+        group.bench_with_input(BenchmarkId::new("decode_from_string", name), &encoded, |b, encoded| {
+            b.iter(|| decode_from_string(black_box(encoded.clone()), Some(encode_type(name))))
+        });
+        group.bench_with_input(BenchmarkId::new("bs58", name), &encoded, |b, encoded| {
+            b.iter(|| bs58::decode(black_box(encoded)).into_vec().unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+/// Only encoding accepts arbitrary lengths, decoding asserts on the expected
+/// key or signature size
+fn encode_sizes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode_sizes");
+
+    for len in [16, 32, 65, 128, 256, 1024] {
+        let input = buffer(len);
+        group.throughput(Throughput::Bytes(len as u64));
+
+        group.bench_with_input(BenchmarkId::new("encode_to_string", len), &input, |b, input| {
+            b.iter(|| encode_to_string(black_box(input.clone()), Some(EncodeType::K1)))
+        });
+        group.bench_with_input(BenchmarkId::new("bs58", len), &input, |b, input| {
+            b.iter(|| bs58::encode(black_box(input)).into_string())
+        });
+    }
+
+    group.finish();
+}
+
+/// Compares the two encoding backends directly, only available when the
+/// hand-rolled encoder is compiled in
+#[cfg(feature = "fast-base58")]
+fn backends(c: &mut Criterion) {
+    let mut group = c.benchmark_group("backends");
+
+    for (name, len) in FIXED_INPUTS {
+        let input = buffer(len);
+        group.throughput(Throughput::Bytes(len as u64));
+
+        group.bench_with_input(BenchmarkId::new("encode_fast", name), &input, |b, input| {
+            b.iter(|| tetanus::base58::encode_fast(black_box(input)))
+        });
+        group.bench_with_input(BenchmarkId::new("bs58", name), &input, |b, input| {
+            b.iter(|| bs58::encode(black_box(input)).into_string())
+        });
+    }
+
+    group.finish();
+}
+
+#[cfg(not(feature = "fast-base58"))]
+criterion_group!(benches, encode, decode, encode_sizes);
+#[cfg(feature = "fast-base58")]
+criterion_group!(benches, encode, decode, encode_sizes, backends);
+criterion_main!(benches);
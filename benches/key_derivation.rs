@@ -0,0 +1,117 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use tetanus::keys::private::PrivateKey;
+use tetanus::keys::public::PublicKey;
+use tetanus::utils::{decode_from_string, encode_to_string, EncodeType};
+
+/// Counts every allocation made by the benchmark binary so that the number of
+/// allocations per call can be reported next to the wall-clock timings
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const PRIVATE_WIF: &str = "5K8AruCpTY6gVeQRMd5UpeuoVR2YheRCjUDAVFrfiahZU4bBccj";
+const PUBLIC_WIF: &str = "STM5jixkNBqJXNtX9vy2GjaqpX2d5jXrcjRXgh1WU5fXZhnDJrLM8";
+const SIGNATURE: &str = "JvYLntg1nfTLFTMX9mXGJB95WnbceLKwcvWTc16tVVCX1eCvFKXAtcuRs8xtRqMhH8oHFYAoWUYg8n9iV5nuLxtHojE2eo";
+
+/// Prints how many allocations a single call of `f` makes
+fn report_allocations<T>(name: &str, f: impl Fn() -> T) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    black_box(f());
+    let after = ALLOCATIONS.load(Ordering::Relaxed);
+
+    println!("{}: {} allocations per call", name, after - before);
+}
+
+fn from_login(c: &mut Criterion) {
+    let mut group = c.benchmark_group("PrivateKey::from_login");
+
+    for len in [4, 16, 64, 256] {
+        let username = "u".repeat(len);
+        let password = "p".repeat(len);
+
+        report_allocations(&format!("from_login/{}", len), || {
+            PrivateKey::from_login(&username, &password, "owner")
+        });
+
+        group.bench_with_input(BenchmarkId::from_parameter(len), &len, |b, _| {
+            b.iter(|| PrivateKey::from_login(black_box(&username), black_box(&password), "owner"))
+        });
+    }
+
+    group.finish();
+}
+
+fn wif_decode(c: &mut Criterion) {
+    report_allocations("PrivateKey::from_string", || PrivateKey::from_string(PRIVATE_WIF));
+    report_allocations("PublicKey::from_string", || PublicKey::from_string(PUBLIC_WIF));
+
+    c.bench_function("PrivateKey::from_string", |b| {
+        b.iter(|| PrivateKey::from_string(black_box(PRIVATE_WIF)))
+    });
+    c.bench_function("PublicKey::from_string", |b| {
+        b.iter(|| PublicKey::from_string(black_box(PUBLIC_WIF)))
+    });
+}
+
+fn encoding(c: &mut Criterion) {
+    let inputs = [
+        ("K1", SIGNATURE),
+        ("Sha256x2", PRIVATE_WIF),
+        ("PubKey", PUBLIC_WIF.trim_start_matches("STM")),
+    ];
+
+    let mut encode_group = c.benchmark_group("encode_to_string");
+    for (name, encoded) in inputs {
+        let decoded = decode_from_string(encoded.to_string(), Some(encode_type(name))).unwrap();
+
+        report_allocations(&format!("encode_to_string/{}", name), || {
+            encode_to_string(decoded.clone(), Some(encode_type(name)))
+        });
+
+        encode_group.bench_with_input(BenchmarkId::from_parameter(name), &decoded, |b, decoded| {
+            b.iter(|| encode_to_string(black_box(decoded.clone()), Some(encode_type(name))))
+        });
+    }
+    encode_group.finish();
+
+    let mut decode_group = c.benchmark_group("decode_from_string");
+    for (name, encoded) in inputs {
+        report_allocations(&format!("decode_from_string/{}", name), || {
+            decode_from_string(encoded.to_string(), Some(encode_type(name)))
+        });
+
+        decode_group.bench_with_input(BenchmarkId::from_parameter(name), encoded, |b, encoded| {
+            b.iter(|| decode_from_string(black_box(encoded.to_string()), Some(encode_type(name))))
+        });
+    }
+    decode_group.finish();
+}
+
+/// `EncodeType` is not `Copy`, so a fresh value is built for every call
+fn encode_type(name: &str) -> EncodeType {
+    match name {
+        "Sha256x2" => EncodeType::Sha256x2,
+        "PubKey" => EncodeType::PubKey,
+        _ => EncodeType::K1,
+    }
+}
+
+criterion_group!(benches, from_login, wif_decode, encoding);
+criterion_main!(benches);
@@ -0,0 +1,24 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use tetanus::keys::private::PrivateKey;
+
+fn sign_batch(c: &mut Criterion) {
+    let private = PrivateKey::from_login("test", "test", "owner");
+    let mut group = c.benchmark_group("sign_batch");
+
+    for count in [1, 10, 100] {
+        let owned: Vec<String> = (0..count).map(|i| format!("message {}", i)).collect();
+        let messages: Vec<&str> = owned.iter().map(String::as_str).collect();
+
+        group.bench_with_input(BenchmarkId::new("sign_message", count), &messages, |b, messages| {
+            b.iter(|| messages.iter().map(|message| private.sign_message(black_box(message))).collect::<Vec<_>>())
+        });
+        group.bench_with_input(BenchmarkId::new("sign_many", count), &messages, |b, messages| {
+            b.iter(|| private.sign_many(black_box(messages)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, sign_batch);
+criterion_main!(benches);
@@ -0,0 +1,80 @@
+//! Exercises key derivation, signing and recovery from a `no_std` crate. Build
+//! it for a bare metal target with
+//!
+//! `cargo build --example no_std_test --no-default-features --target thumbv7em-none-eabihf`
+//!
+//! With the default features it still builds as an ordinary static library, in
+//! which case the allocator and panic handler come from `std`.
+#![no_std]
+
+#[cfg(not(feature = "std"))]
+mod runtime {
+    use core::alloc::{GlobalAlloc, Layout};
+    use core::cell::UnsafeCell;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    const HEAP_SIZE: usize = 64 * 1024;
+
+    /// Hands out memory from a fixed buffer and never frees it, enough for a
+    /// handful of signatures
+    struct BumpAllocator {
+        heap: UnsafeCell<[u8; HEAP_SIZE]>,
+        next: AtomicUsize,
+    }
+
+    unsafe impl Sync for BumpAllocator {}
+
+    unsafe impl GlobalAlloc for BumpAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let base = self.heap.get() as usize;
+            let mut start = 0;
+            let claimed = self.next.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |next| {
+                start = (base + next).next_multiple_of(layout.align()) - base;
+                Some(start + layout.size()).filter(|end| *end <= HEAP_SIZE)
+            });
+
+            match claimed {
+                Ok(_) => (base + start) as *mut u8,
+                Err(_) => core::ptr::null_mut(),
+            }
+        }
+
+        unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: BumpAllocator = BumpAllocator {
+        heap: UnsafeCell::new([0; HEAP_SIZE]),
+        next: AtomicUsize::new(0),
+    };
+
+    #[panic_handler]
+    fn panic(_info: &core::panic::PanicInfo) -> ! {
+        loop {}
+    }
+}
+
+use tetanus::keys::{private::PrivateKey, public::PublicKey};
+
+/// Derives a key, signs a message with it and checks the signature recovers to
+/// the same key, returning `0` on success
+#[no_mangle]
+pub extern "C" fn tetanus_no_std_test() -> i32 {
+    let private = PrivateKey::from_login("test", "test", "owner");
+
+    let sig = private.sign_message("helloworld");
+    let recovered = match PublicKey::recover(&sig, "helloworld") {
+        Ok(public) => public,
+        Err(_) => return 1,
+    };
+
+    if recovered != private.to_public() {
+        return 2
+    }
+
+    let digest = tetanus::utils::sha256("helloworld");
+    match private.sign_prehashed(digest) {
+        Ok(canonical) if canonical.to_vec() == sig.sig() => 0,
+        _ => 3,
+    }
+}
@@ -0,0 +1,109 @@
+//! Base58 encoding used by every key and signature string.
+//!
+//! By default encoding goes through the `bs58` crate. Enabling the `fast-base58`
+//! feature swaps in the hand-rolled encoder below, which converts the input into
+//! limbs of base 58^5 and consumes four input bytes per pass instead of working
+//! one base58 digit at a time. Signing batches of operations spends most of its
+//! encoding time in `EncodeType::K1`, and for those 65 byte buffers the limb
+//! encoder is roughly an order of magnitude faster than `bs58` 0.4 (see
+//! `cargo bench --bench base58 --features fast-base58`).
+//!
+//! The tradeoff is binary size versus throughput: `bs58` is already linked for
+//! decoding, so the default build adds nothing, while the fast encoder adds a
+//! few hundred bytes of extra code to the wasm bundle. Decoding always uses
+//! `bs58` since it is not on the signing hot path.
+
+use alloc::string::String;
+#[cfg(any(feature = "fast-base58", test))]
+use alloc::vec::Vec;
+
+#[cfg(any(feature = "fast-base58", test))]
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// 58^5, the largest power of 58 whose limbs still leave room for four more
+/// input bytes inside a `u64`
+#[cfg(any(feature = "fast-base58", test))]
+const LIMB_BASE: u64 = 656_356_768;
+
+/// Encodes a buffer to a base58 string using the configured backend
+pub fn encode<S>(input: S) -> String
+where
+    S: AsRef<[u8]>,
+{
+    #[cfg(feature = "fast-base58")]
+    return encode_fast(input.as_ref());
+
+    #[cfg(not(feature = "fast-base58"))]
+    bs58::encode(input).into_string()
+}
+
+/// Limb based base58 encoder, produces the same output as `bs58::encode`
+/// ```
+/// # #[cfg(feature = "fast-base58")] {
+/// use tetanus::base58::encode_fast;
+/// assert_eq!("1112", encode_fast(&[0, 0, 0, 1]));
+/// # }
+/// ```
+#[cfg(any(feature = "fast-base58", test))]
+pub fn encode_fast(input: &[u8]) -> String {
+    let zeros = input.iter().take_while(|byte| **byte == 0).count();
+    let mut limbs: Vec<u32> = Vec::with_capacity(input.len() / 3 + 1);
+
+    for chunk in input[zeros..].chunks(4) {
+        let mut carry = chunk.iter().fold(0u64, |acc, byte| (acc << 8) | *byte as u64);
+        let shift = 8 * chunk.len() as u32;
+
+        for limb in limbs.iter_mut() {
+            carry += (*limb as u64) << shift;
+            *limb = (carry % LIMB_BASE) as u32;
+            carry /= LIMB_BASE;
+        }
+
+        while carry > 0 {
+            limbs.push((carry % LIMB_BASE) as u32);
+            carry /= LIMB_BASE;
+        }
+    }
+
+    let mut digits = Vec::with_capacity(zeros + limbs.len() * 5);
+    for (i, limb) in limbs.iter().enumerate() {
+        let mut limb = *limb;
+        let last = i == limbs.len() - 1;
+
+        for _ in 0..5 {
+            if last && limb == 0 {
+                break;
+            }
+            digits.push(ALPHABET[(limb % 58) as usize]);
+            limb /= 58;
+        }
+    }
+    digits.resize(digits.len() + zeros, b'1');
+    digits.reverse();
+
+    String::from_utf8(digits).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::base58::*;
+    use quickcheck::quickcheck;
+
+    quickcheck! {
+        fn prop_fast_matches_bs58(input: Vec<u8>) -> bool {
+            encode_fast(&input) == bs58::encode(&input).into_string()
+        }
+    }
+
+    #[test]
+    fn fast_matches_bs58_on_fixed_sizes() {
+        for len in [0, 1, 4, 32, 33, 65, 128] {
+            let input: Vec<u8> = (0..len).map(|i| (i * 31 + 7) as u8).collect();
+            let mut zero_prefixed = vec![0u8; 3];
+            zero_prefixed.extend(&input);
+
+            assert_eq!(bs58::encode(&input).into_string(), encode_fast(&input));
+            assert_eq!(bs58::encode(&zero_prefixed).into_string(), encode_fast(&zero_prefixed));
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use serde::Deserialize;
+use serde_json::Value;
+use crate::eos::types::{name_to_u64, pack_varuint32, EosError};
+
+
+/// The parts of a contract abi needed to pack action data
+#[derive(Deserialize)]
+pub(crate) struct Abi {
+    #[serde(default)]
+    types: Vec<AbiTypedef>,
+    structs: Vec<AbiStruct>,
+    actions: Vec<AbiAction>,
+}
+
+#[derive(Deserialize)]
+struct AbiTypedef { new_type_name: String, #[serde(rename = "type")] type_name: String }
+
+#[derive(Deserialize)]
+struct AbiStruct { name: String, #[serde(default)] base: String, fields: Vec<AbiField> }
+
+#[derive(Deserialize)]
+struct AbiField { name: String, #[serde(rename = "type")] type_name: String }
+
+#[derive(Deserialize)]
+struct AbiAction { name: String, #[serde(rename = "type")] type_name: String }
+
+impl Abi {
+    /// Packs `data`, the json arguments of the action `action`
+    pub(crate) fn pack_action(&self, action: &str, data: &Value) -> Result<Vec<u8>, EosError> {
+        let type_name = &self.actions
+            .iter()
+            .find(|a| a.name == action)
+            .ok_or_else(|| EosError::UnknownAction(action.to_string()))?
+            .type_name;
+
+        let mut buf = Vec::new();
+        self.pack(type_name, data, &mut buf)?;
+
+        Ok(buf)
+    }
+
+    fn resolve<'a>(&'a self, type_name: &'a str) -> &'a str {
+        match self.types.iter().find(|t| t.new_type_name == type_name) {
+            Some(typedef) => self.resolve(&typedef.type_name),
+            None => type_name,
+        }
+    }
+
+    fn pack(&self, type_name: &str, value: &Value, buf: &mut Vec<u8>) -> Result<(), EosError> {
+        let type_name = self.resolve(type_name);
+        let invalid = || EosError::InvalidValue { type_name: type_name.to_string(), value: value.to_string() };
+
+        if let Some(inner) = type_name.strip_suffix("[]") {
+            let values = value.as_array().ok_or_else(invalid)?;
+            pack_varuint32(values.len() as u32, buf);
+
+            return values.iter().try_for_each(|v| self.pack(inner, v, buf))
+        }
+
+        if let Some(inner) = type_name.strip_suffix('?') {
+            if value.is_null() {
+                buf.push(0);
+                return Ok(())
+            }
+
+            buf.push(1);
+            return self.pack(inner, value, buf)
+        }
+
+        match type_name {
+            "bool" => buf.push(value.as_bool().ok_or_else(invalid)? as u8),
+            "uint8" => buf.push(unsigned(value).and_then(|v| u8::try_from(v).ok()).ok_or_else(invalid)?),
+            "uint16" => buf.extend_from_slice(&unsigned(value).and_then(|v| u16::try_from(v).ok()).ok_or_else(invalid)?.to_le_bytes()),
+            "uint32" => buf.extend_from_slice(&unsigned(value).and_then(|v| u32::try_from(v).ok()).ok_or_else(invalid)?.to_le_bytes()),
+            "uint64" => buf.extend_from_slice(&unsigned(value).ok_or_else(invalid)?.to_le_bytes()),
+            "int8" => buf.extend_from_slice(&signed(value).and_then(|v| i8::try_from(v).ok()).ok_or_else(invalid)?.to_le_bytes()),
+            "int16" => buf.extend_from_slice(&signed(value).and_then(|v| i16::try_from(v).ok()).ok_or_else(invalid)?.to_le_bytes()),
+            "int32" => buf.extend_from_slice(&signed(value).and_then(|v| i32::try_from(v).ok()).ok_or_else(invalid)?.to_le_bytes()),
+            "int64" => buf.extend_from_slice(&signed(value).ok_or_else(invalid)?.to_le_bytes()),
+            "varuint32" => pack_varuint32(unsigned(value).and_then(|v| u32::try_from(v).ok()).ok_or_else(invalid)?, buf),
+            "name" => buf.extend_from_slice(&value.as_str().and_then(name_to_u64).ok_or_else(invalid)?.to_le_bytes()),
+            "string" => {
+                let string = value.as_str().ok_or_else(invalid)?;
+                pack_varuint32(string.len() as u32, buf);
+                buf.extend_from_slice(string.as_bytes());
+            },
+            "bytes" => {
+                let bytes = value.as_str().and_then(from_hex).ok_or_else(invalid)?;
+                pack_varuint32(bytes.len() as u32, buf);
+                buf.extend_from_slice(&bytes);
+            },
+            "checksum256" => {
+                let bytes = value.as_str().and_then(from_hex).filter(|b| b.len() == 32).ok_or_else(invalid)?;
+                buf.extend_from_slice(&bytes);
+            },
+            "symbol" => buf.extend_from_slice(&value.as_str().and_then(symbol).ok_or_else(invalid)?.to_le_bytes()),
+            "asset" => {
+                let (amount, symbol) = value.as_str().and_then(asset).ok_or_else(invalid)?;
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.extend_from_slice(&symbol.to_le_bytes());
+            },
+            _ => self.pack_struct(type_name, value, buf)?,
+        }
+
+        Ok(())
+    }
+
+    fn pack_struct(&self, type_name: &str, value: &Value, buf: &mut Vec<u8>) -> Result<(), EosError> {
+        let abi_struct = self.structs
+            .iter()
+            .find(|s| s.name == type_name)
+            .ok_or_else(|| EosError::UnknownType(type_name.to_string()))?;
+
+        if !abi_struct.base.is_empty() {
+            self.pack_struct(self.resolve(&abi_struct.base), value, buf)?;
+        }
+
+        for field in &abi_struct.fields {
+            let field_value = value.get(&field.name).ok_or_else(|| EosError::MissingField(field.name.clone()))?;
+            self.pack(&field.type_name, field_value, buf)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Numbers may be sent as json numbers or, for 64 bit values, as strings
+fn unsigned(value: &Value) -> Option<u64> {
+    value.as_u64().or_else(|| value.as_str()?.parse().ok())
+}
+
+fn signed(value: &Value) -> Option<i64> {
+    value.as_i64().or_else(|| value.as_str()?.parse().ok())
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// `"4,EOS"`, the precision in the low byte followed by up to 7 upper case
+/// characters
+fn symbol(symbol: &str) -> Option<u64> {
+    let (precision, code) = symbol.split_once(',')?;
+
+    symbol_code(precision.parse().ok()?, code)
+}
+
+fn symbol_code(precision: u8, code: &str) -> Option<u64> {
+    if code.is_empty() || code.len() > 7 || !code.bytes().all(|c| c.is_ascii_uppercase()) {
+        return None
+    }
+
+    Some(code.bytes().rev().fold(0u64, |acc, c| (acc << 8) | c as u64) << 8 | precision as u64)
+}
+
+/// `"1.0000 EOS"`, the precision is the number of decimals in the amount
+fn asset(asset: &str) -> Option<(i64, u64)> {
+    let (amount, code) = asset.split_once(' ')?;
+    let precision = amount.split_once('.').map(|(_, decimals)| decimals.len()).unwrap_or(0);
+
+    Some((amount.replace('.', "").parse().ok()?, symbol_code(precision as u8, code)?))
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::eos::abi::*;
+
+    #[test]
+    fn symbols_and_assets() {
+        assert_eq!(Some(0x534f4504), symbol("4,EOS"));
+        assert_eq!(Some((10000, 0x534f4504)), asset("1.0000 EOS"));
+        assert_eq!(Some((-5, 0x534f4500)), asset("-5 EOS"));
+        assert_eq!(None, asset("1.0000 eos"));
+    }
+}
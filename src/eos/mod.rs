@@ -0,0 +1,3 @@
+mod abi;
+pub mod transaction;
+pub mod types;
\ No newline at end of file
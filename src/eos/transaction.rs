@@ -0,0 +1,155 @@
+use thiserror::Error;
+use crate::eos::types::{pack_varuint32, Action};
+use crate::keys::{private::PrivateKey, r1_private::R1PrivateKey};
+use crate::signatures::SignatureWrapper;
+use crate::types::signature::{SignatureError, SignatureType};
+use crate::utils::sha256;
+
+
+/// An EOSIO transaction, laid out in the order of its packed wire format
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct EosTransaction {
+    /// Seconds since the unix epoch
+    pub expiration: u32,
+    pub ref_block_num: u16,
+    pub ref_block_prefix: u32,
+    pub max_net_usage_words: u32,
+    pub max_cpu_usage_ms: u8,
+    pub delay_sec: u32,
+    pub context_free_actions: Vec<Action>,
+    pub actions: Vec<Action>,
+    pub transaction_extensions: Vec<(u16, Vec<u8>)>,
+}
+
+/// An error involving an EOS transaction.
+#[derive(Debug, Error)]
+pub enum TransactionError {
+    /// An account, action or permission name that cannot be name encoded
+    #[error("invalid eosio name: {0}")]
+    InvalidName(String),
+    #[error(transparent)]
+    Signature(#[from] SignatureError),
+}
+
+impl EosTransaction {
+    /// Packs the transaction into the EOSIO binary format, which is what gets
+    /// signed and what `push_transaction` expects as `packed_trx`
+    pub fn to_bytes(&self) -> Result<Vec<u8>, TransactionError> {
+        let mut buf = Vec::new();
+
+        buf.extend_from_slice(&self.expiration.to_le_bytes());
+        buf.extend_from_slice(&self.ref_block_num.to_le_bytes());
+        buf.extend_from_slice(&self.ref_block_prefix.to_le_bytes());
+        pack_varuint32(self.max_net_usage_words, &mut buf);
+        buf.push(self.max_cpu_usage_ms);
+        pack_varuint32(self.delay_sec, &mut buf);
+
+        for actions in [&self.context_free_actions, &self.actions] {
+            pack_varuint32(actions.len() as u32, &mut buf);
+            for action in actions {
+                action.pack(&mut buf)?;
+            }
+        }
+
+        pack_varuint32(self.transaction_extensions.len() as u32, &mut buf);
+        for (extension_type, data) in &self.transaction_extensions {
+            buf.extend_from_slice(&extension_type.to_le_bytes());
+            pack_varuint32(data.len() as u32, &mut buf);
+            buf.extend_from_slice(data);
+        }
+
+        Ok(buf)
+    }
+
+    /// The digest signed by every key, `sha256(chain_id || packed_trx ||
+    /// context_free_data_digest)`. Context free data is not supported so the
+    /// last part is always 32 zero bytes
+    pub fn digest(&self, chain_id: [u8; 32]) -> Result<[u8; 32], TransactionError> {
+        let signing_data = [&chain_id[..], &self.to_bytes()?, &[0; 32]].concat();
+
+        Ok(sha256(signing_data))
+    }
+
+    /// Signs the transaction for the chain `chain_id` with a K1 key
+    /// ```
+    /// use tetanus::eos::transaction::EosTransaction;
+    /// use tetanus::keys::{private::PrivateKey, public::PublicKey};
+    /// let private = PrivateKey::from_login("test", "test", "owner");
+    /// let tx = EosTransaction { expiration: 1_700_000_000, ..Default::default() };
+    ///
+    /// let sig = tx.sign_with_key(&private, [0; 32]).unwrap();
+    /// assert!(sig.to_string().starts_with("SIG_K1_"));
+    ///
+    /// let public = PublicKey::recover_prehashed(&sig, tx.digest([0; 32]).unwrap()).unwrap();
+    /// assert_eq!(private.to_public(), public)
+    /// ```
+    pub fn sign_with_key(&self, private: &PrivateKey, chain_id: [u8; 32]) -> Result<SignatureWrapper, TransactionError> {
+        let sig = private.sign_prehashed(self.digest(chain_id)?)?;
+
+        Ok(SignatureWrapper::new_typed(sig.to_vec(), SignatureType::K1))
+    }
+
+    /// Signs the transaction for the chain `chain_id` with an R1 key, the
+    /// signature is returned as `SIG_R1_`
+    pub fn sign_with_r1_key(&self, private: &R1PrivateKey, chain_id: [u8; 32]) -> Result<SignatureWrapper, TransactionError> {
+        let sig = private.sign_prehashed(self.digest(chain_id)?)?;
+
+        Ok(SignatureWrapper::new_typed(sig.to_vec(), SignatureType::R1))
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::eos::transaction::*;
+    use crate::eos::types::PermissionLevel;
+
+    fn transfer() -> EosTransaction {
+        EosTransaction {
+            expiration: 0x6553f100,
+            ref_block_num: 0x1234,
+            ref_block_prefix: 0xdeadbeef,
+            actions: vec![Action {
+                account: String::from("eosio.token"),
+                name: String::from("transfer"),
+                authorization: vec![PermissionLevel { actor: String::from("eosio"), permission: String::from("active") }],
+                data: vec![1, 2, 3],
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn packs_wire_format() {
+        let expected = [
+            vec![0x00, 0xf1, 0x53, 0x65, 0x34, 0x12, 0xef, 0xbe, 0xad, 0xde],
+            // net usage, cpu usage, delay, no context free actions, one action
+            vec![0x00, 0x00, 0x00, 0x00, 0x01],
+            0x5530ea033482a600u64.to_le_bytes().to_vec(),
+            0xcdcd3c2d57000000u64.to_le_bytes().to_vec(),
+            vec![0x01],
+            0x5530ea0000000000u64.to_le_bytes().to_vec(),
+            0x3232eda800000000u64.to_le_bytes().to_vec(),
+            vec![0x03, 1, 2, 3],
+            // no extensions
+            vec![0x00],
+        ].concat();
+
+        assert_eq!(expected, transfer().to_bytes().unwrap());
+    }
+
+    #[test]
+    fn invalid_names_are_rejected() {
+        let mut tx = transfer();
+        tx.actions[0].name = String::from("Transfer");
+
+        assert!(matches!(tx.to_bytes(), Err(TransactionError::InvalidName(name)) if name == "Transfer"));
+    }
+
+    #[test]
+    fn r1_signature_type() {
+        let sig = transfer().sign_with_r1_key(&R1PrivateKey::new(vec![1; 32]), [0; 32]).unwrap();
+
+        assert_eq!(SignatureType::R1, sig.sig_type());
+    }
+}
@@ -0,0 +1,216 @@
+use serde::Deserialize;
+use serde_json::Value;
+use thiserror::Error;
+use crate::eos::{abi::Abi, transaction::TransactionError};
+
+
+/// An `actor@permission` pair authorizing an action
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PermissionLevel { pub actor: String, pub permission: String }
+
+/// A contract call, `data` holds the already packed action arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Action {
+    pub account: String,
+    pub name: String,
+    pub authorization: Vec<PermissionLevel>,
+    pub data: Vec<u8>,
+}
+
+/// An error turning json into an EOS action.
+#[derive(Debug, Error)]
+pub enum EosError {
+    /// The abi or the action was not valid json, or not of the expected shape
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// The abi does not declare the action
+    #[error("unknown action: {0}")]
+    UnknownAction(String),
+    /// A field type that is neither built in nor declared by the abi
+    #[error("unknown abi type: {0}")]
+    UnknownType(String),
+    /// An argument the abi expects is missing from the action data
+    #[error("missing field: {0}")]
+    MissingField(String),
+    /// A value that does not fit its abi type
+    #[error("invalid {type_name}: {value}")]
+    InvalidValue { type_name: String, value: String },
+}
+
+#[derive(Deserialize)]
+struct ActionJson { account: String, name: String, authorization: Vec<PermissionLevel>, data: Value }
+
+impl PermissionLevel {
+    pub(crate) fn pack(&self, buf: &mut Vec<u8>) -> Result<(), TransactionError> {
+        pack_name(&self.actor, buf)?;
+        pack_name(&self.permission, buf)
+    }
+}
+
+impl Action {
+    /// Builds an action from its human readable json, packing `data` with the
+    /// struct the contract abi declares for the action. Typedefs, base structs,
+    /// arrays (`T[]`) and optionals (`T?`) are resolved, along with the integer,
+    /// `name`, `string`, `bytes`, `checksum256`, `symbol` and `asset` builtins
+    /// ```
+    /// use tetanus::eos::types::Action;
+    /// let abi = r#"{
+    ///     "structs": [{ "name": "transfer", "base": "", "fields": [
+    ///         { "name": "from", "type": "name" },
+    ///         { "name": "to", "type": "name" },
+    ///         { "name": "quantity", "type": "asset" },
+    ///         { "name": "memo", "type": "string" }
+    ///     ]}],
+    ///     "actions": [{ "name": "transfer", "type": "transfer" }]
+    /// }"#;
+    /// let action = r#"{
+    ///     "account": "eosio.token",
+    ///     "name": "transfer",
+    ///     "authorization": [{ "actor": "alice", "permission": "active" }],
+    ///     "data": { "from": "alice", "to": "bob", "quantity": "1.0000 EOS", "memo": "hi" }
+    /// }"#;
+    ///
+    /// let action = Action::from_json(abi, action).unwrap();
+    /// assert_eq!(8 + 8 + 16 + 3, action.data.len());
+    /// ```
+    pub fn from_json(abi_json: &str, action_json: &str) -> Result<Action, EosError> {
+        let abi: Abi = serde_json::from_str(abi_json)?;
+        let action: ActionJson = serde_json::from_str(action_json)?;
+        let data = abi.pack_action(&action.name, &action.data)?;
+
+        Ok(Action { account: action.account, name: action.name, authorization: action.authorization, data })
+    }
+
+    pub(crate) fn pack(&self, buf: &mut Vec<u8>) -> Result<(), TransactionError> {
+        pack_name(&self.account, buf)?;
+        pack_name(&self.name, buf)?;
+
+        pack_varuint32(self.authorization.len() as u32, buf);
+        for permission in &self.authorization {
+            permission.pack(buf)?;
+        }
+
+        pack_varuint32(self.data.len() as u32, buf);
+        buf.extend_from_slice(&self.data);
+
+        Ok(())
+    }
+}
+
+/// Encodes an EOSIO account, action or permission name into its `u64` form.
+/// Names are up to 12 characters of `.12345a-z`, with an optional 13th
+/// character limited to `.12345a-j`. Returns `None` for anything else
+/// ```
+/// use tetanus::eos::types::name_to_u64;
+/// assert_eq!(Some(0x5530ea0000000000), name_to_u64("eosio"));
+/// assert_eq!(None, name_to_u64("Eosio"));
+/// ```
+pub fn name_to_u64(name: &str) -> Option<u64> {
+    let bytes = name.as_bytes();
+    if bytes.len() > 13 {
+        return None
+    }
+
+    let mut value = 0u64;
+    for (i, c) in bytes.iter().enumerate() {
+        let symbol = match c {
+            b'.' => 0,
+            b'1'..=b'5' => (c - b'1' + 1) as u64,
+            b'a'..=b'z' => (c - b'a' + 6) as u64,
+            _ => return None,
+        };
+
+        if i < 12 {
+            value |= symbol << (64 - 5 * (i + 1));
+        } else if symbol > 0x0f {
+            return None
+        } else {
+            value |= symbol;
+        }
+    }
+
+    Some(value)
+}
+
+fn pack_name(name: &str, buf: &mut Vec<u8>) -> Result<(), TransactionError> {
+    let value = name_to_u64(name).ok_or_else(|| TransactionError::InvalidName(name.to_string()))?;
+    buf.extend_from_slice(&value.to_le_bytes());
+
+    Ok(())
+}
+
+/// LEB128, used by EOSIO for every length prefix
+pub(crate) fn pack_varuint32(mut value: u32, buf: &mut Vec<u8>) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+
+        if value == 0 {
+            buf.push(byte);
+            return
+        }
+
+        buf.push(byte | 0x80);
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::eos::types::*;
+
+    #[test]
+    fn known_names() {
+        assert_eq!(Some(0x5530ea033482a600), name_to_u64("eosio.token"));
+        assert_eq!(Some(0xcdcd3c2d57000000), name_to_u64("transfer"));
+        assert_eq!(Some(0x3232eda800000000), name_to_u64("active"));
+        assert_eq!(Some(0), name_to_u64(""));
+        assert_eq!(None, name_to_u64("abcdefghijklz"));
+        assert_eq!(None, name_to_u64("abcdefghijklmn"));
+    }
+
+    #[test]
+    fn from_json_packs_transfer() {
+        let abi = r#"{
+            "types": [{ "new_type_name": "account_name", "type": "name" }],
+            "structs": [{ "name": "transfer", "base": "", "fields": [
+                { "name": "from", "type": "account_name" },
+                { "name": "to", "type": "account_name" },
+                { "name": "quantity", "type": "asset" },
+                { "name": "memo", "type": "string" }
+            ]}],
+            "actions": [{ "name": "transfer", "type": "transfer" }]
+        }"#;
+        let action = r#"{
+            "account": "eosio.token",
+            "name": "transfer",
+            "authorization": [{ "actor": "eosio", "permission": "active" }],
+            "data": { "from": "eosio", "to": "eosio.token", "quantity": "1.0000 EOS", "memo": "hi" }
+        }"#;
+
+        let expected = [
+            0x5530ea0000000000u64.to_le_bytes().to_vec(),
+            0x5530ea033482a600u64.to_le_bytes().to_vec(),
+            10000i64.to_le_bytes().to_vec(),
+            0x534f4504u64.to_le_bytes().to_vec(),
+            vec![0x02, b'h', b'i'],
+        ].concat();
+
+        let action = Action::from_json(abi, action).unwrap();
+        assert_eq!(expected, action.data);
+        assert_eq!(vec![PermissionLevel { actor: String::from("eosio"), permission: String::from("active") }], action.authorization);
+
+        let missing = r#"{ "account": "eosio.token", "name": "transfer", "authorization": [], "data": { "from": "eosio" } }"#;
+        assert!(matches!(Action::from_json(abi, missing), Err(EosError::MissingField(field)) if field == "to"));
+    }
+
+    #[test]
+    fn varuint32() {
+        let mut buf = Vec::new();
+        pack_varuint32(0, &mut buf);
+        pack_varuint32(127, &mut buf);
+        pack_varuint32(300, &mut buf);
+
+        assert_eq!(vec![0x00, 0x7f, 0xac, 0x02], buf);
+    }
+}
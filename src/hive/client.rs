@@ -1,32 +1,967 @@
-use serde::Serialize;
-use serde_json::Value;
-
-
-pub struct HiveClient { pub url: String, client: reqwest::Client }
-
-#[derive(Serialize)]
-struct HiveRequest { pub jsonrpc: String, pub method: String, pub params: Value, pub id: i64 }
-
-
-impl HiveClient {
-    pub fn new(url: &str) -> Self {
-       Self { url: url.to_string(), client: reqwest::Client::new() }
-    }
-
-    pub async fn request(&self, method: &str, params: Value) -> Value {
-        let req = HiveRequest { jsonrpc: String::from("2.0"), method: method.to_string(), params, id: 1 };
-        let json = serde_json::to_string(&req).unwrap();
-
-        let response: &Value = &self.client
-            .post(&self.url)
-            .body(json)
-            .send()
-            .await
-            .unwrap()
-            .json::<Value>()
-            .await
-            .unwrap();
-        
-        response.to_owned()
-    }
-}
+use std::{collections::BTreeMap, fmt, sync::Arc, time::Duration};
+use reqwest::header::HeaderMap;
+use serde::{de::DeserializeOwned, Serialize, Deserialize};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use thiserror::Error;
+#[cfg(feature = "wasm-client")]
+use wasm_bindgen::prelude::*;
+use crate::hive::transactions::{Transaction, TransactionError};
+use crate::hive::types::{
+    AccountHistoryEntry, AccountReputation, AccountVote, Blog, BlogEntry, Block, BucketSizes, Content, DatabaseAccounts,
+    DynamicGlobalProperties, FeedHistory, FollowEntry, FollowType, Followers, Following, HiveAccount, MarketTicker, OpenOrder, OrderBook, Price,
+    Proposal, Proposals, RcAccount, RcAccounts, RcEstimate, RcResourceParams, RcResourcePool, RewardFund, StateResponse,
+    Trade, Trades, Vote, Witness, WitnessSchedule
+};
+
+
+const DEFAULT_URL: &str = "https://api.hive.blog";
+/// The most witnesses `condenser_api.get_witnesses_by_vote` returns at once
+const MAX_WITNESS_LIMIT: u32 = 1000;
+const MAX_BLOG_LIMIT: u32 = 500;
+const MAX_FOLLOW_LIMIT: u32 = 1000;
+const MAX_LOOKUP_LIMIT: u32 = 1000;
+const MAX_ORDER_BOOK_LIMIT: u32 = 500;
+const MAX_TRADE_HISTORY_LIMIT: u32 = 1000;
+
+#[derive(Clone)]
+pub struct HiveClient {
+    pub url: String,
+    transport: Arc<dyn HttpTransport>,
+    fallback_nodes: Vec<String>,
+    max_retries: u32,
+}
+
+/// An error from an `HttpTransport`, anything that kept the request from
+/// getting a response body
+#[derive(Debug, Error)]
+pub enum TransportError {
+    /// reqwest failed to send the request or to read the body
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    /// Any other transport failed
+    #[error("{0}")]
+    Other(String),
+}
+
+/// Sends the json-rpc bodies of a `HiveClient`, `reqwest::Client` unless the
+/// builder is given another one. Swapping it tests the client itself, retries
+/// and fallback nodes included, without any network (see `MockTransport`
+/// with the `test-utils` feature)
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait HttpTransport: Send + Sync {
+    /// Posts `body` to `url` and returns the response body
+    async fn post(&self, url: &str, body: String) -> Result<String, TransportError>;
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl HttpTransport for reqwest::Client {
+    async fn post(&self, url: &str, body: String) -> Result<String, TransportError> {
+        Ok(reqwest::Client::post(self, url).body(body).send().await?.text().await?)
+    }
+}
+
+/// Configures a `HiveClient`, see `HiveClientBuilder::default` for the
+/// defaults
+/// ```
+/// use std::time::Duration;
+/// use tetanus::hive::client::HiveClient;
+/// let client = HiveClient::builder()
+///     .url("https://api.deathwing.me")
+///     .timeout(Duration::from_secs(10))
+///     .max_retries(2)
+///     .fallback_nodes(vec![String::from("https://api.hive.blog")])
+///     .build()
+///     .unwrap();
+/// assert_eq!("https://api.deathwing.me", client.url)
+/// ```
+#[derive(Clone)]
+pub struct HiveClientBuilder {
+    url: String,
+    timeout: Duration,
+    max_retries: u32,
+    fallback_nodes: Vec<String>,
+    headers: HeaderMap,
+    transport: Option<Arc<dyn HttpTransport>>,
+}
+
+impl fmt::Debug for HiveClientBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HiveClientBuilder")
+            .field("url", &self.url)
+            .field("timeout", &self.timeout)
+            .field("max_retries", &self.max_retries)
+            .field("fallback_nodes", &self.fallback_nodes)
+            .field("headers", &self.headers)
+            .field("custom_transport", &self.transport.is_some())
+            .finish()
+    }
+}
+
+impl Default for HiveClientBuilder {
+    /// `https://api.hive.blog` with a 30 second timeout, 3 retries, no
+    /// fallback nodes, no extra headers and reqwest as the transport
+    fn default() -> Self {
+        HiveClientBuilder {
+            url: DEFAULT_URL.to_string(),
+            timeout: Duration::from_secs(30),
+            max_retries: 3,
+            fallback_nodes: Vec::new(),
+            headers: HeaderMap::new(),
+            transport: None,
+        }
+    }
+}
+
+impl HiveClientBuilder {
+    /// The node requests are sent to first
+    pub fn url(mut self, s: &str) -> Self {
+        self.url = s.to_string();
+        self
+    }
+
+    /// How long a single request may take. In wasm `fetch` has no timeout
+    /// support, so this is ignored there
+    pub fn timeout(mut self, d: Duration) -> Self {
+        self.timeout = d;
+        self
+    }
+
+    /// How many times a request that failed at the http level is retried, a
+    /// json-rpc error from the node is returned straight away
+    pub fn max_retries(mut self, n: u32) -> Self {
+        self.max_retries = n;
+        self
+    }
+
+    /// Nodes the retries rotate through after `url`
+    pub fn fallback_nodes(mut self, nodes: Vec<String>) -> Self {
+        self.fallback_nodes = nodes;
+        self
+    }
+
+    /// Headers sent with every request, e.g. an api key for a private node
+    pub fn headers(mut self, headers: HeaderMap) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Sends requests through `transport` instead of reqwest, the timeout and
+    /// headers are then up to the transport
+    pub fn transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    pub fn build(self) -> Result<HiveClient, HiveClientError> {
+        let transport = match self.transport {
+            Some(transport) => transport,
+            None => {
+                let builder = reqwest::Client::builder().default_headers(self.headers);
+                #[cfg(not(target_arch = "wasm32"))]
+                let builder = builder.timeout(self.timeout);
+
+                Arc::new(builder.build()?)
+            },
+        };
+
+        Ok(HiveClient {
+            url: self.url,
+            transport,
+            fallback_nodes: self.fallback_nodes,
+            max_retries: self.max_retries,
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct HiveRequest { pub jsonrpc: String, pub method: String, pub params: Value, pub id: i64 }
+
+/// The query object of the `condenser_api.get_discussions_by_*` methods, the
+/// start author and permlink form the cursor of the next page
+/// ```
+/// use tetanus::hive::client::DiscussionQuery;
+/// let query = DiscussionQuery::new("hive", 10).start("hiveio", "a-post");
+/// assert_eq!(
+///     r#"{"tag":"hive","limit":10,"start_author":"hiveio","start_permlink":"a-post"}"#,
+///     serde_json::to_string(&query).unwrap()
+/// )
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiscussionQuery {
+    tag: String,
+    limit: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_permlink: Option<String>,
+}
+
+impl DiscussionQuery {
+    /// A query for the first `limit` discussions under `tag`, or for the blog
+    /// of the account `tag` with `by_blog`
+    pub fn new(tag: &str, limit: u32) -> Self {
+        DiscussionQuery { tag: tag.to_string(), limit, start_author: None, start_permlink: None }
+    }
+
+    /// Starts the page at `@author/permlink`, usually the last post of the
+    /// previous page, which the node includes again as the first result
+    pub fn start(mut self, author: &str, permlink: &str) -> Self {
+        self.start_author = Some(author.to_string());
+        self.start_permlink = Some(permlink.to_string());
+        self
+    }
+
+    fn with_cursor(tag: &str, limit: u32, start_author: Option<&str>, start_permlink: Option<&str>) -> Self {
+        let query = DiscussionQuery::new(tag, limit);
+
+        match (start_author, start_permlink) {
+            (Some(author), Some(permlink)) => query.start(author, permlink),
+            _ => query,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct HiveResponse { result: Option<Value>, error: Option<RpcError> }
+
+#[derive(Deserialize)]
+struct RpcError { code: i64, message: String }
+
+/// An error involving a request to a hive node.
+#[derive(Debug, Error)]
+pub enum HiveClientError {
+    /// The http request itself failed or the body could not be read
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    /// A custom `HttpTransport` failed
+    #[error("transport error: {0}")]
+    Transport(String),
+    /// The node answered with a json-rpc error object
+    #[error("rpc error {code}: {message}")]
+    Rpc { code: i64, message: String },
+    /// The response did not have the expected shape
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// More results were asked for than the node returns at once, checked
+    /// before anything is sent
+    #[error("limit {limit} is larger than the maximum of {max}")]
+    LimitTooLarge { limit: u32, max: u32 },
+    /// A transaction passed in could not be packed
+    #[error(transparent)]
+    Transaction(#[from] TransactionError),
+}
+
+
+impl HiveClient {
+    /// A client for `url` with the `HiveClientBuilder` defaults
+    pub fn new(url: &str) -> Self {
+        HiveClient::builder().url(url).build().expect("failed to initialize the http client")
+    }
+
+    pub fn builder() -> HiveClientBuilder {
+        HiveClientBuilder::default()
+    }
+
+    /// Sends a json-rpc request to the node and returns the `result` member of
+    /// the response. Transport failures and bodies that are not json-rpc
+    /// responses are retried up to `max_retries` times, moving on to the next
+    /// fallback node on each attempt
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(method = %method, url = %self.url)))]
+    pub async fn request(&self, method: &str, params: Value) -> Result<Value, HiveClientError> {
+        let req = HiveRequest { jsonrpc: String::from("2.0"), method: method.to_string(), params, id: 1 };
+        let json = serde_json::to_string(&req)?;
+        let nodes: Vec<&String> = std::iter::once(&self.url).chain(&self.fallback_nodes).collect();
+
+        let mut attempt = 0;
+        loop {
+            let node = nodes[attempt as usize % nodes.len()];
+
+            let send = self.send(node, json.clone());
+            #[cfg(feature = "tracing")]
+            let send = tracing::Instrument::instrument(send, tracing::info_span!("send", url = %node, attempt));
+
+            match send.await {
+                Err(_error @ (HiveClientError::Http(_) | HiveClientError::Transport(_) | HiveClientError::Json(_)))
+                    if attempt < self.max_retries => {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(error = %_error, attempt, "retrying request");
+                    attempt += 1
+                },
+                result => {
+                    #[cfg(feature = "tracing")]
+                    if let Err(error) = &result {
+                        tracing::error!(%error, attempt, "request failed");
+                    }
+                    return result
+                },
+            }
+        }
+    }
+
+    async fn send(&self, url: &str, json: String) -> Result<Value, HiveClientError> {
+        let body = self.transport.post(url, json).await.map_err(|error| match error {
+            TransportError::Http(error) => HiveClientError::Http(error),
+            TransportError::Other(message) => HiveClientError::Transport(message),
+        })?;
+        let response: HiveResponse = serde_json::from_str(&body)?;
+
+        match response {
+            HiveResponse { error: Some(RpcError { code, message }), .. } => Err(HiveClientError::Rpc { code, message }),
+            HiveResponse { result, .. } => Ok(result.unwrap_or(Value::Null)),
+        }
+    }
+}
+
+/// The query api of `HiveClient` as a trait, so code using a client can be
+/// tested against `MockHiveClient` (with the `test-utils` feature) or any
+/// other implementation. Only `request` has to be implemented, the typed
+/// methods are built on it. Implementations are `Sync` so the typed methods
+/// can be awaited from multi threaded runtimes
+/// ```no_run
+/// use tetanus::hive::client::{HiveClient, HiveClientError, HiveClientTrait};
+///
+/// async fn head_block(client: &impl HiveClientTrait) -> Result<u32, HiveClientError> {
+///     Ok(client.get_dynamic_global_properties().await?.head_block_number)
+/// }
+///
+/// async fn run() -> Result<u32, HiveClientError> {
+///     head_block(&HiveClient::new("https://api.hive.blog")).await
+/// }
+/// ```
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait HiveClientTrait: Sync {
+    /// Sends a json-rpc request and returns the `result` member of the
+    /// response
+    async fn request(&self, method: &str, params: Value) -> Result<Value, HiveClientError>;
+
+    /// Returns the current head block and chain wide state
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_dynamic_global_properties(&self) -> Result<DynamicGlobalProperties, HiveClientError> {
+        parse(self.request("condenser_api.get_dynamic_global_properties", json!([])).await?)
+    }
+
+    /// Returns each of `names` that exists, in no particular order
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_accounts(&self, names: &[&str]) -> Result<Vec<HiveAccount>, HiveClientError> {
+        parse(self.request("condenser_api.get_accounts", json!([names])).await?)
+    }
+
+    /// Returns each of `names` that exists like `get_accounts`, through the
+    /// newer `database_api` rather than the condenser compatibility layer
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn database_find_accounts(&self, names: &[&str]) -> Result<Vec<HiveAccount>, HiveClientError> {
+        let response: DatabaseAccounts = parse(self.request("database_api.find_accounts", json!({ "accounts": names })).await?)?;
+
+        Ok(response.accounts)
+    }
+
+    /// Looks up each of `names`, in the same order, with `None` for the names
+    /// no account has. Unlike `get_accounts` this tells which names are free
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn lookup_account_names(&self, names: &[&str]) -> Result<Vec<Option<HiveAccount>>, HiveClientError> {
+        parse(self.request("condenser_api.lookup_account_names", json!([names])).await?)
+    }
+
+    /// Returns up to `limit` account names in alphabetical order, starting
+    /// with `lower_bound` or the first name after it. Handy for autocomplete,
+    /// the results only share a prefix with `lower_bound` until they run past
+    /// it. `limit` can be at most 1000
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn lookup_accounts(&self, lower_bound: &str, limit: u32) -> Result<Vec<String>, HiveClientError> {
+        check_limit(limit, MAX_LOOKUP_LIMIT)?;
+
+        parse(self.request("condenser_api.lookup_accounts", json!([lower_bound, limit])).await?)
+    }
+
+    /// Returns the reputation of each of `accounts`. `condenser_api` pages
+    /// reputations by name, so every account is its own request: with no
+    /// `limit` only the named accounts are returned and those that do not
+    /// exist are left out, with a `limit` up to that many accounts are
+    /// returned starting at each name
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_account_reputations(&self, accounts: &[&str], limit: Option<u32>) -> Result<Vec<AccountReputation>, HiveClientError> {
+        if let Some(limit) = limit {
+            check_limit(limit, MAX_LOOKUP_LIMIT)?;
+        }
+
+        let mut reputations = vec![];
+        for account in accounts {
+            let page: Vec<AccountReputation> =
+                parse(self.request("condenser_api.get_account_reputations", json!([account, limit.unwrap_or(1)])).await?)?;
+            match limit {
+                Some(_) => reputations.extend(page),
+                None => reputations.extend(page.into_iter().filter(|reputation| reputation.account == *account)),
+            }
+        }
+
+        Ok(reputations)
+    }
+
+    /// Returns the block at height `block_num`, or `None` if it has not been
+    /// produced yet
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_block(&self, block_num: u32) -> Result<Option<Block>, HiveClientError> {
+        parse(self.request("condenser_api.get_block", json!([block_num])).await?)
+    }
+
+    /// Returns everything a condenser page at `path` shows in one call, e.g.
+    /// `@alice` or `trending/hive`, see `StateResponse` for what is in it.
+    /// Meant for porting legacy app code, the typed queries are preferable
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_state(&self, path: &str) -> Result<StateResponse, HiveClientError> {
+        parse(self.request("condenser_api.get_state", json!([path])).await?)
+    }
+
+    /// Returns the post or comment at `@author/permlink`
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_content(&self, author: &str, permlink: &str) -> Result<Content, HiveClientError> {
+        parse(self.request("condenser_api.get_content", json!([author, permlink])).await?)
+    }
+
+    /// Returns the resource credit state of each of `accounts`, including
+    /// the delegations made since HF26. Accounts that do not exist are left
+    /// out
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn find_rc_accounts(&self, accounts: &[&str]) -> Result<Vec<RcAccount>, HiveClientError> {
+        let response: RcAccounts = parse(self.request("rc_api.find_rc_accounts", json!({ "accounts": accounts })).await?)?;
+
+        Ok(response.rc_accounts)
+    }
+
+    /// Returns the resource credit state of each of `accounts`
+    #[deprecated(note = "renamed to `find_rc_accounts` after the rc_api method")]
+    async fn get_accounts_rc(&self, accounts: &[&str]) -> Result<Vec<RcAccount>, HiveClientError> {
+        self.find_rc_accounts(accounts).await
+    }
+
+    /// Returns the price curve of each resource that costs resource credits,
+    /// along with the sizes the node charges per operation
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_resource_params(&self) -> Result<RcResourceParams, HiveClientError> {
+        parse(self.request("rc_api.get_resource_params", json!({})).await?)
+    }
+
+    /// Returns how much of each resource is left in its pool, keyed by
+    /// resource name
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_resource_pool(&self) -> Result<BTreeMap<String, i64>, HiveClientError> {
+        let response: RcResourcePool = parse(self.request("rc_api.get_resource_pool", json!({})).await?)?;
+
+        Ok(response.resource_pool.into_iter().map(|(resource, pool)| (resource, pool.pool)).collect())
+    }
+
+    /// Estimates the resource credits `tx` costs at the current prices, see
+    /// `Transaction::rc_cost`, and whether `account` has that many right now.
+    /// An account that does not exist has none
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, tx)))]
+    async fn estimate_rc_cost(&self, tx: &Transaction, account: &str) -> Result<RcEstimate, HiveClientError> {
+        let params = self.get_resource_params().await?;
+        let pools = self.get_resource_pool().await?;
+        let cost = tx.rc_cost(&params, &pools, &self.get_dynamic_global_properties().await?)?;
+        let available = self.find_rc_accounts(&[account]).await?.first().map_or(0, RcAccount::current_mana);
+
+        Ok(RcEstimate { cost, available, sufficient: cost <= available })
+    }
+
+    /// Returns the reward pool called `name`, on Hive the only pool is `"post"`
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_reward_fund(&self, name: &str) -> Result<RewardFund, HiveClientError> {
+        parse(self.request("condenser_api.get_reward_fund", json!([name])).await?)
+    }
+
+    /// Returns the current median HBD/HIVE price along with the recent feed
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_feed_history(&self) -> Result<FeedHistory, HiveClientError> {
+        parse(self.request("condenser_api.get_feed_history", json!([])).await?)
+    }
+
+    /// Asks the node which of `available_keys` are needed to sign `tx`, the
+    /// node resolves account auths so this also covers delegated authorities.
+    /// `tx` is sent as legacy json, which is all `condenser_api` reads
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, tx)))]
+    async fn get_required_keys(&self, tx: &Transaction, available_keys: &[&str]) -> Result<Vec<String>, HiveClientError> {
+        let json: Value = serde_json::from_str(&tx.to_legacy_json()?)?;
+        let keys: Vec<String> = parse(self.request("condenser_api.get_required_keys", json!([json, available_keys])).await?)?;
+
+        Ok(keys.into_iter().filter(|key| available_keys.contains(&key.as_str())).collect())
+    }
+
+    /// Broadcasts the signed `tx` without waiting for it to be included in a
+    /// block and returns its id. The node only checks the transaction against
+    /// its head state, so an accepted transaction can still expire unapplied
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    async fn broadcast_transaction(&self, tx: &Transaction) -> Result<String, HiveClientError> {
+        let json: Value = serde_json::from_str(&tx.to_legacy_json()?)?;
+        self.request("condenser_api.broadcast_transaction", json!([json])).await?;
+
+        Ok(tx.id()?)
+    }
+
+    /// Broadcasts each of `txs` with `broadcast_transaction`, returning the
+    /// results in the same order. By default they are all sent at once. With
+    /// `fail_fast` they are sent one after the other and nothing is sent after
+    /// the first error, so the result ends with that error. That also suits
+    /// nodes that rate limit
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(count = txs.len())))]
+    async fn broadcast_many(&self, txs: &[Transaction], fail_fast: bool) -> Vec<Result<String, HiveClientError>> {
+        if !fail_fast {
+            return futures::future::join_all(txs.iter().map(|tx| self.broadcast_transaction(tx))).await
+        }
+
+        let mut results = Vec::with_capacity(txs.len());
+        for tx in txs {
+            let result = self.broadcast_transaction(tx).await;
+            let failed = result.is_err();
+            results.push(result);
+            if failed {
+                break
+            }
+        }
+
+        results
+    }
+
+    /// Returns up to `limit` history entries of `account` ending at sequence
+    /// `start`, pass `-1` to start from the most recent. Nodes cap `limit` at
+    /// 1000, page backwards by passing the lowest sequence seen minus one.
+    /// `filter_types` restricts the result to those operation type ids
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_account_history(
+        &self,
+        account: &str,
+        start: i64,
+        limit: u32,
+        filter_types: Option<&[u32]>,
+    ) -> Result<Vec<AccountHistoryEntry>, HiveClientError> {
+        let params = match filter_types {
+            Some(types) => {
+                let (low, high) = operation_filter(types);
+                json!([account, start, limit, low, high])
+            },
+            None => json!([account, start, limit]),
+        };
+
+        parse(self.request("condenser_api.get_account_history", params).await?)
+    }
+
+    /// Returns every vote `account` has cast on posts that have not paid out
+    /// yet
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_account_votes(&self, account: &str) -> Result<Vec<AccountVote>, HiveClientError> {
+        parse(self.request("condenser_api.get_account_votes", json!([account])).await?)
+    }
+
+    /// Returns the votes on `author`'s post or comment `permlink`, including
+    /// removed ones, in the order they were cast
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_active_votes(&self, author: &str, permlink: &str) -> Result<Vec<Vote>, HiveClientError> {
+        parse(self.request("condenser_api.get_active_votes", json!([author, permlink])).await?)
+    }
+
+    /// Returns up to `limit` top level posts by `account`, newest first. Pass
+    /// the permlink of the last post seen to get the next page, which then
+    /// starts with that post again. Nodes cap `limit` at 100
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_account_posts(&self, account: &str, limit: u32, start_permlink: Option<&str>) -> Result<Vec<Content>, HiveClientError> {
+        // the date is ignored by hived, the permlink alone decides where the page starts
+        let params = json!([account, start_permlink.unwrap_or(""), "1970-01-01T00:00:00", limit]);
+
+        parse(self.request("condenser_api.get_discussions_by_author_before_date", params).await?)
+    }
+
+    /// Returns up to `limit` entries of `account`'s blog, its own posts and its
+    /// reblogs, newest first starting from `start_entry_id`. Pass `0` to start
+    /// from the newest entry. `limit` can be at most 500
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_blog(&self, account: &str, start_entry_id: u32, limit: u16) -> Result<Vec<BlogEntry>, HiveClientError> {
+        check_limit(limit.into(), MAX_BLOG_LIMIT)?;
+
+        let params = json!({ "account": account, "start_entry_id": start_entry_id, "limit": limit });
+        let response: Blog = parse(self.request("follow_api.get_blog", params).await?)?;
+
+        Ok(response.blog)
+    }
+
+    /// Returns up to `limit` accounts following `account` with `follow_type`,
+    /// in alphabetical order starting with `start_follower`. Pass an empty
+    /// start for the first page and the last follower seen for the next one.
+    /// `limit` can be at most 1000
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_followers(
+        &self,
+        account: &str,
+        start_follower: &str,
+        follow_type: FollowType,
+        limit: u16,
+    ) -> Result<Vec<FollowEntry>, HiveClientError> {
+        check_limit(limit.into(), MAX_FOLLOW_LIMIT)?;
+
+        let params = json!({ "account": account, "start": start_follower, "type": follow_type, "limit": limit });
+        let response: Followers = parse(self.request("follow_api.get_followers", params).await?)?;
+
+        Ok(response.followers)
+    }
+
+    /// Returns up to `limit` accounts `account` follows with `follow_type`,
+    /// paged like `get_followers`
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_following(
+        &self,
+        account: &str,
+        start_following: &str,
+        follow_type: FollowType,
+        limit: u16,
+    ) -> Result<Vec<FollowEntry>, HiveClientError> {
+        check_limit(limit.into(), MAX_FOLLOW_LIMIT)?;
+
+        let params = json!({ "account": account, "start": start_following, "type": follow_type, "limit": limit });
+        let response: Following = parse(self.request("follow_api.get_following", params).await?)?;
+
+        Ok(response.following)
+    }
+
+    /// Returns the discussions matched by `query` under the `sort` order, e.g.
+    /// `"trending"` calls `condenser_api.get_discussions_by_trending`
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_discussions(&self, sort: &str, query: &DiscussionQuery) -> Result<Vec<Content>, HiveClientError> {
+        parse(self.request(&format!("condenser_api.get_discussions_by_{}", sort), json!([query])).await?)
+    }
+
+    /// Returns the trending posts under `tag`, pass the author and permlink of
+    /// the last post seen to get the next page
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_discussions_by_trending(
+        &self,
+        tag: &str,
+        limit: u32,
+        start_author: Option<&str>,
+        start_permlink: Option<&str>,
+    ) -> Result<Vec<Content>, HiveClientError> {
+        self.get_discussions("trending", &DiscussionQuery::with_cursor(tag, limit, start_author, start_permlink)).await
+    }
+
+    /// Returns the newest posts under `tag`, see `get_discussions_by_trending`
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_discussions_by_created(
+        &self,
+        tag: &str,
+        limit: u32,
+        start_author: Option<&str>,
+        start_permlink: Option<&str>,
+    ) -> Result<Vec<Content>, HiveClientError> {
+        self.get_discussions("created", &DiscussionQuery::with_cursor(tag, limit, start_author, start_permlink)).await
+    }
+
+    /// Returns the hot posts under `tag`, see `get_discussions_by_trending`
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_discussions_by_hot(
+        &self,
+        tag: &str,
+        limit: u32,
+        start_author: Option<&str>,
+        start_permlink: Option<&str>,
+    ) -> Result<Vec<Content>, HiveClientError> {
+        self.get_discussions("hot", &DiscussionQuery::with_cursor(tag, limit, start_author, start_permlink)).await
+    }
+
+    /// Returns the promoted posts under `tag`, see `get_discussions_by_trending`
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_discussions_by_promoted(
+        &self,
+        tag: &str,
+        limit: u32,
+        start_author: Option<&str>,
+        start_permlink: Option<&str>,
+    ) -> Result<Vec<Content>, HiveClientError> {
+        self.get_discussions("promoted", &DiscussionQuery::with_cursor(tag, limit, start_author, start_permlink)).await
+    }
+
+    /// Returns the posts and reblogs on the blog of the account `tag`, see
+    /// `get_discussions_by_trending`
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_discussions_by_blog(
+        &self,
+        tag: &str,
+        limit: u32,
+        start_author: Option<&str>,
+        start_permlink: Option<&str>,
+    ) -> Result<Vec<Content>, HiveClientError> {
+        self.get_discussions("blog", &DiscussionQuery::with_cursor(tag, limit, start_author, start_permlink)).await
+    }
+
+    /// Returns the names of the witnesses producing the current round, the top
+    /// 20 by votes plus one backup witness
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_active_witnesses(&self) -> Result<Vec<String>, HiveClientError> {
+        parse(self.request("condenser_api.get_active_witnesses", json!([])).await?)
+    }
+
+    /// Returns up to `limit` witnesses ordered by votes, starting with
+    /// `start_name` or from the top if it is empty. `limit` can be at most 1000
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_witnesses_by_vote(&self, start_name: &str, limit: u32) -> Result<Vec<Witness>, HiveClientError> {
+        check_limit(limit, MAX_WITNESS_LIMIT)?;
+
+        parse(self.request("condenser_api.get_witnesses_by_vote", json!([start_name, limit])).await?)
+    }
+
+    /// Returns the top `limit` witnesses by votes, paging through
+    /// `get_witnesses_by_vote` with the last owner of each page as the cursor
+    /// of the next
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn list_witnesses(&self, limit: u32) -> Result<Vec<Witness>, HiveClientError> {
+        let mut witnesses: Vec<Witness> = vec![];
+
+        while (witnesses.len() as u32) < limit {
+            let cursor = witnesses.last().map(|witness| witness.owner.clone());
+            // pages after the first start with the cursor again, so ask for one more
+            let page_size = (limit - witnesses.len() as u32 + cursor.is_some() as u32).min(MAX_WITNESS_LIMIT);
+            let page = self.get_witnesses_by_vote(cursor.as_deref().unwrap_or(""), page_size).await?;
+            let last_page = (page.len() as u32) < page_size;
+
+            let before = witnesses.len();
+            witnesses.extend(page.into_iter().filter(|witness| Some(&witness.owner) != cursor.as_ref()));
+            if last_page || witnesses.len() == before {
+                break
+            }
+        }
+
+        witnesses.truncate(limit as usize);
+        Ok(witnesses)
+    }
+
+    /// Returns the current witness schedule
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_witness_schedule(&self) -> Result<WitnessSchedule, HiveClientError> {
+        parse(self.request("condenser_api.get_witness_schedule", json!([])).await?)
+    }
+
+    /// Returns the Decentralized Hive Fund proposals with the given ids, ids
+    /// that do not exist are left out
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn find_proposals(&self, proposal_ids: &[u64]) -> Result<Vec<Proposal>, HiveClientError> {
+        let response: Proposals = parse(self.request("database_api.find_proposals", json!({ "proposal_ids": proposal_ids })).await?)?;
+
+        Ok(response.proposals)
+    }
+
+    /// Returns the orders `account` has open on the internal market
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_open_orders(&self, account: &str) -> Result<Vec<OpenOrder>, HiveClientError> {
+        parse(self.request("condenser_api.get_open_orders", json!([account])).await?)
+    }
+
+    /// Returns the median HBD/HIVE price used for conversions and payouts
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_current_median_price(&self) -> Result<Price, HiveClientError> {
+        parse(self.request("condenser_api.get_current_median_history_price", json!([])).await?)
+    }
+
+    /// Returns the internal market's prices and volume of the last 24 hours
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_ticker(&self) -> Result<MarketTicker, HiveClientError> {
+        parse(self.request("market_history_api.get_ticker", json!({})).await?)
+    }
+
+    /// Returns up to `limit` of the best bids and asks on the internal market,
+    /// at most 500
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_order_book(&self, limit: u32) -> Result<OrderBook, HiveClientError> {
+        check_limit(limit, MAX_ORDER_BOOK_LIMIT)?;
+
+        parse(self.request("condenser_api.get_order_book", json!([limit])).await?)
+    }
+
+    /// Returns up to `limit` trades of the internal market filled from
+    /// `start` to `end`, both times like `"2022-07-01T12:00:00"`, oldest first
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_trade_history(&self, start: &str, end: &str, limit: u32) -> Result<Vec<Trade>, HiveClientError> {
+        check_limit(limit, MAX_TRADE_HISTORY_LIMIT)?;
+
+        let params = json!({ "start": start, "end": end, "limit": limit });
+        let response: Trades = parse(self.request("market_history_api.get_trade_history", params).await?)?;
+
+        Ok(response.trades)
+    }
+
+    /// Returns the bucket sizes in seconds the node keeps market history in
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    async fn get_market_history_buckets(&self) -> Result<Vec<u32>, HiveClientError> {
+        let response: BucketSizes = parse(self.request("market_history_api.get_market_history_buckets", json!({})).await?)?;
+
+        Ok(response.bucket_sizes)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl HiveClientTrait for HiveClient {
+    async fn request(&self, method: &str, params: Value) -> Result<Value, HiveClientError> {
+        HiveClient::request(self, method, params).await
+    }
+}
+
+/// Deserializes a `result` member into `T`
+fn parse<T: DeserializeOwned>(result: Value) -> Result<T, HiveClientError> {
+    Ok(serde_json::from_value(result)?)
+}
+
+/// Rejects a `limit` above what the method allows, nodes answer those with an
+/// assert that does not say what the maximum is
+fn check_limit(limit: u32, max: u32) -> Result<(), HiveClientError> {
+    if limit > max {
+        return Err(HiveClientError::LimitTooLarge { limit, max })
+    }
+
+    Ok(())
+}
+
+/// Splits operation type ids into the two 64 bit masks `get_account_history`
+/// takes, ids 0 to 63 go in the low mask and 64 to 127 in the high one
+fn operation_filter(types: &[u32]) -> (u64, u64) {
+    types.iter().fold((0, 0), |(low, high), id| match id {
+        0..=63 => (low | 1 << id, high),
+        64..=127 => (low, high | 1 << (id - 64)),
+        _ => (low, high),
+    })
+}
+
+/// Javascript facing wrapper around `HiveClient`, every request returns a
+/// promise that resolves with the `result` member of the response or rejects
+/// with the error message.
+///
+/// In wasm `reqwest` uses the browser (or node) `fetch` api, so no tokio
+/// runtime is involved and requests are driven by the javascript event loop.
+#[cfg(feature = "wasm-client")]
+#[wasm_bindgen]
+pub struct WasmHiveClient { inner: HiveClient }
+
+#[cfg(feature = "wasm-client")]
+#[wasm_bindgen]
+impl WasmHiveClient {
+    #[wasm_bindgen(constructor)]
+    pub fn new(url: &str) -> WasmHiveClient {
+        WasmHiveClient { inner: HiveClient::new(url) }
+    }
+
+    /// Sends a json-rpc request, `params` is any json serializable value
+    pub fn request(&self, method: String, params: JsValue) -> js_sys::Promise {
+        let client = self.inner.clone();
+
+        wasm_bindgen_futures::future_to_promise(async move {
+            let params: Value = serde_wasm_bindgen::from_value(params)?;
+            let result = client
+                .request(&method, params)
+                .await
+                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+            // plain objects rather than `Map`s, which is what javascript callers expect from json
+            let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+            Ok(result.serialize(&serializer)?)
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::client::*;
+
+    #[test]
+    fn builder_defaults() {
+        let builder = HiveClientBuilder::default();
+        assert_eq!(Duration::from_secs(30), builder.timeout);
+        assert_eq!(3, builder.max_retries);
+
+        let client = HiveClient::new("https://api.deathwing.me");
+        assert_eq!("https://api.deathwing.me", client.url);
+        assert_eq!(3, client.max_retries);
+        assert!(client.fallback_nodes.is_empty());
+    }
+
+    #[test]
+    fn cursor_needs_author_and_permlink() {
+        assert_eq!(DiscussionQuery::new("hive", 5), DiscussionQuery::with_cursor("hive", 5, Some("hiveio"), None));
+        assert_eq!(
+            DiscussionQuery::new("hive", 5).start("hiveio", "a-post"),
+            DiscussionQuery::with_cursor("hive", 5, Some("hiveio"), Some("a-post"))
+        );
+    }
+
+    #[test]
+    fn operation_filter_masks() {
+        assert_eq!((0, 0), operation_filter(&[]));
+        assert_eq!((0b101, 0), operation_filter(&[0, 2]));
+        assert_eq!((1 << 63, 1), operation_filter(&[63, 64]));
+        assert_eq!((0, 1 << 63), operation_filter(&[127, 128]));
+    }
+
+    #[cfg(feature = "tracing")]
+    mod tracing_spans {
+        use crate::hive::client::*;
+        use std::sync::{Arc, Mutex};
+        use tracing::{field::{Field, Visit}, span::{Attributes, Id}, Event, Subscriber};
+        use tracing_subscriber::{layer::{Context, SubscriberExt}, registry::LookupSpan, Layer};
+
+        /// Records the crate's spans as `parent > name [fields]` and its events
+        /// as their level
+        struct Recorder(Arc<Mutex<Vec<String>>>);
+
+        struct FieldNames(Vec<&'static str>);
+
+        impl Visit for FieldNames {
+            fn record_debug(&mut self, field: &Field, _: &dyn std::fmt::Debug) {
+                self.0.push(field.name());
+            }
+        }
+
+        impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Recorder {
+            fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+                if !attrs.metadata().target().starts_with("tetanus") {
+                    return
+                }
+
+                let parent = ctx.span(id).and_then(|span| span.parent()).map(|parent| parent.name());
+                let mut fields = FieldNames(Vec::new());
+                attrs.record(&mut fields);
+
+                self.0.lock().unwrap().push(format!("{} > {} {:?}", parent.unwrap_or("root"), attrs.metadata().name(), fields.0));
+            }
+
+            fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+                if event.metadata().target().starts_with("tetanus") {
+                    self.0.lock().unwrap().push(event.metadata().level().to_string());
+                }
+            }
+        }
+
+        #[test]
+        fn request_span_tree() {
+            let recorded = Arc::new(Mutex::new(Vec::new()));
+            let subscriber = tracing_subscriber::registry().with(Recorder(recorded.clone()));
+            // nothing listens on port 1, so both attempts fail straight away
+            let client = HiveClient::builder().url("http://127.0.0.1:1").max_retries(1).build().unwrap();
+            let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+
+            tracing::subscriber::with_default(subscriber, || {
+                assert!(runtime.block_on(client.get_dynamic_global_properties()).is_err());
+            });
+
+            assert_eq!(
+                vec![
+                    "root > get_dynamic_global_properties []",
+                    "get_dynamic_global_properties > request [\"method\", \"url\"]",
+                    "request > send [\"url\", \"attempt\"]",
+                    "DEBUG",
+                    "request > send [\"url\", \"attempt\"]",
+                    "ERROR",
+                ],
+                *recorded.lock().unwrap()
+            );
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Builders for Hive Engine token actions. Hive Engine is a sidechain that
+//! reads `custom_json` operations with the id `ssc-mainnet-hive`, each naming a
+//! contract, an action and its payload. Moving tokens needs the active
+//! authority, so these are signed with it.
+//!
+//! Quantities are decimal strings as Hive Engine takes them, e.g. `"1.500"`.
+//! Only their form is checked here, the sidechain rejects more decimals than
+//! the token has
+use serde_json::{json, Value};
+use crate::hive::operations::CustomJsonOperation;
+use crate::hive::transactions::TransactionError;
+
+/// The `custom_json` id the Hive Engine mainnet reads
+pub const HIVE_ENGINE_ID: &str = "ssc-mainnet-hive";
+
+/// Transfers `quantity` of the token `symbol` from `from` to `to`
+/// ```
+/// use tetanus::hive::hive_engine::he_transfer;
+/// let op = he_transfer("alice", "bob", "1.500", "BEE", "thanks").unwrap();
+/// assert_eq!(("ssc-mainnet-hive", vec!["alice".to_string()]), (op.id.as_str(), op.required_auths));
+/// assert!(he_transfer("alice", "bob", "-1", "BEE", "").is_err())
+/// ```
+pub fn he_transfer(from: &str, to: &str, quantity: &str, symbol: &str, memo: &str) -> Result<CustomJsonOperation, TransactionError> {
+    let payload = json!({ "symbol": symbol, "to": to, "quantity": quantity, "memo": memo });
+
+    tokens(from, "transfer", quantity, payload)
+}
+
+/// Stakes `quantity` of the token `symbol` to `account` itself
+pub fn he_stake(account: &str, quantity: &str, symbol: &str) -> Result<CustomJsonOperation, TransactionError> {
+    tokens(account, "stake", quantity, json!({ "to": account, "symbol": symbol, "quantity": quantity }))
+}
+
+/// Starts unstaking `quantity` of the token `symbol`, paid out over the
+/// token's unstaking period
+pub fn he_unstake(account: &str, quantity: &str, symbol: &str) -> Result<CustomJsonOperation, TransactionError> {
+    tokens(account, "unstake", quantity, json!({ "symbol": symbol, "quantity": quantity }))
+}
+
+fn tokens(account: &str, action: &str, quantity: &str, payload: Value) -> Result<CustomJsonOperation, TransactionError> {
+    validate_quantity(quantity)?;
+    let json = json!({ "contractName": "tokens", "contractAction": action, "contractPayload": payload });
+
+    Ok(CustomJsonOperation::active(account, HIVE_ENGINE_ID, &json))
+}
+
+/// Checks that `quantity` is a positive decimal such as `"10"` or `"0.001"`,
+/// without a sign or exponent
+fn validate_quantity(quantity: &str) -> Result<(), TransactionError> {
+    let (whole, fraction) = quantity.split_once('.').unwrap_or((quantity, ""));
+    let digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+    let decimal = !whole.is_empty() && digits(whole) && digits(fraction) && !quantity.ends_with('.');
+
+    if decimal && quantity.bytes().any(|byte| (b'1'..=b'9').contains(&byte)) {
+        return Ok(())
+    }
+
+    Err(TransactionError::InvalidValue { field: "quantity".to_string(), reason: format!("must be a positive decimal, got {:?}", quantity) })
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::hive_engine::*;
+    use crate::hive::operations::TypedOperation;
+
+    fn payload(op: &CustomJsonOperation) -> Value {
+        serde_json::from_str(&op.json).unwrap()
+    }
+
+    #[test]
+    fn builds_token_operations() {
+        let transfer = he_transfer("alice", "bob", "1.500", "BEE", "thanks").unwrap();
+        assert_eq!(json!({
+            "contractName": "tokens",
+            "contractAction": "transfer",
+            "contractPayload": { "symbol": "BEE", "to": "bob", "quantity": "1.500", "memo": "thanks" },
+        }), payload(&transfer));
+
+        let stake = he_stake("alice", "100", "LEO").unwrap();
+        assert_eq!(json!({
+            "contractName": "tokens",
+            "contractAction": "stake",
+            "contractPayload": { "to": "alice", "symbol": "LEO", "quantity": "100" },
+        }), payload(&stake));
+
+        let unstake = he_unstake("alice", "0.001", "LEO").unwrap();
+        assert_eq!(json!({ "symbol": "LEO", "quantity": "0.001" }), payload(&unstake)["contractPayload"]);
+        assert_eq!("unstake", payload(&unstake)["contractAction"]);
+
+        for op in [transfer, stake, unstake] {
+            assert_eq!((HIVE_ENGINE_ID, vec!["alice".to_string()]), (op.id.as_str(), op.required_auths.clone()));
+            assert!(op.required_posting_auths.is_empty());
+            assert!(op.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn quantities_are_checked() {
+        for valid in ["1", "10", "0.001", "1.500", "00.5"] {
+            assert!(validate_quantity(valid).is_ok(), "{}", valid);
+        }
+        for invalid in ["", "0", "0.000", "-1", "+1", "1e3", ".5", "1.", "1.2.3", "1,5", " 1", "NaN"] {
+            assert!(matches!(validate_quantity(invalid), Err(TransactionError::InvalidValue { field, .. }) if field == "quantity"), "{}", invalid);
+        }
+        assert!(he_stake("alice", "0", "LEO").is_err());
+    }
+}
@@ -0,0 +1,82 @@
+//! Builders for the `custom_json` operations hivemind, Hive's social layer,
+//! reads follows, reblogs, mutes and community subscriptions from. All of them
+//! are signed with the posting authority of the acting account.
+use serde_json::{json, Value};
+use crate::hive::operations::CustomJsonOperation;
+
+/// The `custom_json` id of follows, mutes and reblogs
+pub const FOLLOW_ID: &str = "follow";
+/// The `custom_json` id of community operations
+pub const COMMUNITY_ID: &str = "community";
+
+fn follow_op(follower: &str, following: &str, what: &[&str], notes: &str) -> CustomJsonOperation {
+    let mut follow = json!({ "follower": follower, "following": following, "what": what });
+    if !notes.is_empty() {
+        follow["notes"] = Value::from(notes);
+    }
+
+    CustomJsonOperation::posting(follower, FOLLOW_ID, &json!(["follow", follow]))
+}
+
+/// Follows `following`'s blog, so their posts show up in `follower`'s feed
+/// ```
+/// use tetanus::hive::hivemind::hivemind_follow;
+/// let op = hivemind_follow("alice", "bob");
+/// assert_eq!("follow", op.id);
+/// assert_eq!(r#"["follow",{"follower":"alice","following":"bob","what":["blog"]}]"#, op.json)
+/// ```
+pub fn hivemind_follow(follower: &str, following: &str) -> CustomJsonOperation {
+    follow_op(follower, following, &["blog"], "")
+}
+
+/// Reblogs `@author/permlink` onto `account`'s blog
+pub fn hivemind_reblog(account: &str, author: &str, permlink: &str) -> CustomJsonOperation {
+    let reblog = json!(["reblog", { "account": account, "author": author, "permlink": permlink }]);
+
+    CustomJsonOperation::posting(account, FOLLOW_ID, &reblog)
+}
+
+/// Subscribes `account` to `community`, e.g. `hive-174578`
+pub fn hivemind_subscribe(account: &str, community: &str) -> CustomJsonOperation {
+    CustomJsonOperation::posting(account, COMMUNITY_ID, &json!(["subscribe", { "community": community }]))
+}
+
+/// Mutes `other` for `account`, which hides their posts and replies in apps
+/// that honor it. `notes` is not part of hivemind's follow format, it is only
+/// added to the json when not empty, for apps that want to show why
+pub fn hivemind_mute(account: &str, other: &str, notes: &str) -> CustomJsonOperation {
+    follow_op(account, other, &["ignore"], notes)
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::hivemind::*;
+    use crate::hive::operations::TypedOperation;
+
+    fn payload(op: &CustomJsonOperation) -> Value {
+        serde_json::from_str(&op.json).unwrap()
+    }
+
+    #[test]
+    fn builds_hivemind_operations() {
+        let reblog = hivemind_reblog("alice", "bob", "a-post");
+        assert_eq!(("follow", vec!["alice".to_string()]), (reblog.id.as_str(), reblog.required_posting_auths.clone()));
+        assert_eq!(json!(["reblog", { "account": "alice", "author": "bob", "permlink": "a-post" }]), payload(&reblog));
+
+        let subscribe = hivemind_subscribe("alice", "hive-174578");
+        assert_eq!("community", subscribe.id);
+        assert_eq!(json!(["subscribe", { "community": "hive-174578" }]), payload(&subscribe));
+
+        assert_eq!(json!(["follow", { "follower": "alice", "following": "spam", "what": ["ignore"] }]), payload(&hivemind_mute("alice", "spam", "")));
+        assert_eq!(
+            json!(["follow", { "follower": "alice", "following": "spam", "what": ["ignore"], "notes": "bot" }]),
+            payload(&hivemind_mute("alice", "spam", "bot"))
+        );
+
+        for op in [hivemind_follow("alice", "bob"), reblog, subscribe, hivemind_mute("alice", "spam", "bot")] {
+            assert!(op.validate().is_ok());
+            assert!(op.required_auths.is_empty());
+        }
+    }
+}
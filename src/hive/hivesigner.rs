@@ -0,0 +1,115 @@
+use serde_json::Value;
+use crate::hive::transactions::{legacy_assets, Operation};
+
+
+const HIVESIGNER_URL: &str = "https://hivesigner.com";
+
+/// Returns a hivesigner link that asks the user to sign and broadcast
+/// `operation`, after which hivesigner sends them to `redirect_uri`. With
+/// `no_broadcast` hivesigner only signs and hands the transaction back.
+/// Assets are always passed as legacy `1.000 HIVE` strings
+/// ```
+/// use serde_json::json;
+/// use tetanus::hive::{hivesigner::hivesigner_sign_url, transactions::Operation};
+/// let data = json!({ "to": "fabien", "amount": "1.000 HIVE" });
+/// let op = Operation::new("transfer", data.as_object().unwrap().clone());
+/// assert_eq!(
+///     "https://hivesigner.com/sign/transfer?to=fabien&amount=1.000%20HIVE&redirect_uri=https%3A%2F%2Fexample.com",
+///     hivesigner_sign_url(&op, "https://example.com", false)
+/// )
+/// ```
+pub fn hivesigner_sign_url(operation: &Operation, redirect_uri: &str, no_broadcast: bool) -> String {
+    let mut data = Value::Object(operation.data());
+    // an asset that fails to convert is passed as its json like other objects
+    let _ = legacy_assets(&mut data);
+
+    let mut params: Vec<String> = data.as_object().into_iter().flatten()
+        .map(|(key, value)| {
+            // strings are passed as is, anything else (numbers, authorities,
+            // json_metadata objects) as its json
+            let value = match value {
+                Value::String(string) => string.clone(),
+                other => other.to_string(),
+            };
+
+            format!("{}={}", encode_uri_component(key), encode_uri_component(&value))
+        })
+        .collect();
+
+    if no_broadcast {
+        params.push(String::from("no_broadcast=1"));
+    }
+    params.push(format!("redirect_uri={}", encode_uri_component(redirect_uri)));
+
+    format!("{}/sign/{}?{}", HIVESIGNER_URL, encode_uri_component(operation.name()), params.join("&"))
+}
+
+/// Returns the hivesigner oauth2 link that logs the user in to `app` with
+/// the permissions in `scope` (e.g. `"vote"`, `"comment"`)
+/// ```
+/// use tetanus::hive::hivesigner::hivesigner_login_url;
+/// assert_eq!(
+///     "https://hivesigner.com/oauth2/authorize?client_id=demo.app&redirect_uri=https%3A%2F%2Fdemo.app%2Fcallback&scope=vote%2Ccomment",
+///     hivesigner_login_url("demo.app", &["vote", "comment"], "https://demo.app/callback")
+/// )
+/// ```
+pub fn hivesigner_login_url(app: &str, scope: &[&str], redirect_uri: &str) -> String {
+    format!(
+        "{}/oauth2/authorize?client_id={}&redirect_uri={}&scope={}",
+        HIVESIGNER_URL,
+        encode_uri_component(app),
+        encode_uri_component(redirect_uri),
+        encode_uri_component(&scope.join(","))
+    )
+}
+
+/// Percent encodes everything but the characters javascript's
+/// `encodeURIComponent` leaves alone, which is what hivesigner expects
+fn encode_uri_component(input: &str) -> String {
+    input.bytes().fold(String::with_capacity(input.len()), |mut encoded, byte| {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9'
+            | b'-' | b'_' | b'.' | b'!' | b'~' | b'*' | b'\'' | b'(' | b')' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+
+        encoded
+    })
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::hivesigner::*;
+    use serde_json::json;
+
+    #[test]
+    fn sign_url_encodes_json_values() {
+        let data = json!({ "voter": "alice", "author": "bob", "permlink": "a-post", "weight": 10000 });
+        let op = Operation::new("vote", data.as_object().unwrap().clone());
+
+        assert_eq!(
+            "https://hivesigner.com/sign/vote?voter=alice&author=bob&permlink=a-post&weight=10000&no_broadcast=1&redirect_uri=https%3A%2F%2Fexample.com%2F%3Fdone%3D1",
+            hivesigner_sign_url(&op, "https://example.com/?done=1", true)
+        );
+    }
+
+    #[cfg(feature = "nai")]
+    #[test]
+    fn sign_url_uses_legacy_assets() {
+        use crate::hive::operations::TransferToVestingOperation;
+        use crate::hive::types::{AssetSymbol, HiveAsset};
+
+        let op = TransferToVestingOperation { from: "alice".to_string(), to: String::new(), amount: HiveAsset::new(1000, AssetSymbol::Hive) };
+
+        assert_eq!(
+            "https://hivesigner.com/sign/transfer_to_vesting?from=alice&to=&amount=1.000%20HIVE&redirect_uri=https%3A%2F%2Fexample.com",
+            hivesigner_sign_url(&op.into(), "https://example.com", false)
+        );
+    }
+
+    #[test]
+    fn unicode_is_percent_encoded() {
+        assert_eq!("%C3%A9t%C3%A9%20%F0%9F%8C%9E", encode_uri_component("été 🌞"));
+    }
+}
@@ -0,0 +1,591 @@
+//! In memory `HiveClientTrait` and `HttpTransport` implementations for tests,
+//! enabled with the `test-utils` feature.
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use crate::hive::client::{HiveClientError, HiveClientTrait, HttpTransport, TransportError};
+
+
+/// A queued result, or the code and message of a json-rpc error
+type MockResponse = Result<Value, (i64, String)>;
+
+/// Answers requests with pre-programmed responses, each json-rpc method has
+/// its own queue which is answered in order. Requests are recorded so tests
+/// can check the params that were sent
+/// ```
+/// use serde_json::json;
+/// use tetanus::hive::{client::HiveClientTrait, mock::MockHiveClient};
+/// let client = MockHiveClient::new();
+/// client.push_response("condenser_api.get_active_witnesses", json!(["gtg", "blocktrades"]));
+///
+/// let witnesses = futures::executor::block_on(client.get_active_witnesses()).unwrap();
+/// assert_eq!(vec!["gtg", "blocktrades"], witnesses);
+/// assert_eq!(vec![("condenser_api.get_active_witnesses".to_string(), json!([]))], client.requests());
+/// ```
+#[derive(Default)]
+pub struct MockHiveClient {
+    responses: Mutex<HashMap<String, VecDeque<MockResponse>>>,
+    requests: Mutex<Vec<(String, Value)>>,
+}
+
+impl MockHiveClient {
+    pub fn new() -> Self {
+        MockHiveClient::default()
+    }
+
+    /// Queues `result` as the next answer to `method`
+    pub fn push_response(&self, method: &str, result: Value) {
+        self.push(method, Ok(result));
+    }
+
+    /// Queues a json-rpc error as the next answer to `method`
+    pub fn push_error(&self, method: &str, code: i64, message: &str) {
+        self.push(method, Err((code, message.to_string())));
+    }
+
+    /// Every request sent so far as `(method, params)`, oldest first
+    pub fn requests(&self) -> Vec<(String, Value)> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn push(&self, method: &str, response: MockResponse) {
+        self.responses.lock().unwrap().entry(method.to_string()).or_default().push_back(response);
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl HiveClientTrait for MockHiveClient {
+    /// Answers with the next queued response for `method`, once the queue is
+    /// empty a `-32601` method not found error is returned
+    async fn request(&self, method: &str, params: Value) -> Result<Value, HiveClientError> {
+        self.requests.lock().unwrap().push((method.to_string(), params));
+
+        let response = self.responses.lock().unwrap().get_mut(method).and_then(VecDeque::pop_front);
+        match response {
+            Some(Ok(result)) => Ok(result),
+            Some(Err((code, message))) => Err(HiveClientError::Rpc { code, message }),
+            None => Err(HiveClientError::Rpc { code: -32601, message: format!("no mock response for {}", method) }),
+        }
+    }
+}
+
+/// A queued response body, or the message of a transport failure
+type MockBody = Result<String, String>;
+
+/// Answers a `HiveClient`'s http posts with pre-programmed bodies, in the
+/// order they were queued. Where `MockHiveClient` stands in for the whole
+/// client, this tests `HiveClient` itself, its json-rpc envelope, retries and
+/// fallback nodes. Once the queue is empty every post fails
+/// ```
+/// use std::sync::Arc;
+/// use serde_json::json;
+/// use tetanus::hive::{client::{HiveClient, HiveClientTrait}, mock::MockTransport};
+/// let transport = Arc::new(MockTransport::new());
+/// transport.push_result(json!(["gtg", "blocktrades"]));
+/// let client = HiveClient::builder().transport(transport.clone()).build().unwrap();
+///
+/// let witnesses = futures::executor::block_on(client.get_active_witnesses()).unwrap();
+/// assert_eq!(vec!["gtg", "blocktrades"], witnesses);
+/// assert_eq!("condenser_api.get_active_witnesses", transport.requests()[0].1["method"]);
+/// ```
+#[derive(Default)]
+pub struct MockTransport {
+    bodies: Mutex<VecDeque<MockBody>>,
+    requests: Mutex<Vec<(String, Value)>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        MockTransport::default()
+    }
+
+    /// Queues a json-rpc response with `result` as the next body
+    pub fn push_result(&self, result: Value) {
+        self.push_body(&json!({ "jsonrpc": "2.0", "result": result, "id": 1 }).to_string());
+    }
+
+    /// Queues a json-rpc error response as the next body
+    pub fn push_error(&self, code: i64, message: &str) {
+        self.push_body(&json!({ "jsonrpc": "2.0", "error": { "code": code, "message": message }, "id": 1 }).to_string());
+    }
+
+    /// Queues `body` as is, e.g. the html of a gateway error
+    pub fn push_body(&self, body: &str) {
+        self.bodies.lock().unwrap().push_back(Ok(body.to_string()));
+    }
+
+    /// Queues a failure to get any response, such as a refused connection
+    pub fn push_failure(&self, message: &str) {
+        self.bodies.lock().unwrap().push_back(Err(message.to_string()));
+    }
+
+    /// Every post so far as `(url, json body)`, oldest first
+    pub fn requests(&self) -> Vec<(String, Value)> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl HttpTransport for MockTransport {
+    async fn post(&self, url: &str, body: String) -> Result<String, TransportError> {
+        let body = serde_json::from_str(&body).unwrap_or(Value::String(body));
+        self.requests.lock().unwrap().push((url.to_string(), body));
+
+        match self.bodies.lock().unwrap().pop_front() {
+            Some(Ok(body)) => Ok(body),
+            Some(Err(message)) => Err(TransportError::Other(message)),
+            None => Err(TransportError::Other("no mock body queued".to_string())),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use crate::hive::client::{DiscussionQuery, HiveClient};
+    use crate::hive::mock::*;
+    use crate::hive::transactions::Transaction;
+    use crate::hive::types::{FollowType, RcEstimate};
+    use futures::executor::block_on;
+    use serde_json::json;
+
+    #[test]
+    fn responses_are_returned_in_order() {
+        let client = MockHiveClient::new();
+        client.push_response("condenser_api.get_block", json!(null));
+        client.push_error("condenser_api.get_block", -32000, "Assert Exception");
+
+        assert_eq!(None, block_on(client.get_block(1)).unwrap());
+        assert!(matches!(block_on(client.get_block(2)), Err(HiveClientError::Rpc { code: -32000, .. })));
+        assert!(matches!(block_on(client.get_block(3)), Err(HiveClientError::Rpc { code: -32601, .. })));
+
+        let params: Vec<Value> = client.requests().into_iter().map(|(_, params)| params).collect();
+        assert_eq!(vec![json!([1]), json!([2]), json!([3])], params);
+    }
+
+    #[test]
+    fn typed_methods_unwrap_responses() {
+        let client = MockHiveClient::new();
+        client.push_response("rc_api.find_rc_accounts", json!({ "rc_accounts": [] }));
+        client.push_response("condenser_api.get_required_keys", json!(["STM1", "STM2"]));
+
+        assert!(block_on(client.find_rc_accounts(&["alice"])).unwrap().is_empty());
+        assert_eq!(json!({ "accounts": ["alice"] }), client.requests()[0].1);
+        let tx: Transaction = serde_json::from_value(json!({
+            "ref_block_num": 1, "ref_block_prefix": 2, "expiration": "2024-01-01T00:00:00", "operations": [], "extensions": []
+        })).unwrap();
+        // keys the caller did not offer are dropped
+        assert_eq!(vec!["STM2"], block_on(client.get_required_keys(&tx, &["STM2", "STM3"])).unwrap());
+    }
+
+    #[cfg(feature = "nai")]
+    #[test]
+    fn required_keys_sends_legacy_assets() {
+        let client = MockHiveClient::new();
+        client.push_response("condenser_api.get_required_keys", json!([]));
+
+        let tx: Transaction = serde_json::from_value(json!({
+            "ref_block_num": 1, "ref_block_prefix": 2, "expiration": "2024-01-01T00:00:00", "extensions": [],
+            "operations": [["transfer", { "from": "alice", "to": "bob", "amount": "1.000 HIVE", "memo": "" }]]
+        })).unwrap();
+        block_on(client.get_required_keys(&tx, &["STM1"])).unwrap();
+
+        let (_, params) = client.requests().pop().unwrap();
+        assert_eq!(json!("1.000 HIVE"), params[0]["operations"][0][1]["amount"]);
+    }
+
+    #[test]
+    fn activity_queries_send_cursors() {
+        let client = MockHiveClient::new();
+        client.push_response("condenser_api.get_account_votes", json!([{ "authorperm": "hiveio/a-post", "weight": 5, "rshares": "12", "percent": 100, "time": "2022-07-01T12:00:00" }]));
+        client.push_response("condenser_api.get_discussions_by_author_before_date", json!([]));
+        client.push_response("condenser_api.get_discussions_by_author_before_date", json!([]));
+
+        client.push_response("condenser_api.get_active_votes", json!([{ "voter": "alice", "weight": 0, "rshares": -7, "percent": -10000, "reputation": 0, "time": "2022-07-01T12:00:00" }]));
+
+        assert_eq!(12, block_on(client.get_account_votes("alice")).unwrap()[0].rshares);
+        assert!(block_on(client.get_account_posts("alice", 10, None)).unwrap().is_empty());
+        assert!(block_on(client.get_account_posts("alice", 10, Some("a-post"))).unwrap().is_empty());
+        assert!(block_on(client.get_active_votes("bob", "a-post")).unwrap()[0].is_downvote());
+
+        let params: Vec<Value> = client.requests().into_iter().map(|(_, params)| params).collect();
+        assert_eq!(json!(["alice"]), params[0]);
+        assert_eq!(json!(["alice", "", "1970-01-01T00:00:00", 10]), params[1]);
+        assert_eq!(json!(["alice", "a-post", "1970-01-01T00:00:00", 10]), params[2]);
+        assert_eq!(json!(["bob", "a-post"]), params[3]);
+    }
+
+    #[test]
+    fn witnesses_are_paged() {
+        let witness = |owner: &str| json!({
+            "owner": owner, "signing_key": "STM5F9tCbND6zWPwksy1rEN24WjPiQWSU2vwGgegQVjAcYDe1a3oP", "url": "", "votes": "1",
+            "virtual_last_update": "0", "virtual_position": "0", "virtual_scheduled_time": "0", "running_version": "1.27.0",
+            "props": { "account_creation_fee": "3.000 HIVE", "maximum_block_size": 65536, "hbd_interest_rate": 2000 },
+        });
+        let client = MockHiveClient::new();
+        let names: Vec<String> = (0..1500).map(|i| format!("witness{}", i)).collect();
+        client.push_response("condenser_api.get_witnesses_by_vote", Value::Array(names[..1000].iter().map(|name| witness(name)).collect()));
+        client.push_response("condenser_api.get_witnesses_by_vote", Value::Array(names[999..].iter().map(|name| witness(name)).collect()));
+
+        let witnesses = block_on(client.list_witnesses(2000)).unwrap();
+        assert_eq!(names, witnesses.iter().map(|witness| witness.owner.clone()).collect::<Vec<_>>());
+
+        let params: Vec<Value> = client.requests().into_iter().map(|(_, params)| params).collect();
+        assert_eq!(vec![json!(["", 1000]), json!(["witness999", 1000])], params);
+
+        client.push_response("condenser_api.get_witnesses_by_vote", Value::Array(names[..3].iter().map(|name| witness(name)).collect()));
+        assert_eq!(2, block_on(client.list_witnesses(2)).unwrap().len());
+
+        assert!(matches!(block_on(client.get_witnesses_by_vote("", 1001)), Err(HiveClientError::LimitTooLarge { limit: 1001, max: 1000 })));
+        assert_eq!(3, client.requests().len());
+    }
+
+    #[test]
+    fn blog_limit_is_checked() {
+        let client = MockHiveClient::new();
+        client.push_response("follow_api.get_blog", json!({ "blog": [] }));
+
+        assert!(block_on(client.get_blog("alice", 0, 500)).unwrap().is_empty());
+        let params = json!({ "account": "alice", "start_entry_id": 0, "limit": 500 });
+        assert_eq!(vec![("follow_api.get_blog".to_string(), params)], client.requests());
+
+        assert!(matches!(block_on(client.get_blog("alice", 0, 501)), Err(HiveClientError::LimitTooLarge { limit: 501, max: 500 })));
+        assert_eq!(1, client.requests().len());
+    }
+
+    #[test]
+    fn follows_send_their_type() {
+        use crate::hive::types::FollowType;
+
+        let client = MockHiveClient::new();
+        client.push_response("follow_api.get_followers", json!({ "followers": [{ "follower": "bob", "following": "alice", "what": ["blog"] }] }));
+        client.push_response("follow_api.get_following", json!({ "following": [] }));
+
+        assert_eq!("bob", block_on(client.get_followers("alice", "", FollowType::Blog, 1000)).unwrap()[0].follower);
+        assert!(block_on(client.get_following("alice", "bob", FollowType::Ignore, 10)).unwrap().is_empty());
+        assert!(matches!(block_on(client.get_following("alice", "", FollowType::Blog, 1001)), Err(HiveClientError::LimitTooLarge { max: 1000, .. })));
+
+        assert_eq!(vec![
+            ("follow_api.get_followers".to_string(), json!({ "account": "alice", "start": "", "type": "blog", "limit": 1000 })),
+            ("follow_api.get_following".to_string(), json!({ "account": "alice", "start": "bob", "type": "ignore", "limit": 10 })),
+        ], client.requests());
+    }
+
+    #[test]
+    fn lookups_keep_missing_names() {
+        let authority = json!({ "weight_threshold": 1, "account_auths": [], "key_auths": [["STM5jixkNBqJXNtX9vy2GjaqpX2d5jXrcjRXgh1WU5fXZhnDJrLM8", 1]] });
+        let account = json!({
+            "id": 1370484, "name": "test", "owner": authority, "active": authority, "posting": authority,
+            "memo_key": "STM5jixkNBqJXNtX9vy2GjaqpX2d5jXrcjRXgh1WU5fXZhnDJrLM8", "json_metadata": "", "posting_json_metadata": "", "proxy": "",
+            "recovery_account": "hiveio", "created": "2020-03-20T14:15:06", "post_count": 12,
+            "voting_manabar": { "current_mana": "9800000000", "last_update_time": 1656674400 },
+            "balance": "10.000 HIVE", "savings_balance": "0.000 HIVE", "hbd_balance": "1.234 HBD", "savings_hbd_balance": "0.000 HBD",
+            "vesting_shares": "20000.000000 VESTS", "delegated_vesting_shares": "0.000000 VESTS", "received_vesting_shares": "0.000000 VESTS",
+            "reward_hive_balance": "0.001 HIVE", "reward_hbd_balance": "0.002 HBD", "reward_vesting_balance": "3.000000 VESTS", "reputation": 0,
+        });
+        let client = MockHiveClient::new();
+        client.push_response("condenser_api.lookup_account_names", json!([null, account]));
+        client.push_response("condenser_api.lookup_accounts", json!(["test", "test-1", "testing"]));
+
+        let accounts = block_on(client.lookup_account_names(&["free-name", "test"])).unwrap();
+        assert_eq!(None, accounts[0]);
+        assert_eq!("test", accounts[1].as_ref().unwrap().name);
+
+        assert_eq!(vec!["test", "test-1", "testing"], block_on(client.lookup_accounts("test", 3)).unwrap());
+        assert!(matches!(block_on(client.lookup_accounts("test", 1001)), Err(HiveClientError::LimitTooLarge { .. })));
+
+        let params: Vec<Value> = client.requests().into_iter().map(|(_, params)| params).collect();
+        assert_eq!(vec![json!([["free-name", "test"]]), json!(["test", 3])], params);
+    }
+
+    #[test]
+    fn reputations_are_looked_up_by_name() {
+        let client = MockHiveClient::new();
+        client.push_response("condenser_api.get_account_reputations", json!([{ "account": "alice", "reputation": "95720068393725" }]));
+        client.push_response("condenser_api.get_account_reputations", json!([{ "account": "freename", "reputation": 0 }]));
+        client.push_response("condenser_api.get_account_reputations", json!([
+            { "account": "bob", "reputation": 0 }, { "account": "bobby", "reputation": "10000000000" },
+        ]));
+
+        let reputations = block_on(client.get_account_reputations(&["alice", "free-name"], None)).unwrap();
+        assert_eq!(vec!["alice"], reputations.iter().map(|reputation| reputation.account.as_str()).collect::<Vec<_>>());
+        assert_eq!(2, block_on(client.get_account_reputations(&["bob"], Some(2))).unwrap().len());
+        assert!(matches!(block_on(client.get_account_reputations(&["bob"], Some(1001))), Err(HiveClientError::LimitTooLarge { .. })));
+
+        let params: Vec<Value> = client.requests().into_iter().map(|(_, params)| params).collect();
+        assert_eq!(vec![json!(["alice", 1]), json!(["free-name", 1]), json!(["bob", 2])], params);
+    }
+
+    #[test]
+    fn market_queries_send_their_params() {
+        let client = MockHiveClient::new();
+        client.push_response("market_history_api.get_ticker", json!({
+            "latest": "0.301", "lowest_ask": "0.302", "highest_bid": "0.301", "percent_change": "0.00",
+            "hive_volume": "1.000 HIVE", "hbd_volume": "0.301 HBD",
+        }));
+        client.push_response("condenser_api.get_order_book", json!({ "bids": [], "asks": [] }));
+
+        assert_eq!("0.302", block_on(client.get_ticker()).unwrap().lowest_ask);
+        assert!(block_on(client.get_order_book(50)).unwrap().bids.is_empty());
+        assert!(matches!(block_on(client.get_order_book(501)), Err(HiveClientError::LimitTooLarge { limit: 501, max: 500 })));
+
+        let params: Vec<Value> = client.requests().into_iter().map(|(_, params)| params).collect();
+        assert_eq!(vec![json!({}), json!([50])], params);
+    }
+
+    #[test]
+    fn trade_history_sends_date_range() {
+        let client = MockHiveClient::new();
+        client.push_response("market_history_api.get_trade_history", json!({ "trades": [
+            { "date": "2022-07-01T12:00:03", "current_pays": "3.012 HBD", "open_pays": "10.000 HIVE" },
+        ] }));
+        client.push_response("market_history_api.get_market_history_buckets", json!({ "bucket_sizes": [15, 60, 300, 3600, 86400] }));
+
+        let trades = block_on(client.get_trade_history("2022-07-01T12:00:00", "2022-07-01T13:00:00", 100)).unwrap();
+        assert_eq!("2022-07-01T12:00:03", trades[0].date);
+        assert_eq!(vec![15, 60, 300, 3600, 86400], block_on(client.get_market_history_buckets()).unwrap());
+        assert!(matches!(block_on(client.get_trade_history("", "", 1001)), Err(HiveClientError::LimitTooLarge { .. })));
+
+        let params: Vec<Value> = client.requests().into_iter().map(|(_, params)| params).collect();
+        assert_eq!(vec![json!({ "start": "2022-07-01T12:00:00", "end": "2022-07-01T13:00:00", "limit": 100 }), json!({})], params);
+    }
+
+    #[test]
+    fn rc_estimates_compare_cost_and_mana() {
+        let client = MockHiveClient::new();
+        let curve = json!({ "coeff_a": 1, "coeff_b": 1, "shift": 0 });
+        let params = json!({
+            "resource_names": ["resource_history_bytes"],
+            "resource_params": { "resource_history_bytes": { "resource_dynamics_params": { "resource_unit": 1 }, "price_curve_params": curve } },
+            "size_info": {}
+        });
+        let props = json!({
+            "head_block_number": 1, "head_block_id": "", "time": "2024-01-01T00:00:00", "current_witness": "gtg",
+            "current_supply": "1.000 HIVE", "current_hbd_supply": "1.000 HBD", "total_vesting_fund_hive": "1.000 HIVE",
+            "total_vesting_shares": "0.144000 VESTS", "hbd_interest_rate": 2000,
+            "last_irreversible_block_num": 1, "maximum_block_size": 65536
+        });
+        let account = |mana: u64| json!({ "rc_accounts": [{
+            "account": "alice", "rc_manabar": { "current_mana": mana.to_string(), "last_update_time": u32::MAX }, "max_rc": "1000000"
+        }] });
+        for rc_accounts in [account(1000), account(10), json!({ "rc_accounts": [] })] {
+            client.push_response("rc_api.get_resource_params", params.clone());
+            client.push_response("rc_api.get_resource_pool", json!({ "resource_pool": { "resource_history_bytes": { "pool": "1" } } }));
+            client.push_response("condenser_api.get_dynamic_global_properties", props.clone());
+            client.push_response("rc_api.find_rc_accounts", rc_accounts);
+        }
+
+        let tx: Transaction = serde_json::from_value(json!({
+            "ref_block_num": 1, "ref_block_prefix": 2, "expiration": "2024-01-01T00:00:00",
+            "operations": [["vote", { "voter": "alice", "author": "bob", "permlink": "a-post", "weight": 10000 }]], "extensions": []
+        })).unwrap();
+        let cost = tx.estimated_bytes().unwrap() as u64 + 1;
+
+        assert_eq!(RcEstimate { cost, available: 1000, sufficient: true }, block_on(client.estimate_rc_cost(&tx, "alice")).unwrap());
+        assert_eq!(RcEstimate { cost, available: 10, sufficient: false }, block_on(client.estimate_rc_cost(&tx, "alice")).unwrap());
+        assert_eq!(RcEstimate { cost, available: 0, sufficient: false }, block_on(client.estimate_rc_cost(&tx, "nobody")).unwrap());
+
+        let (method, params) = client.requests().pop().unwrap();
+        assert_eq!(("rc_api.find_rc_accounts", json!({ "accounts": ["nobody"] })), (method.as_str(), params));
+    }
+
+    #[test]
+    fn state_is_fetched_by_path() {
+        let client = MockHiveClient::new();
+        client.push_response("condenser_api.get_state", json!({ "current_route": "trending/hive", "accounts": {}, "content": {} }));
+
+        let state = block_on(client.get_state("trending/hive")).unwrap();
+        assert_eq!("trending/hive", state.0["current_route"]);
+        assert_eq!(None, state.get_account("alice"));
+        assert_eq!(vec![("condenser_api.get_state".to_string(), json!(["trending/hive"]))], client.requests());
+    }
+
+    #[test]
+    fn database_accounts_are_found_by_name() {
+        let client = MockHiveClient::new();
+        client.push_response("database_api.find_accounts", json!({ "accounts": [] }));
+
+        assert!(block_on(client.database_find_accounts(&["alice", "bob"])).unwrap().is_empty());
+        assert_eq!(vec![("database_api.find_accounts".to_string(), json!({ "accounts": ["alice", "bob"] }))], client.requests());
+    }
+
+    fn hive_client(transport: &Arc<MockTransport>, max_retries: u32) -> HiveClient {
+        HiveClient::builder()
+            .url("https://node-a")
+            .fallback_nodes(vec!["https://node-b".to_string()])
+            .max_retries(max_retries)
+            .transport(transport.clone())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn transport_carries_the_envelope() {
+        let transport = Arc::new(MockTransport::new());
+        transport.push_result(json!(["gtg"]));
+        transport.push_error(-32602, "Invalid parameters");
+        let client = hive_client(&transport, 3);
+
+        assert_eq!(vec!["gtg"], block_on(client.get_active_witnesses()).unwrap());
+        // json-rpc errors come from the node and are not retried
+        let error = block_on(client.get_block(1)).unwrap_err();
+        assert!(matches!(error, HiveClientError::Rpc { code: -32602, .. }));
+
+        let requests = transport.requests();
+        assert_eq!(2, requests.len());
+        assert_eq!(("https://node-a".to_string(), json!({ "jsonrpc": "2.0", "method": "condenser_api.get_active_witnesses", "params": [], "id": 1 })), requests[0]);
+        assert_eq!(json!([1]), requests[1].1["params"]);
+    }
+
+    #[test]
+    fn failures_rotate_through_fallback_nodes() {
+        let transport = Arc::new(MockTransport::new());
+        transport.push_failure("connection refused");
+        transport.push_body("<html>502 Bad Gateway</html>");
+        transport.push_result(json!(["gtg"]));
+        let client = hive_client(&transport, 2);
+
+        assert_eq!(vec!["gtg"], block_on(client.get_active_witnesses()).unwrap());
+        let urls: Vec<String> = transport.requests().into_iter().map(|(url, _)| url).collect();
+        assert_eq!(vec!["https://node-a", "https://node-b", "https://node-a"], urls);
+
+        // the last failure is returned once the retries run out
+        transport.push_body("<html>502 Bad Gateway</html>");
+        transport.push_failure("connection refused");
+        let error = block_on(hive_client(&transport, 1).get_active_witnesses()).unwrap_err();
+        assert!(matches!(error, HiveClientError::Transport(message) if message == "connection refused"));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn typed_methods_go_through_the_transport() {
+        let transport = Arc::new(MockTransport::new());
+        // nothing is queued, so every method stops after its first request
+        let client = hive_client(&transport, 0);
+        let tx: Transaction = serde_json::from_value(json!({
+            "ref_block_num": 1, "ref_block_prefix": 2, "expiration": "2024-01-01T00:00:00", "operations": [], "extensions": []
+        })).unwrap();
+
+        block_on(async {
+            assert!(client.get_dynamic_global_properties().await.is_err());
+            assert!(client.get_accounts(&["alice"]).await.is_err());
+            assert!(client.database_find_accounts(&["alice"]).await.is_err());
+            assert!(client.lookup_account_names(&["alice"]).await.is_err());
+            assert!(client.lookup_accounts("a", 10).await.is_err());
+            assert!(client.get_account_reputations(&["alice"], None).await.is_err());
+            assert!(client.get_block(1).await.is_err());
+            assert!(client.get_state("@alice").await.is_err());
+            assert!(client.get_content("alice", "a-post").await.is_err());
+            assert!(client.find_rc_accounts(&["alice"]).await.is_err());
+            assert!(client.get_accounts_rc(&["alice"]).await.is_err());
+            assert!(client.get_resource_params().await.is_err());
+            assert!(client.get_resource_pool().await.is_err());
+            assert!(client.estimate_rc_cost(&tx, "alice").await.is_err());
+            assert!(client.get_reward_fund("post").await.is_err());
+            assert!(client.get_feed_history().await.is_err());
+            assert!(client.get_required_keys(&tx, &[]).await.is_err());
+            assert!(client.broadcast_transaction(&tx).await.is_err());
+            assert!(client.broadcast_many(std::slice::from_ref(&tx), false).await[0].is_err());
+            assert!(client.get_account_history("alice", -1, 10, None).await.is_err());
+            assert!(client.get_account_votes("alice").await.is_err());
+            assert!(client.get_active_votes("bob", "a-post").await.is_err());
+            assert!(client.get_account_posts("alice", 10, None).await.is_err());
+            assert!(client.get_blog("alice", 0, 10).await.is_err());
+            assert!(client.get_followers("alice", "", FollowType::Blog, 10).await.is_err());
+            assert!(client.get_following("alice", "", FollowType::Blog, 10).await.is_err());
+            assert!(client.get_discussions("trending", &DiscussionQuery::new("hive", 10)).await.is_err());
+            assert!(client.get_discussions_by_trending("hive", 10, None, None).await.is_err());
+            assert!(client.get_discussions_by_created("hive", 10, None, None).await.is_err());
+            assert!(client.get_discussions_by_hot("hive", 10, None, None).await.is_err());
+            assert!(client.get_discussions_by_promoted("hive", 10, None, None).await.is_err());
+            assert!(client.get_discussions_by_blog("alice", 10, None, None).await.is_err());
+            assert!(client.get_active_witnesses().await.is_err());
+            assert!(client.get_witnesses_by_vote("", 10).await.is_err());
+            assert!(client.list_witnesses(10).await.is_err());
+            assert!(client.get_witness_schedule().await.is_err());
+            assert!(client.find_proposals(&[1]).await.is_err());
+            assert!(client.get_open_orders("alice").await.is_err());
+            assert!(client.get_current_median_price().await.is_err());
+            assert!(client.get_ticker().await.is_err());
+            assert!(client.get_order_book(10).await.is_err());
+            assert!(client.get_trade_history("", "", 10).await.is_err());
+            assert!(client.get_market_history_buckets().await.is_err());
+        });
+
+        let methods: Vec<String> = transport.requests().into_iter().map(|(_, body)| body["method"].as_str().unwrap().to_string()).collect();
+        assert_eq!(vec![
+            "condenser_api.get_dynamic_global_properties", "condenser_api.get_accounts", "database_api.find_accounts",
+            "condenser_api.lookup_account_names", "condenser_api.lookup_accounts", "condenser_api.get_account_reputations",
+            "condenser_api.get_block", "condenser_api.get_state", "condenser_api.get_content", "rc_api.find_rc_accounts",
+            "rc_api.find_rc_accounts", "rc_api.get_resource_params", "rc_api.get_resource_pool", "rc_api.get_resource_params",
+            "condenser_api.get_reward_fund", "condenser_api.get_feed_history", "condenser_api.get_required_keys",
+            "condenser_api.broadcast_transaction", "condenser_api.broadcast_transaction",
+            "condenser_api.get_account_history", "condenser_api.get_account_votes", "condenser_api.get_active_votes",
+            "condenser_api.get_discussions_by_author_before_date", "follow_api.get_blog", "follow_api.get_followers",
+            "follow_api.get_following", "condenser_api.get_discussions_by_trending", "condenser_api.get_discussions_by_trending",
+            "condenser_api.get_discussions_by_created", "condenser_api.get_discussions_by_hot",
+            "condenser_api.get_discussions_by_promoted", "condenser_api.get_discussions_by_blog",
+            "condenser_api.get_active_witnesses", "condenser_api.get_witnesses_by_vote", "condenser_api.get_witnesses_by_vote",
+            "condenser_api.get_witness_schedule", "database_api.find_proposals", "condenser_api.get_open_orders",
+            "condenser_api.get_current_median_history_price", "market_history_api.get_ticker", "condenser_api.get_order_book",
+            "market_history_api.get_trade_history", "market_history_api.get_market_history_buckets",
+        ], methods);
+    }
+
+    fn signed_votes(count: u64) -> Vec<Transaction> {
+        let key = crate::keys::private::PrivateKey::from_login("alice", "password", "posting");
+
+        (1..=count).map(|ref_block_num| {
+            let mut tx: Transaction = serde_json::from_value(json!({
+                "ref_block_num": ref_block_num, "ref_block_prefix": 2, "expiration": "2024-01-01T00:00:00",
+                "operations": [["vote", { "voter": "alice", "author": "bob", "permlink": "a-post", "weight": 10000 }]], "extensions": []
+            })).unwrap();
+            tx.add_signature(key.sign_transaction(&tx).unwrap());
+            tx
+        }).collect()
+    }
+
+    #[test]
+    fn broadcasts_return_transaction_ids() {
+        let txs = signed_votes(3);
+        let ids: Vec<String> = txs.iter().map(|tx| tx.id().unwrap()).collect();
+        let transport = Arc::new(MockTransport::new());
+        let client = hive_client(&transport, 0);
+
+        transport.push_result(json!({}));
+        transport.push_error(-32000, "missing required posting authority");
+        transport.push_result(json!({}));
+        let results = block_on(client.broadcast_many(&txs, false));
+        assert_eq!(3, results.len());
+        assert_eq!(&ids[0], results[0].as_ref().unwrap());
+        assert!(matches!(&results[1], Err(HiveClientError::Rpc { code: -32000, .. })));
+        assert_eq!(&ids[2], results[2].as_ref().unwrap());
+
+        // the signed transaction goes out in the legacy json, signatures included
+        let sent = &transport.requests()[0].1;
+        assert_eq!("condenser_api.broadcast_transaction", sent["method"]);
+        assert_eq!(serde_json::from_str::<Value>(&txs[0].to_legacy_json().unwrap()).unwrap(), sent["params"][0]);
+        assert_eq!(1, sent["params"][0]["signatures"].as_array().unwrap().len());
+    }
+
+    #[test]
+    fn fail_fast_stops_at_the_first_error() {
+        let txs = signed_votes(3);
+        let transport = Arc::new(MockTransport::new());
+        let client = hive_client(&transport, 0);
+
+        transport.push_result(json!({}));
+        transport.push_error(-32000, "duplicate transaction");
+        transport.push_result(json!({}));
+        let results = block_on(client.broadcast_many(&txs, true));
+        assert_eq!(2, results.len());
+        assert!(results[0].is_ok() && results[1].is_err());
+        assert_eq!(2, transport.requests().len());
+
+        assert!(block_on(client.broadcast_many(&[], true)).is_empty());
+    }
+}
@@ -1,2 +1,15 @@
-pub mod transactions;
-pub mod client;
\ No newline at end of file
+pub mod transactions;
+pub mod operations;
+pub mod packing;
+#[cfg(feature = "hive-client")]
+pub mod client;
+pub mod types;
+pub mod hive_engine;
+pub mod hivemind;
+pub mod hivesigner;
+pub mod splinterlands;
+pub mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "test-utils")]
+pub mod mock;
\ No newline at end of file
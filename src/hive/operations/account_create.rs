@@ -0,0 +1,162 @@
+use serde::{Serialize, Deserialize};
+use crate::hive::operations::{validate_account, validate_non_negative, validate_symbol, TypedOperation};
+use crate::hive::packing::{pack_string, PackingError, Unpacker};
+use crate::hive::transactions::TransactionError;
+use crate::hive::types::{public_key_bytes, AssetSymbol, Authority, HiveAsset};
+use crate::keys::public::PublicKey;
+
+/// Creates `new_account_name`, paid for by `creator` with `fee` which must be
+/// at least the witnesses' account creation fee
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountCreateOperation {
+    pub fee: HiveAsset,
+    pub creator: String,
+    pub new_account_name: String,
+    pub owner: Authority,
+    pub active: Authority,
+    pub posting: Authority,
+    pub memo_key: String,
+    pub json_metadata: String,
+}
+
+impl TypedOperation for AccountCreateOperation {
+    const NAME: &'static str = "account_create";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_account("creator", &self.creator)?;
+        validate_account("new_account_name", &self.new_account_name)?;
+        validate_symbol("fee", &self.fee, &[AssetSymbol::Hive])?;
+        validate_non_negative("fee", &self.fee)?;
+
+        for (field, authority) in [("owner", &self.owner), ("active", &self.active), ("posting", &self.posting)] {
+            authority.validate().map_err(|error| TransactionError::InvalidAuthority { field: field.to_string(), error })?;
+        }
+
+        if self.memo_key.parse::<PublicKey>().is_err() {
+            return Err(TransactionError::InvalidValue { field: "memo_key".to_string(), reason: "must be a public key".to_string() })
+        }
+        if !self.json_metadata.is_empty() && serde_json::from_str::<serde_json::Value>(&self.json_metadata).is_err() {
+            return Err(TransactionError::InvalidValue { field: "json_metadata".to_string(), reason: "must be empty or json".to_string() })
+        }
+
+        Ok(())
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        self.fee.pack(buf);
+        pack_string(buf, &self.creator);
+        pack_string(buf, &self.new_account_name);
+        self.owner.pack(buf);
+        self.active.pack(buf);
+        self.posting.pack(buf);
+        buf.extend_from_slice(&public_key_bytes(&self.memo_key));
+        pack_string(buf, &self.json_metadata);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(AccountCreateOperation {
+            fee: unpacker.asset()?,
+            creator: unpacker.string()?,
+            new_account_name: unpacker.string()?,
+            owner: Authority::unpack(unpacker)?,
+            active: Authority::unpack(unpacker)?,
+            posting: Authority::unpack(unpacker)?,
+            memo_key: unpacker.public_key()?,
+            json_metadata: unpacker.string()?,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::operations::*;
+    use crate::hive::types::{Authority, AuthorityError};
+    use crate::keys::private::PrivateKey;
+
+    fn key(role: &str) -> String {
+        PrivateKey::from_login("newbie", "password", role).to_public().to_string(None)
+    }
+
+    fn authority(role: &str) -> Authority {
+        Authority { weight_threshold: 1, account_auths: vec![], key_auths: vec![(key(role), 1)] }
+    }
+
+    fn create() -> AccountCreateOperation {
+        AccountCreateOperation {
+            fee: HiveAsset::new(3000, AssetSymbol::Hive),
+            creator: "alice".to_string(),
+            new_account_name: "newbie".to_string(),
+            owner: authority("owner"),
+            active: authority("active"),
+            posting: Authority { account_auths: vec![("ecency.app".to_string(), 1)], ..authority("posting") },
+            memo_key: key("memo"),
+            json_metadata: String::new(),
+        }
+    }
+
+    #[test]
+    fn packs_account_create() {
+        let mut buf = vec![];
+        Operation::from(create()).pack(&mut buf).unwrap();
+
+        let public = |role: &str| key(role).parse::<crate::keys::public::PublicKey>().unwrap().as_bytes().to_vec();
+        let key_authority = |role: &str| [vec![1, 0, 0, 0, 0, 1], public(role), vec![1, 0]].concat();
+        let expected = [
+            vec![9], 3000i64.to_le_bytes().to_vec(), vec![3], b"STEEM\0\0".to_vec(),
+            vec![5], b"alice".to_vec(), vec![6], b"newbie".to_vec(),
+            key_authority("owner"), key_authority("active"),
+            vec![1, 0, 0, 0, 1, 10], b"ecency.app".to_vec(), vec![1, 0, 1], public("posting"), vec![1, 0],
+            public("memo"), vec![0],
+        ].concat();
+        assert_eq!(expected, buf);
+    }
+
+    #[test]
+    fn packs_authorities_sorted() {
+        let unsorted = Authority {
+            weight_threshold: 2,
+            account_auths: vec![("carol".to_string(), 1), ("bob".to_string(), 1)],
+            key_auths: vec![],
+        };
+        let sorted = Authority { account_auths: unsorted.account_auths.iter().rev().cloned().collect(), ..unsorted.clone() };
+
+        let (mut a, mut b) = (vec![], vec![]);
+        unsorted.pack(&mut a);
+        sorted.pack(&mut b);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let op = Operation::from(create());
+        let json = serde_json::to_value(&op).unwrap();
+
+        assert_eq!("account_create", json[0]);
+        assert_eq!(1, json[1]["owner"]["key_auths"][0][1]);
+        assert_eq!(op, serde_json::from_value(json).unwrap());
+    }
+
+    #[test]
+    fn authorities_are_checked() {
+        assert!(create().validate().is_ok());
+
+        let bad_name = AccountCreateOperation { new_account_name: "Newbie".to_string(), ..create() };
+        assert!(matches!(bad_name.validate(), Err(TransactionError::InvalidAccountName { field, .. }) if field == "new_account_name"));
+
+        let locked = AccountCreateOperation { active: Authority { weight_threshold: 2, ..authority("active") }, ..create() };
+        assert!(matches!(
+            locked.validate(),
+            Err(TransactionError::InvalidAuthority { field, error: AuthorityError::Impossible { .. } }) if field == "active"
+        ));
+
+        let bad_key = AccountCreateOperation { owner: Authority { key_auths: vec![("STM1".to_string(), 1)], ..authority("owner") }, ..create() };
+        assert!(matches!(bad_key.validate(), Err(TransactionError::InvalidAuthority { error: AuthorityError::InvalidKey(_), .. })));
+
+        let duplicate = AccountCreateOperation { owner: Authority { key_auths: vec![(key("owner"), 1); 2], ..authority("owner") }, ..create() };
+        assert!(matches!(duplicate.validate(), Err(TransactionError::InvalidAuthority { error: AuthorityError::Duplicate(_), .. })));
+
+        let bad_memo = AccountCreateOperation { memo_key: "memo".to_string(), ..create() };
+        assert!(bad_memo.validate().is_err());
+    }
+}
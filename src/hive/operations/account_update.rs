@@ -0,0 +1,144 @@
+use serde::{Serialize, Deserialize};
+use crate::hive::operations::{pack_optional, unpack_optional, validate_account, validate_metadata, TypedOperation};
+use crate::hive::packing::{pack_string, PackingError, Unpacker};
+use crate::hive::transactions::TransactionError;
+use crate::hive::types::{public_key_bytes, Authority};
+use crate::keys::public::PublicKey;
+
+/// Changes an account's keys and metadata. An authority that is `Some`
+/// replaces the current one entirely, `None` keeps it. Unlike
+/// `AccountUpdate2Operation` the memo key and `json_metadata` are always set,
+/// and the operation needs the active authority, or the owner authority
+/// when `owner` is replaced
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountUpdateOperation {
+    pub account: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Authority>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active: Option<Authority>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub posting: Option<Authority>,
+    pub memo_key: String,
+    pub json_metadata: String,
+}
+
+impl TypedOperation for AccountUpdateOperation {
+    const NAME: &'static str = "account_update";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_account("account", &self.account)?;
+
+        for (field, authority) in [("owner", &self.owner), ("active", &self.active), ("posting", &self.posting)] {
+            if let Some(authority) = authority {
+                authority.validate().map_err(|error| TransactionError::InvalidAuthority { field: field.to_string(), error })?;
+            }
+        }
+
+        if self.memo_key.parse::<PublicKey>().is_err() {
+            return Err(TransactionError::InvalidValue { field: "memo_key".to_string(), reason: "must be a public key".to_string() })
+        }
+        validate_metadata("json_metadata", &self.json_metadata)
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.account);
+        pack_optional(buf, &self.owner, |buf, authority| authority.pack(buf));
+        pack_optional(buf, &self.active, |buf, authority| authority.pack(buf));
+        pack_optional(buf, &self.posting, |buf, authority| authority.pack(buf));
+        buf.extend_from_slice(&public_key_bytes(&self.memo_key));
+        pack_string(buf, &self.json_metadata);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(AccountUpdateOperation {
+            account: unpacker.string()?,
+            owner: unpack_optional(unpacker, Authority::unpack)?,
+            active: unpack_optional(unpacker, Authority::unpack)?,
+            posting: unpack_optional(unpacker, Authority::unpack)?,
+            memo_key: unpacker.public_key()?,
+            json_metadata: unpacker.string()?,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::operations::*;
+    use crate::hive::types::Authority;
+    use crate::keys::{private::PrivateKey, public::PublicKey};
+    use serde_json::json;
+
+    fn key(role: &str) -> String {
+        PrivateKey::from_login("alice", "password", role).to_public().to_string(None)
+    }
+
+    fn authority(role: &str) -> Authority {
+        Authority { weight_threshold: 1, account_auths: vec![], key_auths: vec![(key(role), 1)] }
+    }
+
+    fn update() -> AccountUpdateOperation {
+        AccountUpdateOperation {
+            account: "alice".to_string(),
+            owner: None,
+            active: Some(authority("active")),
+            posting: None,
+            memo_key: key("memo"),
+            json_metadata: String::new(),
+        }
+    }
+
+    #[test]
+    fn packs_account_update() {
+        let mut buf = vec![];
+        Operation::from(update()).pack(&mut buf).unwrap();
+
+        let bytes = |role| key(role).parse::<PublicKey>().unwrap().as_bytes().to_vec();
+        let expected = [
+            vec![10, 5], b"alice".to_vec(), vec![0, 1],
+            vec![1, 0, 0, 0, 0, 1], bytes("active"), vec![1, 0],
+            vec![0], bytes("memo"), vec![0],
+        ].concat();
+        assert_eq!(expected, buf);
+    }
+
+    #[test]
+    fn packs_without_authorities() {
+        let op = AccountUpdateOperation { active: None, json_metadata: "{}".to_string(), ..update() };
+        let mut buf = vec![];
+        Operation::from(op).pack(&mut buf).unwrap();
+
+        assert_eq!([vec![10, 5], b"alice".to_vec(), vec![0, 0, 0]].concat(), buf[..10]);
+        assert_eq!(10 + 33 + 3, buf.len());
+        assert_eq!(&[2, b'{', b'}'], &buf[43..]);
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let json = json!(["account_update", {
+            "account": "alice",
+            "active": { "weight_threshold": 1, "account_auths": [], "key_auths": [[key("active"), 1]] },
+            "memo_key": key("memo"),
+            "json_metadata": "",
+        }]);
+        let op: Operation = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(Operation::AccountUpdate(update()), op);
+        assert_eq!(json, serde_json::to_value(&op).unwrap());
+    }
+
+    #[test]
+    fn updates_are_checked() {
+        assert!(update().validate().is_ok());
+
+        let locked = AccountUpdateOperation { owner: Some(Authority { weight_threshold: 2, ..authority("owner") }), ..update() };
+        assert!(matches!(locked.validate(), Err(TransactionError::InvalidAuthority { field, .. }) if field == "owner"));
+
+        let bad_memo = AccountUpdateOperation { memo_key: String::new(), ..update() };
+        assert!(matches!(bad_memo.validate(), Err(TransactionError::InvalidValue { field, .. }) if field == "memo_key"));
+
+        let bad_metadata = AccountUpdateOperation { json_metadata: "{".to_string(), ..update() };
+        assert!(matches!(bad_metadata.validate(), Err(TransactionError::InvalidValue { field, .. }) if field == "json_metadata"));
+    }
+}
@@ -0,0 +1,176 @@
+use serde::{Serialize, Deserialize};
+use crate::hive::operations::{pack_optional, unpack_optional, validate_account, validate_metadata, TypedOperation};
+use crate::hive::packing::{pack_string, write_varint, PackingError, Unpacker};
+use crate::hive::transactions::TransactionError;
+use crate::hive::types::{public_key_bytes, Authority};
+use crate::keys::public::PublicKey;
+
+/// Updates an account's metadata and, optionally, its authorities and memo
+/// key. Unlike `account_update` every authority is optional, so the common
+/// case of changing the profile or the posting authority only needs the
+/// posting key (or active for the posting authority), not the owner key.
+/// `None` leaves a field as it is
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountUpdate2Operation {
+    pub account: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Authority>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active: Option<Authority>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub posting: Option<Authority>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo_key: Option<String>,
+    pub json_metadata: String,
+    pub posting_json_metadata: String,
+    pub extensions: Vec<String>,
+}
+
+impl TypedOperation for AccountUpdate2Operation {
+    const NAME: &'static str = "account_update2";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_account("account", &self.account)?;
+
+        for (field, authority) in [("owner", &self.owner), ("active", &self.active), ("posting", &self.posting)] {
+            if let Some(authority) = authority {
+                authority.validate().map_err(|error| TransactionError::InvalidAuthority { field: field.to_string(), error })?;
+            }
+        }
+
+        if self.memo_key.as_ref().is_some_and(|key| key.parse::<PublicKey>().is_err()) {
+            return Err(TransactionError::InvalidValue { field: "memo_key".to_string(), reason: "must be a public key".to_string() })
+        }
+        validate_metadata("json_metadata", &self.json_metadata)?;
+        validate_metadata("posting_json_metadata", &self.posting_json_metadata)?;
+
+        match self.extensions.first() {
+            Some(extension) => Err(TransactionError::UnsupportedExtension(extension.clone())),
+            None => Ok(()),
+        }
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.account);
+        pack_optional(buf, &self.owner, |buf, authority| authority.pack(buf));
+        pack_optional(buf, &self.active, |buf, authority| authority.pack(buf));
+        pack_optional(buf, &self.posting, |buf, authority| authority.pack(buf));
+        pack_optional(buf, &self.memo_key, |buf, key| buf.extend_from_slice(&public_key_bytes(key)));
+        pack_string(buf, &self.json_metadata);
+        pack_string(buf, &self.posting_json_metadata);
+        // no extensions are defined, `validate` rejects any
+        write_varint(buf, 0);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        let op = AccountUpdate2Operation {
+            account: unpacker.string()?,
+            owner: unpack_optional(unpacker, Authority::unpack)?,
+            active: unpack_optional(unpacker, Authority::unpack)?,
+            posting: unpack_optional(unpacker, Authority::unpack)?,
+            memo_key: unpack_optional(unpacker, |unpacker| unpacker.public_key())?,
+            json_metadata: unpacker.string()?,
+            posting_json_metadata: unpacker.string()?,
+            extensions: vec![],
+        };
+
+        match unpacker.varint()? {
+            0 => Ok(op),
+            _ => Err(PackingError::UnsupportedExtension),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::operations::*;
+    use crate::hive::packing::{PackingError, Unpacker};
+    use crate::hive::types::Authority;
+    use crate::keys::{private::PrivateKey, public::PublicKey};
+    use serde_json::json;
+
+    fn key() -> String {
+        PrivateKey::from_login("alice", "password", "posting").to_public().to_string(None)
+    }
+
+    fn update() -> AccountUpdate2Operation {
+        AccountUpdate2Operation {
+            account: "alice".to_string(),
+            owner: None,
+            active: None,
+            posting: None,
+            memo_key: None,
+            json_metadata: String::new(),
+            posting_json_metadata: r#"{"profile":{"name":"Alice"}}"#.to_string(),
+            extensions: vec![],
+        }
+    }
+
+    #[test]
+    fn packs_metadata_update() {
+        let op = update();
+        let mut buf = vec![];
+        Operation::from(op.clone()).pack(&mut buf).unwrap();
+
+        let expected = [
+            vec![43, 5], b"alice".to_vec(), vec![0, 0, 0, 0, 0],
+            vec![op.posting_json_metadata.len() as u8], op.posting_json_metadata.as_bytes().to_vec(), vec![0],
+        ].concat();
+        assert_eq!(expected, buf);
+
+        *buf.last_mut().unwrap() = 1;
+        assert_eq!(Err(PackingError::UnsupportedExtension), Operation::unpack(&mut Unpacker::new(&buf)));
+    }
+
+    #[test]
+    fn packs_posting_update() {
+        let posting = Authority { weight_threshold: 1, account_auths: vec![("ecency.app".to_string(), 1)], key_auths: vec![(key(), 1)] };
+        let op = AccountUpdate2Operation { posting: Some(posting), posting_json_metadata: String::new(), ..update() };
+        let mut buf = vec![];
+        Operation::from(op).pack(&mut buf).unwrap();
+
+        let public = key().parse::<PublicKey>().unwrap().as_bytes().to_vec();
+        let expected = [
+            vec![43, 5], b"alice".to_vec(), vec![0, 0, 1],
+            vec![1, 0, 0, 0, 1, 10], b"ecency.app".to_vec(), vec![1, 0, 1], public, vec![1, 0],
+            vec![0, 0, 0, 0],
+        ].concat();
+        assert_eq!(expected, buf);
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let json = json!(["account_update2", {
+            "account": "alice", "json_metadata": "", "posting_json_metadata": r#"{"profile":{"name":"Alice"}}"#, "extensions": []
+        }]);
+        let op: Operation = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(Operation::AccountUpdate2(update()), op);
+        assert_eq!(json, serde_json::to_value(&op).unwrap());
+
+        let with_memo = Operation::from(AccountUpdate2Operation { memo_key: Some(key()), ..update() });
+        let json = serde_json::to_value(&with_memo).unwrap();
+        assert_eq!(key(), json[1]["memo_key"]);
+        assert_eq!(with_memo, serde_json::from_value(json).unwrap());
+    }
+
+    #[test]
+    fn updates_are_checked() {
+        assert!(update().validate().is_ok());
+
+        let locked = AccountUpdate2Operation {
+            posting: Some(Authority { weight_threshold: 2, account_auths: vec![], key_auths: vec![(key(), 1)] }),
+            ..update()
+        };
+        assert!(matches!(locked.validate(), Err(TransactionError::InvalidAuthority { field, .. }) if field == "posting"));
+
+        let bad_memo = AccountUpdate2Operation { memo_key: Some("STM1".to_string()), ..update() };
+        assert!(matches!(bad_memo.validate(), Err(TransactionError::InvalidValue { field, .. }) if field == "memo_key"));
+
+        let bad_metadata = AccountUpdate2Operation { posting_json_metadata: "{".to_string(), ..update() };
+        assert!(matches!(bad_metadata.validate(), Err(TransactionError::InvalidValue { field, .. }) if field == "posting_json_metadata"));
+
+        let extended = AccountUpdate2Operation { extensions: vec!["x".to_string()], ..update() };
+        assert!(matches!(extended.validate(), Err(TransactionError::UnsupportedExtension(_))));
+    }
+}
@@ -0,0 +1,147 @@
+use serde::{Serialize, Deserialize};
+use crate::hive::operations::{validate_account, validate_non_negative, validate_symbol, TypedOperation};
+use crate::hive::packing::{pack_string, PackingError, Unpacker};
+use crate::hive::transactions::TransactionError;
+use crate::hive::types::{AssetSymbol, HiveAccount, HiveAsset};
+
+/// Moves pending author and curation rewards into the account's balances,
+/// any amount up to what is pending can be claimed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClaimRewardBalanceOperation {
+    pub account: String,
+    #[serde(alias = "reward_steem")]
+    pub reward_hive: HiveAsset,
+    #[serde(alias = "reward_sbd")]
+    pub reward_hbd: HiveAsset,
+    pub reward_vests: HiveAsset,
+}
+
+impl ClaimRewardBalanceOperation {
+    /// Claims everything `account_data` has pending, as returned by
+    /// `get_accounts`
+    pub fn all_rewards(account: &str, account_data: &HiveAccount) -> Self {
+        ClaimRewardBalanceOperation {
+            account: account.to_string(),
+            reward_hive: account_data.reward_hive_balance,
+            reward_hbd: account_data.reward_hbd_balance,
+            reward_vests: account_data.reward_vesting_balance,
+        }
+    }
+}
+
+impl TypedOperation for ClaimRewardBalanceOperation {
+    const NAME: &'static str = "claim_reward_balance";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_account("account", &self.account)?;
+        validate_symbol("reward_hive", &self.reward_hive, &[AssetSymbol::Hive])?;
+        validate_symbol("reward_hbd", &self.reward_hbd, &[AssetSymbol::Hbd])?;
+        validate_symbol("reward_vests", &self.reward_vests, &[AssetSymbol::Vests])?;
+        validate_non_negative("reward_hive", &self.reward_hive)?;
+        validate_non_negative("reward_hbd", &self.reward_hbd)?;
+        validate_non_negative("reward_vests", &self.reward_vests)?;
+
+        if self.reward_hive.amount == 0 && self.reward_hbd.amount == 0 && self.reward_vests.amount == 0 {
+            return Err(TransactionError::InvalidValue {
+                field: "reward_vests".to_string(),
+                reason: "nothing to claim".to_string(),
+            })
+        }
+
+        Ok(())
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.account);
+        self.reward_hive.pack(buf);
+        self.reward_hbd.pack(buf);
+        self.reward_vests.pack(buf);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(ClaimRewardBalanceOperation {
+            account: unpacker.string()?,
+            reward_hive: unpacker.asset()?,
+            reward_hbd: unpacker.asset()?,
+            reward_vests: unpacker.asset()?,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::operations::*;
+    use crate::hive::types::HiveAccount;
+    use serde_json::json;
+
+    fn claim() -> ClaimRewardBalanceOperation {
+        ClaimRewardBalanceOperation {
+            account: "alice".to_string(),
+            reward_hive: HiveAsset::new(1, AssetSymbol::Hive),
+            reward_hbd: HiveAsset::new(0, AssetSymbol::Hbd),
+            reward_vests: HiveAsset::new(3000000, AssetSymbol::Vests),
+        }
+    }
+
+    #[test]
+    fn packs_claim() {
+        let mut buf = vec![];
+        Operation::from(claim()).pack(&mut buf).unwrap();
+
+        let expected = [
+            vec![39, 5], b"alice".to_vec(),
+            vec![1, 0, 0, 0, 0, 0, 0, 0, 3], b"STEEM\0\0".to_vec(),
+            vec![0, 0, 0, 0, 0, 0, 0, 0, 3], b"SBD\0\0\0\0".to_vec(),
+            vec![0xc0, 0xc6, 0x2d, 0, 0, 0, 0, 0, 6], b"VESTS\0\0".to_vec(),
+        ].concat();
+        assert_eq!(expected, buf);
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let json = json!(["claim_reward_balance", {
+            "account": "alice", "reward_hive": "0.001 HIVE", "reward_hbd": "0.000 HBD", "reward_vests": "3.000000 VESTS"
+        }]);
+        let op: Operation = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(Operation::ClaimRewardBalance(claim()), op);
+        #[cfg(not(feature = "nai"))]
+        assert_eq!(json, serde_json::to_value(&op).unwrap());
+    }
+
+    #[test]
+    fn claims_all_pending_rewards() {
+        let authority = json!({ "weight_threshold": 1, "account_auths": [], "key_auths": [] });
+        let account: HiveAccount = serde_json::from_value(json!({
+            "id": 1, "name": "alice", "owner": authority, "active": authority, "posting": authority,
+            "memo_key": "", "json_metadata": "", "posting_json_metadata": "", "proxy": "", "recovery_account": "",
+            "created": "2020-03-20T14:15:06", "post_count": 0,
+            "voting_manabar": { "current_mana": "0", "last_update_time": 0 },
+            "balance": "10.000 HIVE", "savings_balance": "0.000 HIVE", "hbd_balance": "1.234 HBD",
+            "savings_hbd_balance": "0.000 HBD", "vesting_shares": "20000.000000 VESTS",
+            "delegated_vesting_shares": "0.000000 VESTS", "received_vesting_shares": "0.000000 VESTS",
+            "reward_hive_balance": "0.001 HIVE", "reward_hbd_balance": "0.000 HBD", "reward_vesting_balance": "3.000000 VESTS"
+        })).unwrap();
+
+        assert_eq!(claim(), ClaimRewardBalanceOperation::all_rewards("alice", &account));
+    }
+
+    #[test]
+    fn claims_are_checked() {
+        assert!(claim().validate().is_ok());
+
+        let swapped = ClaimRewardBalanceOperation { reward_hbd: HiveAsset::new(1, AssetSymbol::Hive), ..claim() };
+        assert!(matches!(swapped.validate(), Err(TransactionError::InvalidValue { field, .. }) if field == "reward_hbd"));
+
+        let negative = ClaimRewardBalanceOperation { reward_hive: HiveAsset::new(-1, AssetSymbol::Hive), ..claim() };
+        assert!(negative.validate().is_err());
+
+        let nothing = ClaimRewardBalanceOperation {
+            reward_hive: HiveAsset::new(0, AssetSymbol::Hive),
+            reward_vests: HiveAsset::new(0, AssetSymbol::Vests),
+            ..claim()
+        };
+        assert!(nothing.validate().is_err());
+    }
+}
@@ -0,0 +1,297 @@
+use serde::{de, Serialize, Serializer, Deserialize, Deserializer};
+use serde_json::Value;
+use crate::hive::operations::{validate_account, validate_non_negative, validate_symbol, TypedOperation};
+use crate::hive::packing::{pack_string, write_varint, PackingError, Unpacker};
+use crate::hive::transactions::TransactionError;
+use crate::hive::types::{AssetSymbol, HiveAsset};
+
+/// The most beneficiaries the operation itself accepts, hived's
+/// `comment_payout_beneficiaries::validate` wants fewer than 128 so the count
+/// fits in one byte. The evaluator is stricter and currently allows only 8
+/// (`HIVE_MAX_COMMENT_BENEFICIARIES`), that limit is left to the node since
+/// it can change with a hardfork
+const MAX_BENEFICIARIES: usize = 127;
+
+/// Changes how a post pays out, it has to be sent before the post gets any
+/// votes and can only ever make the options more restrictive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommentOptionsOperation {
+    pub author: String,
+    pub permlink: String,
+    /// In HBD, `0.000 HBD` declines the payout
+    pub max_accepted_payout: HiveAsset,
+    /// The part of the author reward paid in HBD rather than Hive Power, in
+    /// hundredths of a percent
+    #[serde(alias = "percent_steem_dollars")]
+    pub percent_hbd: u16,
+    pub allow_votes: bool,
+    pub allow_curation_rewards: bool,
+    pub extensions: Vec<CommentOptionsExtension>,
+}
+
+/// An account receiving part of a post's author reward
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Beneficiary {
+    pub account: String,
+    /// In hundredths of a percent
+    pub weight: u16,
+}
+
+/// The extensions a `CommentOptionsOperation` can carry, serialized as
+/// `[0, {fields}]` with the variant's index first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommentOptionsExtension {
+    /// Sent as `comment_payout_beneficiaries`, the beneficiaries have to be
+    /// sorted by account name
+    CommentPayoutBeneficiaries { beneficiaries: Vec<Beneficiary> },
+}
+
+#[derive(Serialize, Deserialize)]
+struct BeneficiariesFields {
+    beneficiaries: Vec<Beneficiary>,
+}
+
+impl CommentOptionsExtension {
+    fn validate(&self) -> Result<(), TransactionError> {
+        let invalid = |reason: String| TransactionError::InvalidValue { field: "beneficiaries".to_string(), reason };
+
+        match self {
+            CommentOptionsExtension::CommentPayoutBeneficiaries { beneficiaries } => {
+                if beneficiaries.is_empty() || beneficiaries.len() > MAX_BENEFICIARIES {
+                    return Err(invalid(format!("must list 1 to {} accounts", MAX_BENEFICIARIES)))
+                }
+
+                for beneficiary in beneficiaries {
+                    validate_account("beneficiaries", &beneficiary.account)?;
+                }
+                if beneficiaries.windows(2).any(|pair| pair[0].account >= pair[1].account) {
+                    return Err(invalid("must be sorted by account with no repeats".to_string()))
+                }
+
+                let total: u32 = beneficiaries.iter().map(|beneficiary| beneficiary.weight as u32).sum();
+                if total > 10000 {
+                    return Err(invalid(format!("weights add up to {}, more than 10000 (100%)", total)))
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        match self {
+            CommentOptionsExtension::CommentPayoutBeneficiaries { beneficiaries } => {
+                write_varint(buf, 0);
+                write_varint(buf, beneficiaries.len() as u64);
+                for beneficiary in beneficiaries {
+                    pack_string(buf, &beneficiary.account);
+                    buf.extend_from_slice(&beneficiary.weight.to_le_bytes());
+                }
+            },
+        }
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        match unpacker.varint()? {
+            0 => {
+                let beneficiaries = (0..unpacker.count()?)
+                    .map(|_| Ok(Beneficiary { account: unpacker.string()?, weight: unpacker.u16()? }))
+                    .collect::<Result<_, PackingError>>()?;
+                Ok(CommentOptionsExtension::CommentPayoutBeneficiaries { beneficiaries })
+            },
+            _ => Err(PackingError::UnsupportedExtension),
+        }
+    }
+}
+
+impl Serialize for CommentOptionsExtension {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            CommentOptionsExtension::CommentPayoutBeneficiaries { beneficiaries } => {
+                (0, BeneficiariesFields { beneficiaries: beneficiaries.clone() }).serialize(serializer)
+            },
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CommentOptionsExtension {
+    /// Takes the variant as either its index or its name
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (variant, fields) = <(Value, Value)>::deserialize(deserializer)?;
+
+        match variant {
+            Value::Number(index) if index.as_u64() == Some(0) => {},
+            Value::String(name) if name == "comment_payout_beneficiaries" => {},
+            other => return Err(de::Error::custom(format!("unknown comment_options extension {}", other))),
+        }
+
+        let fields: BeneficiariesFields = serde_json::from_value(fields).map_err(de::Error::custom)?;
+        Ok(CommentOptionsExtension::CommentPayoutBeneficiaries { beneficiaries: fields.beneficiaries })
+    }
+}
+
+impl TypedOperation for CommentOptionsOperation {
+    const NAME: &'static str = "comment_options";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_account("author", &self.author)?;
+        validate_symbol("max_accepted_payout", &self.max_accepted_payout, &[AssetSymbol::Hbd])?;
+        validate_non_negative("max_accepted_payout", &self.max_accepted_payout)?;
+
+        if self.percent_hbd > 10000 {
+            return Err(TransactionError::InvalidValue {
+                field: "percent_hbd".to_string(),
+                reason: "must be at most 10000 (100%)".to_string(),
+            })
+        }
+
+        self.extensions.iter().try_for_each(CommentOptionsExtension::validate)
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.author);
+        pack_string(buf, &self.permlink);
+        self.max_accepted_payout.pack(buf);
+        buf.extend_from_slice(&self.percent_hbd.to_le_bytes());
+        buf.push(self.allow_votes as u8);
+        buf.push(self.allow_curation_rewards as u8);
+
+        write_varint(buf, self.extensions.len() as u64);
+        for extension in &self.extensions {
+            extension.pack(buf);
+        }
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(CommentOptionsOperation {
+            author: unpacker.string()?,
+            permlink: unpacker.string()?,
+            max_accepted_payout: unpacker.asset()?,
+            percent_hbd: unpacker.u16()?,
+            allow_votes: unpacker.bool()?,
+            allow_curation_rewards: unpacker.bool()?,
+            extensions: (0..unpacker.count()?).map(|_| CommentOptionsExtension::unpack(unpacker)).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::operations::*;
+    use crate::hive::packing::{PackingError, Unpacker};
+    use crate::hive::transactions::Transaction;
+    use serde_json::json;
+
+    fn options() -> CommentOptionsOperation {
+        CommentOptionsOperation {
+            author: "alice".to_string(),
+            permlink: "hello-world".to_string(),
+            max_accepted_payout: HiveAsset::new(1000000000, AssetSymbol::Hbd),
+            percent_hbd: 10000,
+            allow_votes: true,
+            allow_curation_rewards: true,
+            extensions: vec![CommentOptionsExtension::CommentPayoutBeneficiaries {
+                beneficiaries: vec![
+                    Beneficiary { account: "ecency".to_string(), weight: 300 },
+                    Beneficiary { account: "hive.fund".to_string(), weight: 500 },
+                ],
+            }],
+        }
+    }
+
+    #[test]
+    fn packs_beneficiaries() {
+        let mut buf = vec![];
+        Operation::from(options()).pack(&mut buf).unwrap();
+
+        let expected = [
+            vec![19, 5], b"alice".to_vec(), vec![11], b"hello-world".to_vec(),
+            1000000000i64.to_le_bytes().to_vec(), vec![3], b"SBD\0\0\0\0".to_vec(), vec![0x10, 0x27, 1, 1],
+            vec![1, 0, 2], vec![6], b"ecency".to_vec(), vec![0x2c, 0x01], vec![9], b"hive.fund".to_vec(), vec![0xf4, 0x01],
+        ].concat();
+        assert_eq!(expected, buf);
+    }
+
+    #[test]
+    fn unknown_extensions_are_rejected() {
+        let mut buf = vec![];
+        Operation::from(CommentOptionsOperation { extensions: vec![], ..options() }).pack(&mut buf).unwrap();
+        // one extension of type 1, allowed_vote_assets
+        buf.pop();
+        buf.extend_from_slice(&[1, 1]);
+
+        assert_eq!(Err(PackingError::UnsupportedExtension), Operation::unpack(&mut Unpacker::new(&buf)));
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let json = json!(["comment_options", {
+            "author": "alice", "permlink": "hello-world", "max_accepted_payout": "1000000.000 HBD", "percent_hbd": 10000,
+            "allow_votes": true, "allow_curation_rewards": true,
+            "extensions": [[0, { "beneficiaries": [{ "account": "ecency", "weight": 300 }, { "account": "hive.fund", "weight": 500 }] }]]
+        }]);
+        let op: Operation = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(Operation::CommentOptions(options()), op);
+        #[cfg(not(feature = "nai"))]
+        assert_eq!(json, serde_json::to_value(&op).unwrap());
+
+        let mut named = json;
+        named[1]["extensions"][0][0] = json!("comment_payout_beneficiaries");
+        assert_eq!(op, serde_json::from_value(named).unwrap());
+    }
+
+    #[test]
+    fn beneficiaries_are_checked() {
+        assert!(options().validate().is_ok());
+
+        let beneficiaries = |beneficiaries: &[(&str, u16)]| CommentOptionsOperation {
+            extensions: vec![CommentOptionsExtension::CommentPayoutBeneficiaries {
+                beneficiaries: beneficiaries.iter().map(|(account, weight)| Beneficiary { account: account.to_string(), weight: *weight }).collect(),
+            }],
+            ..options()
+        };
+
+        assert!(beneficiaries(&[("hive.fund", 500), ("ecency", 300)]).validate().is_err());
+        assert!(beneficiaries(&[("ecency", 300), ("ecency", 300)]).validate().is_err());
+        assert!(beneficiaries(&[("ecency", 6000), ("hive.fund", 5000)]).validate().is_err());
+        assert!(beneficiaries(&[]).validate().is_err());
+
+        let accounts: Vec<String> = (0..128).map(|i| format!("user{:03}", i)).collect();
+        let many = |count: usize| beneficiaries(&accounts[..count].iter().map(|account| (account.as_str(), 1)).collect::<Vec<_>>());
+        assert!(many(127).validate().is_ok());
+        assert!(many(128).validate().is_err());
+
+        let hive_payout = CommentOptionsOperation { max_accepted_payout: HiveAsset::new(0, AssetSymbol::Hive), ..options() };
+        assert!(hive_payout.validate().is_err());
+    }
+
+    #[test]
+    fn comment_with_options() {
+        let comment = json!({
+            "parent_author": "", "parent_permlink": "hive", "author": "alice", "permlink": "hello-world",
+            "title": "Hello world", "body": "First post", "json_metadata": "{}"
+        });
+        let tx: Transaction = serde_json::from_value(json!({
+            "ref_block_num": 34294, "ref_block_prefix": 3707022213u32, "expiration": "2024-01-01T00:01:00",
+            "operations": [], "extensions": []
+        })).unwrap();
+        let tx = tx
+            .add_operation(Operation::new("comment", comment.as_object().unwrap().clone()))
+            .add_operation(options());
+
+        tx.validate().unwrap();
+        let bytes = tx.to_bytes().unwrap();
+
+        let mut options_bytes = vec![];
+        Operation::from(options()).pack(&mut options_bytes).unwrap();
+        // header, operation count of 2, the comment, then the options and no extensions
+        assert_eq!(2, bytes[10]);
+        assert_eq!(1, bytes[11]);
+        assert!(bytes.ends_with(&[options_bytes, vec![0]].concat()));
+
+        let json = serde_json::to_value(&tx).unwrap();
+        assert_eq!(json!(["comment", "comment_options"]), json!([json["operations"][0][0], json["operations"][1][0]]));
+    }
+}
@@ -0,0 +1,124 @@
+use serde::{Serialize, Deserialize};
+use crate::hive::operations::{validate_account, validate_positive, validate_symbol, TypedOperation};
+use crate::hive::packing::{pack_string, PackingError, Unpacker};
+use crate::hive::transactions::TransactionError;
+use crate::hive::types::{AssetSymbol, HiveAsset};
+
+/// Converts HBD to HIVE at the median feed price, paid out after 3.5 days
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConvertOperation {
+    pub owner: String,
+    /// Must be unique among the owner's pending conversions, the unix time
+    /// modulo `u32::MAX` is the usual choice
+    pub requestid: u32,
+    pub amount: HiveAsset,
+}
+
+/// Converts HIVE to HBD right away, with part of the HIVE held as collateral
+/// for 3.5 days
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollateralizedConvertOperation {
+    pub owner: String,
+    /// Must be unique among the owner's pending conversions, the unix time
+    /// modulo `u32::MAX` is the usual choice
+    pub requestid: u32,
+    pub amount: HiveAsset,
+}
+
+impl TypedOperation for ConvertOperation {
+    const NAME: &'static str = "convert";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_account("owner", &self.owner)?;
+        validate_symbol("amount", &self.amount, &[AssetSymbol::Hbd])?;
+        validate_positive("amount", &self.amount)
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.owner);
+        buf.extend_from_slice(&self.requestid.to_le_bytes());
+        self.amount.pack(buf);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(ConvertOperation { owner: unpacker.string()?, requestid: unpacker.u32()?, amount: unpacker.asset()? })
+    }
+}
+
+impl TypedOperation for CollateralizedConvertOperation {
+    const NAME: &'static str = "collateralized_convert";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_account("owner", &self.owner)?;
+        validate_symbol("amount", &self.amount, &[AssetSymbol::Hive])?;
+        validate_positive("amount", &self.amount)
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.owner);
+        buf.extend_from_slice(&self.requestid.to_le_bytes());
+        self.amount.pack(buf);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(CollateralizedConvertOperation { owner: unpacker.string()?, requestid: unpacker.u32()?, amount: unpacker.asset()? })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::operations::*;
+    use serde_json::json;
+
+    fn convert() -> ConvertOperation {
+        ConvertOperation { owner: "alice".to_string(), requestid: 1700000000, amount: HiveAsset::new(5000, AssetSymbol::Hbd) }
+    }
+
+    #[test]
+    fn packs_convert() {
+        let mut buf = vec![];
+        Operation::from(convert()).pack(&mut buf).unwrap();
+
+        let expected = [
+            vec![8, 5], b"alice".to_vec(), 1700000000u32.to_le_bytes().to_vec(),
+            vec![0x88, 0x13, 0, 0, 0, 0, 0, 0, 3], b"SBD\0\0\0\0".to_vec(),
+        ].concat();
+        assert_eq!(expected, buf);
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let json = json!(["convert", { "owner": "alice", "requestid": 1700000000, "amount": "5.000 HBD" }]);
+        let op: Operation = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(Operation::Convert(convert()), op);
+        #[cfg(not(feature = "nai"))]
+        assert_eq!(json, serde_json::to_value(&op).unwrap());
+    }
+
+    #[test]
+    fn symbols_are_checked() {
+        assert!(convert().validate().is_ok());
+
+        let hive = HiveAsset::new(5000, AssetSymbol::Hive);
+        assert!(matches!(
+            ConvertOperation { amount: hive, ..convert() }.validate(),
+            Err(TransactionError::InvalidValue { field, .. }) if field == "amount"
+        ));
+        assert!(ConvertOperation { amount: HiveAsset::new(0, AssetSymbol::Hbd), ..convert() }.validate().is_err());
+
+        let collateralized = CollateralizedConvertOperation { owner: "alice".to_string(), requestid: 2, amount: hive };
+        assert!(collateralized.validate().is_ok());
+        assert!(CollateralizedConvertOperation { amount: convert().amount, ..collateralized }.validate().is_err());
+    }
+
+    #[test]
+    fn malformed_typed_json_stays_raw() {
+        let op = Operation::new("convert", json!({ "owner": "alice" }).as_object().unwrap().clone());
+
+        assert!(matches!(op, Operation::Raw(..)));
+        assert!(matches!(op.validate(), Err(TransactionError::InvalidField(_))));
+        assert!(matches!(op.pack(&mut vec![]), Err(TransactionError::InvalidField(_))));
+    }
+}
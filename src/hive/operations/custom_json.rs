@@ -0,0 +1,158 @@
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use crate::hive::operations::{validate_account, TypedOperation};
+use crate::hive::packing::{pack_string, write_varint, PackingError, Unpacker};
+use crate::hive::transactions::TransactionError;
+
+/// The longest `id` hived accepts
+const MAX_ID_LENGTH: usize = 32;
+
+/// Json for second layer apps such as hivemind and games, the chain only checks
+/// that it is valid json signed by the listed accounts. Apps tell their
+/// operations apart by `id`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomJsonOperation {
+    /// Accounts signing with their active authority
+    pub required_auths: Vec<String>,
+    /// Accounts signing with their posting authority
+    pub required_posting_auths: Vec<String>,
+    pub id: String,
+    pub json: String,
+}
+
+impl CustomJsonOperation {
+    /// A custom json signed with `account`'s posting authority, which is all
+    /// most apps ask for
+    /// ```
+    /// use serde_json::json;
+    /// use tetanus::hive::operations::CustomJsonOperation;
+    /// let op = CustomJsonOperation::posting("alice", "myapp", &json!({ "action": "like" }));
+    /// assert_eq!(vec!["alice"], op.required_posting_auths);
+    /// assert_eq!(r#"{"action":"like"}"#, op.json)
+    /// ```
+    pub fn posting(account: &str, id: &str, json: &Value) -> Self {
+        CustomJsonOperation {
+            required_auths: vec![],
+            required_posting_auths: vec![account.to_string()],
+            id: id.to_string(),
+            json: json.to_string(),
+        }
+    }
+
+    /// A custom json signed with `account`'s active authority, for apps that
+    /// move funds such as Hive Engine
+    pub fn active(account: &str, id: &str, json: &Value) -> Self {
+        CustomJsonOperation {
+            required_auths: vec![account.to_string()],
+            required_posting_auths: vec![],
+            id: id.to_string(),
+            json: json.to_string(),
+        }
+    }
+}
+
+fn pack_names(buf: &mut Vec<u8>, names: &[String]) {
+    write_varint(buf, names.len() as u64);
+    for name in names {
+        pack_string(buf, name);
+    }
+}
+
+fn unpack_names(unpacker: &mut Unpacker) -> Result<Vec<String>, PackingError> {
+    (0..unpacker.count()?).map(|_| unpacker.string()).collect()
+}
+
+impl TypedOperation for CustomJsonOperation {
+    const NAME: &'static str = "custom_json";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        if self.required_auths.is_empty() && self.required_posting_auths.is_empty() {
+            return Err(TransactionError::InvalidValue {
+                field: "required_posting_auths".to_string(),
+                reason: "at least one account has to sign".to_string(),
+            })
+        }
+        self.required_auths.iter().try_for_each(|name| validate_account("required_auths", name))?;
+        self.required_posting_auths.iter().try_for_each(|name| validate_account("required_posting_auths", name))?;
+
+        if self.id.len() > MAX_ID_LENGTH {
+            return Err(TransactionError::InvalidValue {
+                field: "id".to_string(),
+                reason: format!("must be at most {} bytes", MAX_ID_LENGTH),
+            })
+        }
+        if serde_json::from_str::<Value>(&self.json).is_err() {
+            return Err(TransactionError::InvalidValue { field: "json".to_string(), reason: "must be valid json".to_string() })
+        }
+
+        Ok(())
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_names(buf, &self.required_auths);
+        pack_names(buf, &self.required_posting_auths);
+        pack_string(buf, &self.id);
+        pack_string(buf, &self.json);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(CustomJsonOperation {
+            required_auths: unpack_names(unpacker)?,
+            required_posting_auths: unpack_names(unpacker)?,
+            id: unpacker.string()?,
+            json: unpacker.string()?,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::operations::*;
+    use serde_json::json;
+
+    fn follow() -> CustomJsonOperation {
+        CustomJsonOperation::posting("alice", "follow", &json!(["follow", { "follower": "alice", "following": "bob", "what": ["blog"] }]))
+    }
+
+    #[test]
+    fn packs_custom_json() {
+        let op = follow();
+        let mut buf = vec![];
+        Operation::from(op.clone()).pack(&mut buf).unwrap();
+
+        let expected = [
+            vec![18, 0, 1, 5], b"alice".to_vec(), vec![6], b"follow".to_vec(),
+            vec![op.json.len() as u8], op.json.as_bytes().to_vec(),
+        ].concat();
+        assert_eq!(expected, buf);
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let json = json!(["custom_json", { "required_auths": [], "required_posting_auths": ["alice"], "id": "follow", "json": follow().json }]);
+        let op: Operation = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(Operation::CustomJson(follow()), op);
+        assert_eq!(json, serde_json::to_value(&op).unwrap());
+    }
+
+    #[test]
+    fn custom_json_is_checked() {
+        assert!(follow().validate().is_ok());
+        assert!(CustomJsonOperation { required_auths: vec!["bob".to_string()], required_posting_auths: vec![], ..follow() }.validate().is_ok());
+
+        let unsigned = CustomJsonOperation { required_posting_auths: vec![], ..follow() };
+        assert!(matches!(unsigned.validate(), Err(TransactionError::InvalidValue { field, .. }) if field == "required_posting_auths"));
+
+        let invalid_account = CustomJsonOperation { required_auths: vec!["Bob".to_string()], ..follow() };
+        assert!(matches!(invalid_account.validate(), Err(TransactionError::InvalidAccountName { field, .. }) if field == "required_auths"));
+
+        let long_id = CustomJsonOperation { id: "x".repeat(33), ..follow() };
+        assert!(matches!(long_id.validate(), Err(TransactionError::InvalidValue { field, .. }) if field == "id"));
+        assert!(CustomJsonOperation { id: "x".repeat(32), ..follow() }.validate().is_ok());
+
+        let invalid_json = CustomJsonOperation { json: "{\"follow\":".to_string(), ..follow() };
+        assert!(matches!(invalid_json.validate(), Err(TransactionError::InvalidValue { field, .. }) if field == "json"));
+    }
+}
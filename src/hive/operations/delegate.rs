@@ -0,0 +1,121 @@
+use serde::{Serialize, Deserialize};
+use crate::hive::operations::{validate_account, validate_non_negative, validate_symbol, TypedOperation};
+use crate::hive::packing::{pack_string, PackingError, Unpacker};
+use crate::hive::transactions::TransactionError;
+use crate::hive::types::{AssetSymbol, HiveAsset};
+
+/// Lends Hive Power to another account. The amount replaces any existing
+/// delegation between the two rather than adding to it, and shares taken back
+/// return to the delegator after five days
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegateVestingSharesOperation {
+    pub delegator: String,
+    pub delegatee: String,
+    pub vesting_shares: HiveAsset,
+}
+
+impl DelegateVestingSharesOperation {
+    /// Takes back everything `delegator` has delegated to `delegatee`
+    pub fn remove_delegation(delegator: &str, delegatee: &str) -> Self {
+        DelegateVestingSharesOperation {
+            delegator: delegator.to_string(),
+            delegatee: delegatee.to_string(),
+            vesting_shares: HiveAsset::new(0, AssetSymbol::Vests),
+        }
+    }
+}
+
+impl TypedOperation for DelegateVestingSharesOperation {
+    const NAME: &'static str = "delegate_vesting_shares";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_account("delegator", &self.delegator)?;
+        validate_account("delegatee", &self.delegatee)?;
+        if self.delegator == self.delegatee {
+            return Err(TransactionError::InvalidValue {
+                field: "delegatee".to_string(),
+                reason: "must not be the delegator".to_string(),
+            })
+        }
+
+        validate_symbol("vesting_shares", &self.vesting_shares, &[AssetSymbol::Vests])?;
+        validate_non_negative("vesting_shares", &self.vesting_shares)
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.delegator);
+        pack_string(buf, &self.delegatee);
+        self.vesting_shares.pack(buf);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(DelegateVestingSharesOperation {
+            delegator: unpacker.string()?,
+            delegatee: unpacker.string()?,
+            vesting_shares: unpacker.asset()?,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::operations::*;
+    use serde_json::json;
+
+    fn delegation() -> DelegateVestingSharesOperation {
+        DelegateVestingSharesOperation {
+            delegator: "alice".to_string(),
+            delegatee: "bob".to_string(),
+            vesting_shares: HiveAsset::new(2000000000, AssetSymbol::Vests),
+        }
+    }
+
+    #[test]
+    fn packs_delegation() {
+        let mut buf = vec![];
+        Operation::from(delegation()).pack(&mut buf).unwrap();
+
+        let expected = [
+            vec![40, 5], b"alice".to_vec(), vec![3], b"bob".to_vec(),
+            2000000000i64.to_le_bytes().to_vec(), vec![6], b"VESTS\0\0".to_vec(),
+        ].concat();
+        assert_eq!(expected, buf);
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let json = json!(["delegate_vesting_shares", { "delegator": "alice", "delegatee": "bob", "vesting_shares": "2000.000000 VESTS" }]);
+        let op: Operation = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(Operation::DelegateVestingShares(delegation()), op);
+        #[cfg(not(feature = "nai"))]
+        assert_eq!(json, serde_json::to_value(&op).unwrap());
+    }
+
+    #[test]
+    fn delegations_are_checked() {
+        assert!(delegation().validate().is_ok());
+
+        let hive = DelegateVestingSharesOperation { vesting_shares: HiveAsset::new(1000, AssetSymbol::Hive), ..delegation() };
+        assert!(matches!(hive.validate(), Err(TransactionError::InvalidValue { field, .. }) if field == "vesting_shares"));
+
+        let negative = DelegateVestingSharesOperation { vesting_shares: HiveAsset::new(-1, AssetSymbol::Vests), ..delegation() };
+        assert!(negative.validate().is_err());
+
+        let to_self = DelegateVestingSharesOperation { delegatee: "alice".to_string(), ..delegation() };
+        assert!(to_self.validate().is_err());
+    }
+
+    #[test]
+    fn removes_delegation() {
+        let removal = DelegateVestingSharesOperation::remove_delegation("alice", "bob");
+
+        assert_eq!(DelegateVestingSharesOperation { vesting_shares: HiveAsset::new(0, AssetSymbol::Vests), ..delegation() }, removal);
+        assert!(removal.validate().is_ok());
+
+        let mut buf = vec![];
+        Operation::from(removal).pack(&mut buf).unwrap();
+        assert_eq!(&[0, 0, 0, 0, 0, 0, 0, 0, 6], &buf[buf.len() - 16..buf.len() - 7]);
+    }
+}
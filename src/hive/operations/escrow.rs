@@ -0,0 +1,317 @@
+use serde::{Serialize, Deserialize};
+use crate::hive::operations::{pack_time, validate_account, validate_non_negative, validate_symbol, validate_time, TypedOperation};
+use crate::hive::packing::{pack_string, PackingError, Unpacker};
+use crate::hive::transactions::TransactionError;
+use crate::hive::types::{AssetSymbol, HiveAsset};
+
+/// Locks HBD and/or HIVE with an agent until the receiver and the agent
+/// approve, either party can dispute it before it is released
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscrowTransferOperation {
+    pub from: String,
+    pub to: String,
+    pub agent: String,
+    /// Chosen by `from`, unique among their open escrows
+    pub escrow_id: u32,
+    #[serde(alias = "sbd_amount")]
+    pub hbd_amount: HiveAsset,
+    #[serde(alias = "steem_amount")]
+    pub hive_amount: HiveAsset,
+    /// Paid to the agent once they approve, in HIVE or HBD
+    pub fee: HiveAsset,
+    /// `YYYY-MM-DDTHH:MM:SS` time by which `to` and `agent` must approve
+    pub ratification_deadline: String,
+    /// `YYYY-MM-DDTHH:MM:SS` time after which either party can release
+    pub escrow_expiration: String,
+    pub json_meta: String,
+}
+
+/// Approves (or rejects) an escrow, sent by its receiver or agent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscrowApproveOperation {
+    pub from: String,
+    pub to: String,
+    pub agent: String,
+    pub who: String,
+    pub escrow_id: u32,
+    pub approve: bool,
+}
+
+/// Hands an escrow over to its agent, sent by its sender or receiver
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscrowDisputeOperation {
+    pub from: String,
+    pub to: String,
+    pub agent: String,
+    pub who: String,
+    pub escrow_id: u32,
+}
+
+/// Releases some or all of an escrow to `receiver`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscrowReleaseOperation {
+    pub from: String,
+    pub to: String,
+    pub agent: String,
+    pub who: String,
+    /// Either `from` or `to`
+    pub receiver: String,
+    pub escrow_id: u32,
+    #[serde(alias = "sbd_amount")]
+    pub hbd_amount: HiveAsset,
+    #[serde(alias = "steem_amount")]
+    pub hive_amount: HiveAsset,
+}
+
+fn invalid(field: &str, reason: &str) -> TransactionError {
+    TransactionError::InvalidValue { field: field.to_string(), reason: reason.to_string() }
+}
+
+fn validate_parties(from: &str, to: &str, agent: &str) -> Result<(), TransactionError> {
+    validate_account("from", from)?;
+    validate_account("to", to)?;
+    validate_account("agent", agent)?;
+
+    if agent == from || agent == to {
+        return Err(invalid("agent", "must be neither from nor to"))
+    }
+
+    Ok(())
+}
+
+/// The escrowed amounts must be in their own symbols, not negative, and not both
+/// zero
+fn validate_amounts(hbd_amount: &HiveAsset, hive_amount: &HiveAsset) -> Result<(), TransactionError> {
+    validate_symbol("hbd_amount", hbd_amount, &[AssetSymbol::Hbd])?;
+    validate_symbol("hive_amount", hive_amount, &[AssetSymbol::Hive])?;
+
+    validate_non_negative("hbd_amount", hbd_amount)?;
+    validate_non_negative("hive_amount", hive_amount)?;
+    if hbd_amount.amount == 0 && hive_amount.amount == 0 {
+        return Err(invalid("hive_amount", "hbd_amount and hive_amount must not both be zero"))
+    }
+
+    Ok(())
+}
+
+impl TypedOperation for EscrowTransferOperation {
+    const NAME: &'static str = "escrow_transfer";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_parties(&self.from, &self.to, &self.agent)?;
+        validate_amounts(&self.hbd_amount, &self.hive_amount)?;
+        validate_symbol("fee", &self.fee, &[AssetSymbol::Hive, AssetSymbol::Hbd])?;
+        validate_non_negative("fee", &self.fee)?;
+
+        let ratification_deadline = validate_time("ratification_deadline", &self.ratification_deadline)?;
+        let escrow_expiration = validate_time("escrow_expiration", &self.escrow_expiration)?;
+        if ratification_deadline >= escrow_expiration {
+            return Err(invalid("ratification_deadline", "must be before escrow_expiration"))
+        }
+
+        if !self.json_meta.is_empty() && serde_json::from_str::<serde_json::Value>(&self.json_meta).is_err() {
+            return Err(invalid("json_meta", "must be empty or json"))
+        }
+
+        Ok(())
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.from);
+        pack_string(buf, &self.to);
+        self.hbd_amount.pack(buf);
+        self.hive_amount.pack(buf);
+        buf.extend_from_slice(&self.escrow_id.to_le_bytes());
+        pack_string(buf, &self.agent);
+        self.fee.pack(buf);
+        pack_string(buf, &self.json_meta);
+        pack_time(buf, &self.ratification_deadline);
+        pack_time(buf, &self.escrow_expiration);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        let (from, to) = (unpacker.string()?, unpacker.string()?);
+        let (hbd_amount, hive_amount) = (unpacker.asset()?, unpacker.asset()?);
+        let (escrow_id, agent, fee) = (unpacker.u32()?, unpacker.string()?, unpacker.asset()?);
+        let json_meta = unpacker.string()?;
+        let (ratification_deadline, escrow_expiration) = (unpacker.time()?, unpacker.time()?);
+
+        Ok(EscrowTransferOperation {
+            from, to, agent, escrow_id, hbd_amount, hive_amount, fee, ratification_deadline, escrow_expiration, json_meta,
+        })
+    }
+}
+
+impl TypedOperation for EscrowApproveOperation {
+    const NAME: &'static str = "escrow_approve";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_parties(&self.from, &self.to, &self.agent)?;
+
+        if self.who != self.to && self.who != self.agent {
+            return Err(invalid("who", "must be to or agent"))
+        }
+
+        Ok(())
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.from);
+        pack_string(buf, &self.to);
+        pack_string(buf, &self.agent);
+        pack_string(buf, &self.who);
+        buf.extend_from_slice(&self.escrow_id.to_le_bytes());
+        buf.push(self.approve as u8);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(EscrowApproveOperation {
+            from: unpacker.string()?,
+            to: unpacker.string()?,
+            agent: unpacker.string()?,
+            who: unpacker.string()?,
+            escrow_id: unpacker.u32()?,
+            approve: unpacker.bool()?,
+        })
+    }
+}
+
+impl TypedOperation for EscrowDisputeOperation {
+    const NAME: &'static str = "escrow_dispute";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_parties(&self.from, &self.to, &self.agent)?;
+
+        if self.who != self.from && self.who != self.to {
+            return Err(invalid("who", "must be from or to"))
+        }
+
+        Ok(())
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.from);
+        pack_string(buf, &self.to);
+        pack_string(buf, &self.agent);
+        pack_string(buf, &self.who);
+        buf.extend_from_slice(&self.escrow_id.to_le_bytes());
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(EscrowDisputeOperation {
+            from: unpacker.string()?,
+            to: unpacker.string()?,
+            agent: unpacker.string()?,
+            who: unpacker.string()?,
+            escrow_id: unpacker.u32()?,
+        })
+    }
+}
+
+impl TypedOperation for EscrowReleaseOperation {
+    const NAME: &'static str = "escrow_release";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_parties(&self.from, &self.to, &self.agent)?;
+        validate_amounts(&self.hbd_amount, &self.hive_amount)?;
+
+        if self.who != self.from && self.who != self.to && self.who != self.agent {
+            return Err(invalid("who", "must be from, to or agent"))
+        }
+        if self.receiver != self.from && self.receiver != self.to {
+            return Err(invalid("receiver", "must be from or to"))
+        }
+
+        Ok(())
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.from);
+        pack_string(buf, &self.to);
+        pack_string(buf, &self.agent);
+        pack_string(buf, &self.who);
+        pack_string(buf, &self.receiver);
+        buf.extend_from_slice(&self.escrow_id.to_le_bytes());
+        self.hbd_amount.pack(buf);
+        self.hive_amount.pack(buf);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(EscrowReleaseOperation {
+            from: unpacker.string()?,
+            to: unpacker.string()?,
+            agent: unpacker.string()?,
+            who: unpacker.string()?,
+            receiver: unpacker.string()?,
+            escrow_id: unpacker.u32()?,
+            hbd_amount: unpacker.asset()?,
+            hive_amount: unpacker.asset()?,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::operations::*;
+
+    fn transfer() -> EscrowTransferOperation {
+        EscrowTransferOperation {
+            from: "alice".to_string(),
+            to: "bob".to_string(),
+            agent: "carol".to_string(),
+            escrow_id: 72526562,
+            hbd_amount: HiveAsset::new(1000, AssetSymbol::Hbd),
+            hive_amount: HiveAsset::new(0, AssetSymbol::Hive),
+            fee: HiveAsset::new(10, AssetSymbol::Hbd),
+            ratification_deadline: "2024-01-02T00:00:00".to_string(),
+            escrow_expiration: "2024-01-09T00:00:00".to_string(),
+            json_meta: String::new(),
+        }
+    }
+
+    #[test]
+    fn packs_transfer() {
+        let mut buf = vec![];
+        Operation::from(transfer()).pack(&mut buf).unwrap();
+
+        let asset = |amount: i64, symbol: &[u8]| [&amount.to_le_bytes()[..], &[3], symbol].concat();
+        let expected = [
+            vec![27, 5], b"alice".to_vec(), vec![3], b"bob".to_vec(),
+            asset(1000, b"SBD\0\0\0\0"), asset(0, b"STEEM\0\0"),
+            72526562u32.to_le_bytes().to_vec(), vec![5], b"carol".to_vec(), asset(10, b"SBD\0\0\0\0"), vec![0],
+            1704153600u32.to_le_bytes().to_vec(), 1704758400u32.to_le_bytes().to_vec(),
+        ].concat();
+        assert_eq!(expected, buf);
+    }
+
+    #[test]
+    fn transfer_rules() {
+        assert!(transfer().validate().is_ok());
+
+        let late = EscrowTransferOperation { ratification_deadline: "2024-01-10T00:00:00".to_string(), ..transfer() };
+        assert!(matches!(late.validate(), Err(TransactionError::InvalidValue { field, .. }) if field == "ratification_deadline"));
+
+        let negative = EscrowTransferOperation { hive_amount: HiveAsset::new(-1, AssetSymbol::Hive), ..transfer() };
+        assert!(negative.validate().is_err());
+
+        let empty = EscrowTransferOperation { hbd_amount: HiveAsset::new(0, AssetSymbol::Hbd), ..transfer() };
+        assert!(empty.validate().is_err());
+
+        let swapped = EscrowTransferOperation { hbd_amount: HiveAsset::new(1000, AssetSymbol::Hive), ..transfer() };
+        assert!(swapped.validate().is_err());
+
+        let own_agent = EscrowTransferOperation { agent: "alice".to_string(), ..transfer() };
+        assert!(own_agent.validate().is_err());
+    }
+
+    #[test]
+    fn legacy_field_names() {
+        let json = serde_json::json!(["escrow_release", {
+            "from": "alice", "to": "bob", "agent": "carol", "who": "carol", "receiver": "bob", "escrow_id": 1,
+            "sbd_amount": "1.000 HBD", "steem_amount": "0.000 HIVE"
+        }]);
+
+        assert!(matches!(serde_json::from_value(json).unwrap(), Operation::EscrowRelease(_)));
+    }
+}
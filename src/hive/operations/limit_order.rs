@@ -0,0 +1,154 @@
+use serde::{Serialize, Deserialize};
+use crate::hive::operations::{pack_time, validate_account, validate_positive, validate_symbol, validate_time, TypedOperation};
+use crate::hive::packing::{pack_string, PackingError, Unpacker};
+use crate::hive::transactions::TransactionError;
+use crate::hive::types::{AssetSymbol, HiveAsset};
+
+/// Offers `amount_to_sell` on the internal market at a price of at least
+/// `min_to_receive`, trading HIVE for HBD or the other way around
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitOrderCreateOperation {
+    pub owner: String,
+    /// Must be unique among the owner's open orders, used to cancel it
+    pub orderid: u32,
+    pub amount_to_sell: HiveAsset,
+    pub min_to_receive: HiveAsset,
+    /// Cancels the order unless it fills completely right away
+    pub fill_or_kill: bool,
+    /// `YYYY-MM-DDTHH:MM:SS` time the order is cancelled at, no more than 28
+    /// days out
+    pub expiration: String,
+}
+
+/// Cancels an open order, returning what is left of it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitOrderCancelOperation {
+    pub owner: String,
+    pub orderid: u32,
+}
+
+impl TypedOperation for LimitOrderCreateOperation {
+    const NAME: &'static str = "limit_order_create";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_account("owner", &self.owner)?;
+        validate_symbol("amount_to_sell", &self.amount_to_sell, &[AssetSymbol::Hive, AssetSymbol::Hbd])?;
+        validate_symbol("min_to_receive", &self.min_to_receive, &[AssetSymbol::Hive, AssetSymbol::Hbd])?;
+
+        if self.amount_to_sell.symbol == self.min_to_receive.symbol {
+            return Err(TransactionError::InvalidValue {
+                field: "min_to_receive".to_string(),
+                reason: format!("must not be {} like amount_to_sell", self.min_to_receive.symbol.as_str()),
+            })
+        }
+
+        validate_positive("amount_to_sell", &self.amount_to_sell)?;
+        validate_positive("min_to_receive", &self.min_to_receive)?;
+        validate_time("expiration", &self.expiration).map(|_| ())
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.owner);
+        buf.extend_from_slice(&self.orderid.to_le_bytes());
+        self.amount_to_sell.pack(buf);
+        self.min_to_receive.pack(buf);
+        buf.push(self.fill_or_kill as u8);
+        pack_time(buf, &self.expiration);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(LimitOrderCreateOperation {
+            owner: unpacker.string()?,
+            orderid: unpacker.u32()?,
+            amount_to_sell: unpacker.asset()?,
+            min_to_receive: unpacker.asset()?,
+            fill_or_kill: unpacker.bool()?,
+            expiration: unpacker.time()?,
+        })
+    }
+}
+
+impl TypedOperation for LimitOrderCancelOperation {
+    const NAME: &'static str = "limit_order_cancel";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_account("owner", &self.owner)
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.owner);
+        buf.extend_from_slice(&self.orderid.to_le_bytes());
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(LimitOrderCancelOperation { owner: unpacker.string()?, orderid: unpacker.u32()? })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::operations::*;
+    use serde_json::json;
+
+    fn order() -> LimitOrderCreateOperation {
+        LimitOrderCreateOperation {
+            owner: "alice".to_string(),
+            orderid: 1,
+            amount_to_sell: HiveAsset::new(10000, AssetSymbol::Hive),
+            min_to_receive: HiveAsset::new(3000, AssetSymbol::Hbd),
+            fill_or_kill: false,
+            expiration: "2024-01-28T00:00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn packs_orders() {
+        let mut buf = vec![];
+        Operation::from(order()).pack(&mut buf).unwrap();
+        let expected = [
+            vec![5, 5], b"alice".to_vec(), vec![1, 0, 0, 0],
+            10000i64.to_le_bytes().to_vec(), vec![3], b"STEEM\0\0".to_vec(),
+            3000i64.to_le_bytes().to_vec(), vec![3], b"SBD\0\0\0\0".to_vec(),
+            vec![0], 1706400000u32.to_le_bytes().to_vec(),
+        ].concat();
+        assert_eq!(expected, buf);
+
+        let mut buf = vec![];
+        Operation::from(LimitOrderCancelOperation { owner: "alice".to_string(), orderid: 1 }).pack(&mut buf).unwrap();
+        assert_eq!([vec![6, 5], b"alice".to_vec(), vec![1, 0, 0, 0]].concat(), buf);
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let json = json!(["limit_order_create", {
+            "owner": "alice", "orderid": 1, "amount_to_sell": "10.000 HIVE", "min_to_receive": "3.000 HBD",
+            "fill_or_kill": false, "expiration": "2024-01-28T00:00:00"
+        }]);
+        let op: Operation = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(Operation::LimitOrderCreate(order()), op);
+        #[cfg(not(feature = "nai"))]
+        assert_eq!(json, serde_json::to_value(&op).unwrap());
+    }
+
+    #[test]
+    fn orders_trade_hive_for_hbd() {
+        assert!(order().validate().is_ok());
+
+        let reversed = LimitOrderCreateOperation { amount_to_sell: order().min_to_receive, min_to_receive: order().amount_to_sell, ..order() };
+        assert!(reversed.validate().is_ok());
+
+        let same = LimitOrderCreateOperation { min_to_receive: HiveAsset::new(3000, AssetSymbol::Hive), ..order() };
+        assert!(matches!(same.validate(), Err(TransactionError::InvalidValue { field, .. }) if field == "min_to_receive"));
+
+        let vests = LimitOrderCreateOperation { amount_to_sell: HiveAsset::new(1000000, AssetSymbol::Vests), ..order() };
+        assert!(vests.validate().is_err());
+
+        let free = LimitOrderCreateOperation { min_to_receive: HiveAsset::new(0, AssetSymbol::Hbd), ..order() };
+        assert!(free.validate().is_err());
+
+        let no_expiration = LimitOrderCreateOperation { expiration: String::new(), ..order() };
+        assert!(no_expiration.validate().is_err());
+    }
+}
@@ -0,0 +1,594 @@
+//! Hive operations. The ones with a typed struct are checked and packed from
+//! their fields, anything else is kept as `Operation::Raw` json and packed from
+//! the field table below as far as it is known.
+use std::fmt;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::{self, DeserializeOwned, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Value};
+use crate::hive::packing::{pack_string, write_varint, PackingError, Unpacker};
+use crate::hive::transactions::{parse_time, TransactionError};
+use crate::hive::types::{AssetSymbol, HiveAsset};
+use crate::hive::utils::validate_account_name;
+
+mod account_create;
+mod account_update;
+mod account_update2;
+mod claim_reward;
+mod comment_options;
+mod convert;
+mod custom_json;
+mod delegate;
+mod escrow;
+mod limit_order;
+mod recovery;
+mod savings;
+mod vesting;
+mod vesting_route;
+mod witness_update;
+mod witness_vote;
+
+pub use account_create::AccountCreateOperation;
+pub use account_update::AccountUpdateOperation;
+pub use account_update2::AccountUpdate2Operation;
+pub use claim_reward::ClaimRewardBalanceOperation;
+pub use comment_options::{Beneficiary, CommentOptionsExtension, CommentOptionsOperation};
+pub use convert::{CollateralizedConvertOperation, ConvertOperation};
+pub use custom_json::CustomJsonOperation;
+pub use delegate::DelegateVestingSharesOperation;
+pub use escrow::{EscrowApproveOperation, EscrowDisputeOperation, EscrowReleaseOperation, EscrowTransferOperation};
+pub use limit_order::{LimitOrderCancelOperation, LimitOrderCreateOperation};
+pub use recovery::{ChangeRecoveryAccountOperation, RecoverAccountOperation};
+pub use savings::{TransferFromSavingsOperation, TransferToSavingsOperation};
+pub use vesting::{TransferToVestingOperation, WithdrawVestingOperation};
+pub use vesting_route::{SetWithdrawVestingRouteOperation, WithdrawVestingRouteBuilder};
+pub use witness_update::{ChainProperties, WitnessUpdateOperation};
+pub use witness_vote::{AccountWitnessProxyOperation, AccountWitnessVoteOperation};
+
+/// Every non virtual operation, in protocol order
+pub(crate) const OPERATION_NAMES: [&str; 50] = [
+    "vote", "comment", "transfer", "transfer_to_vesting", "withdraw_vesting",
+    "limit_order_create", "limit_order_cancel", "feed_publish", "convert", "account_create",
+    "account_update", "witness_update", "account_witness_vote", "account_witness_proxy", "pow",
+    "custom", "report_over_production", "delete_comment", "custom_json", "comment_options",
+    "set_withdraw_vesting_route", "limit_order_create2", "claim_account", "create_claimed_account", "request_account_recovery",
+    "recover_account", "change_recovery_account", "escrow_transfer", "escrow_dispute", "escrow_release",
+    "pow2", "escrow_approve", "transfer_to_savings", "transfer_from_savings", "cancel_transfer_from_savings",
+    "custom_binary", "decline_voting_rights", "reset_account", "set_reset_account", "claim_reward_balance",
+    "delegate_vesting_shares", "account_create_with_delegation", "witness_set_properties", "account_update2", "create_proposal",
+    "update_proposal_votes", "remove_proposal", "update_proposal", "collateralized_convert", "recurrent_transfer",
+];
+
+/// Operation fields that always hold an account name
+const ACCOUNT_FIELDS: [&str; 14] = [
+    "voter", "author", "from", "to", "account", "owner", "creator", "new_account_name",
+    "delegator", "delegatee", "agent", "who", "from_account", "to_account",
+];
+
+/// `OPERATION_NAMES.contains(&name)` for const contexts
+const fn is_operation_name(name: &str) -> bool {
+    let mut i = 0;
+    while i < OPERATION_NAMES.len() {
+        let candidate = OPERATION_NAMES[i].as_bytes();
+        if candidate.len() == name.len() {
+            let mut j = 0;
+            while j < candidate.len() && candidate[j] == name.as_bytes()[j] {
+                j += 1;
+            }
+            if j == candidate.len() {
+                return true
+            }
+        }
+        i += 1;
+    }
+
+    false
+}
+
+/// The binary type of an operation field
+#[derive(Clone, Copy)]
+enum FieldType { String, Asset, Int16, Uint32 }
+
+/// The fields of the operations that can be packed, in wire order
+fn operation_fields(name: &str) -> Option<&'static [(&'static str, FieldType)]> {
+    use FieldType::*;
+
+    Some(match name {
+        "vote" => &[("voter", String), ("author", String), ("permlink", String), ("weight", Int16)],
+        "comment" => &[
+            ("parent_author", String), ("parent_permlink", String), ("author", String), ("permlink", String),
+            ("title", String), ("body", String), ("json_metadata", String),
+        ],
+        "transfer" => &[("from", String), ("to", String), ("amount", Asset), ("memo", String)],
+        "delete_comment" => &[("author", String), ("permlink", String)],
+        "cancel_transfer_from_savings" => &[("from", String), ("request_id", Uint32)],
+        _ => return None,
+    })
+}
+
+/// Implemented by every typed operation struct
+pub trait TypedOperation: Serialize + DeserializeOwned {
+    /// The protocol name of the operation, e.g. `"convert"`
+    const NAME: &'static str;
+
+    /// Checks the fields against the rules hived applies to the operation
+    fn validate(&self) -> Result<(), TransactionError>;
+
+    /// Packs the fields in wire order, without the operation's type id
+    fn pack(&self, buf: &mut Vec<u8>);
+
+    /// Reads the fields back in the order `pack` writes them
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError>;
+}
+
+/// Declares the `Operation` enum over the typed operations, every variant
+/// wraps the struct of the same name with an `Operation` suffix
+macro_rules! operations {
+    ($($variant:ident($op:ty)),* $(,)?) => {
+        /// A hive operation, serialized as the `["name", {fields}]` pair hive
+        /// uses in json
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum Operation {
+            $($variant($op),)*
+            /// An operation that has no typed struct, as its name and json fields
+            Raw(String, Map<String, Value>),
+        }
+
+        impl Operation {
+            /// The operation name, e.g. `"vote"`
+            pub fn name(&self) -> &str {
+                match self {
+                    $(Operation::$variant(_) => <$op>::NAME,)*
+                    Operation::Raw(name, _) => name,
+                }
+            }
+
+            /// Parses the fields of a typed operation, `None` if `name` has no
+            /// typed struct
+            fn typed(name: &str, data: &Map<String, Value>) -> Option<Result<Operation, serde_json::Error>> {
+                match name {
+                    $(<$op>::NAME => Some(serde_json::from_value(Value::Object(data.clone())).map(Operation::$variant)),)*
+                    _ => None,
+                }
+            }
+
+            fn validate_typed(&self) -> Option<Result<(), TransactionError>> {
+                match self {
+                    $(Operation::$variant(op) => Some(op.validate()),)*
+                    Operation::Raw(..) => None,
+                }
+            }
+
+            fn pack_typed(&self, buf: &mut Vec<u8>) -> bool {
+                match self {
+                    $(Operation::$variant(op) => op.pack(buf),)*
+                    Operation::Raw(..) => return false,
+                }
+
+                true
+            }
+
+            fn unpack_typed(name: &str, unpacker: &mut Unpacker) -> Option<Result<Operation, PackingError>> {
+                match name {
+                    $(<$op>::NAME => Some(<$op>::unpack(unpacker).map(Operation::$variant)),)*
+                    _ => None,
+                }
+            }
+
+            fn typed_data(&self) -> Option<Map<String, Value>> {
+                let value = match self {
+                    $(Operation::$variant(op) => serde_json::to_value(op),)*
+                    Operation::Raw(..) => return None,
+                };
+
+                match value {
+                    Ok(Value::Object(data)) => Some(data),
+                    _ => None,
+                }
+            }
+        }
+
+        // every typed operation has to be one hived can pack
+        $(const _: () = assert!(is_operation_name(<$op>::NAME), "typed operation missing from OPERATION_NAMES");)*
+
+        impl Serialize for Operation {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                match self {
+                    $(Operation::$variant(op) => (<$op>::NAME, op).serialize(serializer),)*
+                    Operation::Raw(name, data) => (name, data).serialize(serializer),
+                }
+            }
+        }
+
+        $(
+            impl From<$op> for Operation {
+                fn from(op: $op) -> Self {
+                    Operation::$variant(op)
+                }
+            }
+        )*
+    };
+}
+
+operations! {
+    Convert(ConvertOperation),
+    CollateralizedConvert(CollateralizedConvertOperation),
+    EscrowTransfer(EscrowTransferOperation),
+    EscrowApprove(EscrowApproveOperation),
+    EscrowDispute(EscrowDisputeOperation),
+    EscrowRelease(EscrowReleaseOperation),
+    ClaimRewardBalance(ClaimRewardBalanceOperation),
+    DelegateVestingShares(DelegateVestingSharesOperation),
+    AccountCreate(AccountCreateOperation),
+    WitnessUpdate(WitnessUpdateOperation),
+    TransferToSavings(TransferToSavingsOperation),
+    TransferFromSavings(TransferFromSavingsOperation),
+    LimitOrderCreate(LimitOrderCreateOperation),
+    LimitOrderCancel(LimitOrderCancelOperation),
+    CommentOptions(CommentOptionsOperation),
+    SetWithdrawVestingRoute(SetWithdrawVestingRouteOperation),
+    RecoverAccount(RecoverAccountOperation),
+    ChangeRecoveryAccount(ChangeRecoveryAccountOperation),
+    AccountWitnessVote(AccountWitnessVoteOperation),
+    AccountWitnessProxy(AccountWitnessProxyOperation),
+    CustomJson(CustomJsonOperation),
+    TransferToVesting(TransferToVestingOperation),
+    WithdrawVesting(WithdrawVestingOperation),
+    AccountUpdate(AccountUpdateOperation),
+    AccountUpdate2(AccountUpdate2Operation),
+}
+
+impl Operation {
+    /// Creates an operation from its name (e.g. `"vote"`) and its json fields.
+    /// Operations with a typed struct are parsed into it, if the fields do not
+    /// fit it the operation is kept raw and fails to validate and pack.
+    /// Deserializing goes through here as well
+    pub fn new(name: &str, data: Map<String, Value>) -> Operation {
+        match Operation::typed(name, &data) {
+            Some(Ok(op)) => op,
+            _ => Operation::Raw(name.to_string(), data),
+        }
+    }
+
+    /// The id the operation is packed with, its index in hived's operation
+    /// list (`vote` = 0, `comment` = 1, `transfer` = 2, ...). `None` for raw
+    /// operations with a name hived doesn't know
+    /// ```
+    /// use serde_json::Map;
+    /// use tetanus::hive::operations::Operation;
+    /// assert_eq!(Some(18), Operation::new("custom_json", Map::new()).type_id());
+    /// assert_eq!(None, Operation::new("fill_order", Map::new()).type_id());
+    /// ```
+    pub fn type_id(&self) -> Option<u8> {
+        OPERATION_NAMES.iter().position(|name| *name == self.name()).map(|id| id as u8)
+    }
+
+    /// The operation fields, keyed by their hive names
+    pub fn data(&self) -> Map<String, Value> {
+        match self {
+            Operation::Raw(_, data) => data.clone(),
+            typed => typed.typed_data().unwrap_or_default(),
+        }
+    }
+
+    /// Checks that the operation is one hive knows and that its fields are
+    /// valid. Raw operations only have the fields holding account names
+    /// (`voter`, `from`, `to`, ...) checked
+    pub fn validate(&self) -> Result<(), TransactionError> {
+        if let Some(result) = self.validate_typed() {
+            return result
+        }
+        if let Some(error) = self.typed_error() {
+            return Err(error)
+        }
+
+        if self.type_id().is_none() {
+            return Err(TransactionError::UnknownOperation(self.name().to_string()))
+        }
+
+        let data = self.data();
+        for field in ACCOUNT_FIELDS {
+            // an empty `to` or `proxy` is how some operations mean "nobody"
+            if let Some(Value::String(name)) = data.get(field).filter(|v| v.as_str() != Some("")) {
+                validate_account(field, name)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Why a raw operation that has a typed struct did not parse into it
+    fn typed_error(&self) -> Option<TransactionError> {
+        match self {
+            Operation::Raw(name, data) => match Operation::typed(name, data) {
+                Some(Err(error)) => Some(TransactionError::InvalidField(error.to_string())),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Packs the operation, its type id followed by its fields
+    pub(crate) fn pack(&self, buf: &mut Vec<u8>) -> Result<(), TransactionError> {
+        let unsupported = || TransactionError::UnsupportedOperation(self.name().to_string());
+        let type_id = self.type_id().ok_or_else(unsupported)?;
+
+        write_varint(buf, type_id as u64);
+
+        if self.pack_typed(buf) {
+            return Ok(())
+        }
+
+        if let Some(error) = self.typed_error() {
+            return Err(error)
+        }
+
+        let data = self.data();
+        let fields = operation_fields(self.name()).ok_or_else(unsupported)?;
+
+        for (field, field_type) in fields {
+            let invalid = || TransactionError::InvalidField(field.to_string());
+            let value = data.get(*field).ok_or_else(invalid)?;
+
+            match field_type {
+                FieldType::String => pack_string(buf, value.as_str().ok_or_else(invalid)?),
+                FieldType::Asset => {
+                    let asset = HiveAsset::from_value(value).map_err(|_| invalid())?;
+                    asset.pack(buf);
+                },
+                FieldType::Int16 => {
+                    let int = value.as_i64().and_then(|v| i16::try_from(v).ok()).ok_or_else(invalid)?;
+                    buf.extend_from_slice(&int.to_le_bytes());
+                },
+                FieldType::Uint32 => {
+                    let int = value.as_u64().and_then(|v| u32::try_from(v).ok()).ok_or_else(invalid)?;
+                    buf.extend_from_slice(&int.to_le_bytes());
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads an operation packed by `pack`, typed if it has a struct and raw
+    /// otherwise
+    pub(crate) fn unpack(unpacker: &mut Unpacker) -> Result<Operation, PackingError> {
+        // ids past u8 are just as unknown as the ones below it
+        let type_id = u8::try_from(unpacker.varint()?).unwrap_or(u8::MAX);
+        let unknown = || PackingError::UnknownOperationType(type_id);
+        let name = *OPERATION_NAMES.get(type_id as usize).ok_or_else(unknown)?;
+
+        if let Some(op) = Operation::unpack_typed(name, unpacker) {
+            return op
+        }
+
+        let mut data = Map::new();
+        for (field, field_type) in operation_fields(name).ok_or_else(unknown)? {
+            let value = match field_type {
+                FieldType::String => Value::String(unpacker.string()?),
+                // the legacy string form, which is what `to_legacy_json` sends too
+                FieldType::Asset => Value::String(unpacker.asset()?.to_string()),
+                FieldType::Int16 => Value::from(unpacker.i16()?),
+                FieldType::Uint32 => Value::from(unpacker.u32()?),
+            };
+            data.insert(field.to_string(), value);
+        }
+
+        Ok(Operation::Raw(name.to_string(), data))
+    }
+}
+
+/// Reads both forms nodes send operations in, the `["vote", {fields}]` pair
+/// of condenser_api and the `{"type": "vote_operation", "value": {fields}}`
+/// object of the appbase apis such as `block_api`
+struct OperationVisitor;
+
+impl<'de> Visitor<'de> for OperationVisitor {
+    type Value = Operation;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(r#"an operation as ["name", {fields}] or {"type": "name_operation", "value": {fields}}"#)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Operation, A::Error> {
+        let name: String = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let data: Map<String, Value> = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        if seq.next_element::<IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(3, &self))
+        }
+
+        Ok(Operation::new(&name, data))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Operation, A::Error> {
+        let (mut name, mut data) = (None, None);
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "type" => name = Some(map.next_value::<String>()?),
+                "value" => data = Some(map.next_value::<Map<String, Value>>()?),
+                _ => { map.next_value::<IgnoredAny>()?; },
+            }
+        }
+        let name = name.ok_or_else(|| de::Error::missing_field("type"))?;
+        let data = data.ok_or_else(|| de::Error::missing_field("value"))?;
+
+        Ok(Operation::new(name.strip_suffix("_operation").unwrap_or(&name), data))
+    }
+}
+
+impl<'de> Deserialize<'de> for Operation {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(OperationVisitor)
+    }
+}
+
+/// Shows only the operation name, e.g. `vote`
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Checks an operation field that holds an account name
+pub(crate) fn validate_account(field: &str, name: &str) -> Result<(), TransactionError> {
+    validate_account_name(name)
+        .map_err(|error| TransactionError::InvalidAccountName { field: field.to_string(), error })
+}
+
+/// Checks that an asset field is in one of the `allowed` symbols
+pub(crate) fn validate_symbol(field: &str, asset: &HiveAsset, allowed: &[AssetSymbol]) -> Result<(), TransactionError> {
+    if allowed.contains(&asset.symbol) {
+        return Ok(())
+    }
+
+    let allowed: Vec<&str> = allowed.iter().map(AssetSymbol::as_str).collect();
+    Err(TransactionError::InvalidValue {
+        field: field.to_string(),
+        reason: format!("must be {}, got {}", allowed.join(" or "), asset.symbol.as_str()),
+    })
+}
+
+/// Checks that an asset field holds a strictly positive amount
+pub(crate) fn validate_positive(field: &str, asset: &HiveAsset) -> Result<(), TransactionError> {
+    if asset.amount > 0 {
+        return Ok(())
+    }
+
+    Err(TransactionError::InvalidValue { field: field.to_string(), reason: format!("must be positive, got {}", asset) })
+}
+
+/// Checks that an asset field doesn't hold a negative amount
+pub(crate) fn validate_non_negative(field: &str, asset: &HiveAsset) -> Result<(), TransactionError> {
+    if asset.amount >= 0 {
+        return Ok(())
+    }
+
+    Err(TransactionError::InvalidValue { field: field.to_string(), reason: format!("must not be negative, got {}", asset) })
+}
+
+/// Parses a `YYYY-MM-DDTHH:MM:SS` time field into seconds since the unix
+/// epoch, it has to fit the u32 it is packed as
+pub(crate) fn validate_time(field: &str, time: &str) -> Result<u32, TransactionError> {
+    parse_time(time).and_then(|seconds| u32::try_from(seconds).ok()).ok_or_else(|| TransactionError::InvalidValue {
+        field: field.to_string(),
+        reason: format!("must be a YYYY-MM-DDTHH:MM:SS time, got {}", time),
+    })
+}
+
+/// Packs a time field as seconds since the unix epoch, zero if it doesn't
+/// parse
+pub(crate) fn pack_time(buf: &mut Vec<u8>, time: &str) {
+    let seconds = validate_time("", time).unwrap_or_default();
+    buf.extend_from_slice(&seconds.to_le_bytes());
+}
+
+/// Metadata fields hold json or nothing at all
+pub(crate) fn validate_metadata(field: &str, metadata: &str) -> Result<(), TransactionError> {
+    if !metadata.is_empty() && serde_json::from_str::<serde_json::Value>(metadata).is_err() {
+        return Err(TransactionError::InvalidValue { field: field.to_string(), reason: "must be empty or json".to_string() })
+    }
+
+    Ok(())
+}
+
+/// Optional fields pack as a presence byte followed by the value
+pub(crate) fn pack_optional<T>(buf: &mut Vec<u8>, value: &Option<T>, pack: impl Fn(&mut Vec<u8>, &T)) {
+    buf.push(value.is_some() as u8);
+    if let Some(value) = value {
+        pack(buf, value);
+    }
+}
+
+pub(crate) fn unpack_optional<T>(
+    unpacker: &mut Unpacker,
+    unpack: impl Fn(&mut Unpacker) -> Result<T, PackingError>,
+) -> Result<Option<T>, PackingError> {
+    match unpacker.bool()? {
+        true => unpack(unpacker).map(Some),
+        false => Ok(None),
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The operation list from hived's `protocol/operations.hpp`, numbered the
+    /// way it is there
+    const HIVED_OPERATIONS: &[(u8, &str)] = &[
+        (0, "vote"), (1, "comment"), (2, "transfer"), (3, "transfer_to_vesting"), (4, "withdraw_vesting"),
+        (5, "limit_order_create"), (6, "limit_order_cancel"), (7, "feed_publish"), (8, "convert"),
+        (9, "account_create"), (10, "account_update"), (11, "witness_update"), (12, "account_witness_vote"),
+        (13, "account_witness_proxy"), (14, "pow"), (15, "custom"), (16, "report_over_production"),
+        (17, "delete_comment"), (18, "custom_json"), (19, "comment_options"), (20, "set_withdraw_vesting_route"),
+        (21, "limit_order_create2"), (22, "claim_account"), (23, "create_claimed_account"),
+        (24, "request_account_recovery"), (25, "recover_account"), (26, "change_recovery_account"),
+        (27, "escrow_transfer"), (28, "escrow_dispute"), (29, "escrow_release"), (30, "pow2"), (31, "escrow_approve"),
+        (32, "transfer_to_savings"), (33, "transfer_from_savings"), (34, "cancel_transfer_from_savings"),
+        (35, "custom_binary"), (36, "decline_voting_rights"), (37, "reset_account"), (38, "set_reset_account"),
+        (39, "claim_reward_balance"), (40, "delegate_vesting_shares"), (41, "account_create_with_delegation"),
+        (42, "witness_set_properties"), (43, "account_update2"), (44, "create_proposal"),
+        (45, "update_proposal_votes"), (46, "remove_proposal"), (47, "update_proposal"),
+        (48, "collateralized_convert"), (49, "recurrent_transfer"),
+    ];
+
+    #[test]
+    fn type_ids_match_hived() {
+        assert_eq!(HIVED_OPERATIONS.len(), OPERATION_NAMES.len());
+
+        for (id, name) in HIVED_OPERATIONS {
+            assert_eq!(Some(*id), Operation::Raw(name.to_string(), Map::new()).type_id(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn typed_operations_have_type_ids() {
+        let convert = ConvertOperation { owner: "alice".to_string(), requestid: 1, amount: HiveAsset::new(1, AssetSymbol::Hbd) };
+        let route = SetWithdrawVestingRouteOperation {
+            from_account: "alice".to_string(),
+            to_account: "bob".to_string(),
+            percent: 100,
+            auto_vest: false,
+        };
+
+        assert_eq!(Some(8), Operation::from(convert).type_id());
+        assert_eq!(Some(20), Operation::from(route).type_id());
+        assert_eq!(None, Operation::Raw("producer_reward".to_string(), Map::new()).type_id());
+    }
+
+    #[test]
+    fn reads_both_json_forms() {
+        let convert = Operation::from(ConvertOperation { owner: "alice".to_string(), requestid: 1, amount: HiveAsset::new(1000, AssetSymbol::Hbd) });
+        let pair = serde_json::json!(["convert", { "owner": "alice", "requestid": 1, "amount": "1.000 HBD" }]);
+        let appbase = serde_json::json!({
+            "type": "convert_operation",
+            "value": { "owner": "alice", "requestid": 1, "amount": { "amount": "1000", "precision": 3, "nai": "@@000000013" } }
+        });
+
+        assert_eq!(convert, serde_json::from_value(pair).unwrap());
+        assert_eq!(convert, serde_json::from_value(appbase).unwrap());
+        let reward: Operation = serde_json::from_value(serde_json::json!({ "value": { "producer": "gtg" }, "type": "producer_reward_operation" })).unwrap();
+        assert_eq!("producer_reward", reward.name());
+
+        for invalid in [
+            serde_json::json!(["convert"]),
+            serde_json::json!(["convert", {}, {}]),
+            serde_json::json!({ "type": "convert_operation" }),
+            serde_json::json!({ "value": {} }),
+            serde_json::json!("convert"),
+        ] {
+            assert!(serde_json::from_value::<Operation>(invalid).is_err());
+        }
+    }
+
+    #[test]
+    fn writes_fields_in_struct_order() {
+        let convert = Operation::from(ConvertOperation { owner: "alice".to_string(), requestid: 1, amount: HiveAsset::new(1000, AssetSymbol::Hbd) });
+        let raw = Operation::new("vote", serde_json::from_str(r#"{"weight":100,"voter":"alice"}"#).unwrap());
+
+        #[cfg(not(feature = "nai"))]
+        assert_eq!(r#"["convert",{"owner":"alice","requestid":1,"amount":"1.000 HBD"}]"#, serde_json::to_string(&convert).unwrap());
+        assert!(serde_json::to_string(&convert).unwrap().starts_with(r#"["convert",{"owner":"alice","requestid":1,"amount":"#));
+        assert_eq!(r#"["vote",{"weight":100,"voter":"alice"}]"#, serde_json::to_string(&raw).unwrap());
+    }
+}
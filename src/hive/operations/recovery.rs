@@ -0,0 +1,187 @@
+use serde::{Serialize, Deserialize};
+use crate::hive::operations::{validate_account, TypedOperation};
+use crate::hive::packing::{pack_string, write_varint, PackingError, Unpacker};
+use crate::hive::transactions::TransactionError;
+use crate::hive::types::Authority;
+
+/// Takes back a stolen account, signed by both `new_owner_authority` and an
+/// owner authority the account had in the last 30 days. The recovery account
+/// has to have requested the recovery first
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoverAccountOperation {
+    pub account_to_recover: String,
+    pub new_owner_authority: Authority,
+    pub recent_owner_authority: Authority,
+    pub extensions: Vec<String>,
+}
+
+/// Names the account that can recover `account_to_recover`, which takes effect
+/// after 30 days
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeRecoveryAccountOperation {
+    pub account_to_recover: String,
+    pub new_recovery_account: String,
+    pub extensions: Vec<String>,
+}
+
+fn validate_authority(field: &str, authority: &Authority) -> Result<(), TransactionError> {
+    authority.validate().map_err(|error| TransactionError::InvalidAuthority { field: field.to_string(), error })
+}
+
+/// Neither operation has any extensions defined
+fn validate_extensions(extensions: &[String]) -> Result<(), TransactionError> {
+    match extensions.first() {
+        Some(extension) => Err(TransactionError::UnsupportedExtension(extension.clone())),
+        None => Ok(()),
+    }
+}
+
+/// An empty extension list, the only kind there is
+fn unpack_extensions(unpacker: &mut Unpacker) -> Result<Vec<String>, PackingError> {
+    match unpacker.varint()? {
+        0 => Ok(vec![]),
+        _ => Err(PackingError::UnsupportedExtension),
+    }
+}
+
+impl TypedOperation for RecoverAccountOperation {
+    const NAME: &'static str = "recover_account";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_account("account_to_recover", &self.account_to_recover)?;
+        validate_authority("new_owner_authority", &self.new_owner_authority)?;
+        validate_authority("recent_owner_authority", &self.recent_owner_authority)?;
+
+        if self.new_owner_authority == self.recent_owner_authority {
+            return Err(TransactionError::InvalidValue {
+                field: "new_owner_authority".to_string(),
+                reason: "must differ from recent_owner_authority".to_string(),
+            })
+        }
+
+        validate_extensions(&self.extensions)
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.account_to_recover);
+        self.new_owner_authority.pack(buf);
+        self.recent_owner_authority.pack(buf);
+        write_varint(buf, self.extensions.len() as u64);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(RecoverAccountOperation {
+            account_to_recover: unpacker.string()?,
+            new_owner_authority: Authority::unpack(unpacker)?,
+            recent_owner_authority: Authority::unpack(unpacker)?,
+            extensions: unpack_extensions(unpacker)?,
+        })
+    }
+}
+
+impl TypedOperation for ChangeRecoveryAccountOperation {
+    const NAME: &'static str = "change_recovery_account";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_account("account_to_recover", &self.account_to_recover)?;
+        validate_account("new_recovery_account", &self.new_recovery_account)?;
+        validate_extensions(&self.extensions)
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.account_to_recover);
+        pack_string(buf, &self.new_recovery_account);
+        write_varint(buf, self.extensions.len() as u64);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(ChangeRecoveryAccountOperation {
+            account_to_recover: unpacker.string()?,
+            new_recovery_account: unpacker.string()?,
+            extensions: unpack_extensions(unpacker)?,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::operations::*;
+    use crate::hive::packing::{PackingError, Unpacker};
+    use crate::hive::types::{Authority, AuthorityError};
+    use crate::keys::{private::PrivateKey, public::PublicKey};
+    use serde_json::json;
+
+    fn key(password: &str) -> String {
+        PrivateKey::from_login("alice", password, "owner").to_public().to_string(None)
+    }
+
+    fn owner(password: &str) -> Authority {
+        Authority { weight_threshold: 1, account_auths: vec![], key_auths: vec![(key(password), 1)] }
+    }
+
+    fn recover() -> RecoverAccountOperation {
+        RecoverAccountOperation {
+            account_to_recover: "alice".to_string(),
+            new_owner_authority: owner("new password"),
+            recent_owner_authority: owner("old password"),
+            extensions: vec![],
+        }
+    }
+
+    #[test]
+    fn packs_recovery() {
+        let mut buf = vec![];
+        Operation::from(recover()).pack(&mut buf).unwrap();
+
+        let authority = |password: &str| {
+            [vec![1, 0, 0, 0, 0, 1], key(password).parse::<PublicKey>().unwrap().as_bytes().to_vec(), vec![1, 0]].concat()
+        };
+        let expected = [vec![25, 5], b"alice".to_vec(), authority("new password"), authority("old password"), vec![0]].concat();
+        assert_eq!(expected, buf);
+
+        let change = ChangeRecoveryAccountOperation {
+            account_to_recover: "alice".to_string(),
+            new_recovery_account: "bob".to_string(),
+            extensions: vec![],
+        };
+        let mut buf = vec![];
+        Operation::from(change).pack(&mut buf).unwrap();
+        assert_eq!([vec![26, 5], b"alice".to_vec(), vec![3], b"bob".to_vec(), vec![0]].concat(), buf);
+
+        *buf.last_mut().unwrap() = 1;
+        assert_eq!(Err(PackingError::UnsupportedExtension), Operation::unpack(&mut Unpacker::new(&buf)));
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let json = json!(["change_recovery_account", { "account_to_recover": "alice", "new_recovery_account": "bob", "extensions": [] }]);
+        let op: Operation = serde_json::from_value(json.clone()).unwrap();
+
+        assert!(matches!(op, Operation::ChangeRecoveryAccount(_)));
+        assert_eq!(json, serde_json::to_value(&op).unwrap());
+
+        let op = Operation::from(recover());
+        assert_eq!(op, serde_json::from_value(serde_json::to_value(&op).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn authorities_are_checked() {
+        assert!(recover().validate().is_ok());
+
+        let same = RecoverAccountOperation { new_owner_authority: owner("old password"), ..recover() };
+        assert!(matches!(same.validate(), Err(TransactionError::InvalidValue { field, .. }) if field == "new_owner_authority"));
+
+        let impossible = RecoverAccountOperation { new_owner_authority: Authority { weight_threshold: 2, ..owner("new password") }, ..recover() };
+        assert!(matches!(
+            impossible.validate(),
+            Err(TransactionError::InvalidAuthority { field, error: AuthorityError::Impossible { .. } }) if field == "new_owner_authority"
+        ));
+
+        let bad_key = RecoverAccountOperation { recent_owner_authority: Authority { key_auths: vec![("STM".to_string(), 1)], ..owner("old password") }, ..recover() };
+        assert!(matches!(bad_key.validate(), Err(TransactionError::InvalidAuthority { field, .. }) if field == "recent_owner_authority"));
+
+        let extended = RecoverAccountOperation { extensions: vec!["foo".to_string()], ..recover() };
+        assert!(matches!(extended.validate(), Err(TransactionError::UnsupportedExtension(_))));
+    }
+}
@@ -0,0 +1,166 @@
+use serde::{Serialize, Deserialize};
+use crate::hive::operations::{validate_account, validate_positive, validate_symbol, TypedOperation};
+use crate::hive::packing::{pack_string, PackingError, Unpacker};
+use crate::hive::transactions::TransactionError;
+use crate::hive::types::{AssetSymbol, HiveAsset};
+
+/// The longest memo hived accepts, in bytes
+const MAX_MEMO_SIZE: usize = 2048;
+
+/// Moves HIVE or HBD into `to`'s savings, which only pay out three days after
+/// a `TransferFromSavingsOperation`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferToSavingsOperation {
+    pub from: String,
+    pub to: String,
+    pub amount: HiveAsset,
+    pub memo: String,
+}
+
+/// Starts a withdrawal from `from`'s savings, paid to `to` after three days
+/// unless cancelled
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferFromSavingsOperation {
+    pub from: String,
+    /// Must be unique among `from`'s pending withdrawals, used to cancel it
+    pub request_id: u32,
+    pub to: String,
+    pub amount: HiveAsset,
+    pub memo: String,
+}
+
+fn validate_transfer(from: &str, to: &str, amount: &HiveAsset, memo: &str) -> Result<(), TransactionError> {
+    validate_account("from", from)?;
+    validate_account("to", to)?;
+    validate_symbol("amount", amount, &[AssetSymbol::Hive, AssetSymbol::Hbd])?;
+    validate_positive("amount", amount)?;
+
+    if memo.len() >= MAX_MEMO_SIZE {
+        return Err(TransactionError::InvalidValue {
+            field: "memo".to_string(),
+            reason: format!("must be shorter than {} bytes", MAX_MEMO_SIZE),
+        })
+    }
+
+    Ok(())
+}
+
+impl TypedOperation for TransferToSavingsOperation {
+    const NAME: &'static str = "transfer_to_savings";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_transfer(&self.from, &self.to, &self.amount, &self.memo)
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.from);
+        pack_string(buf, &self.to);
+        self.amount.pack(buf);
+        pack_string(buf, &self.memo);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(TransferToSavingsOperation {
+            from: unpacker.string()?,
+            to: unpacker.string()?,
+            amount: unpacker.asset()?,
+            memo: unpacker.string()?,
+        })
+    }
+}
+
+impl TypedOperation for TransferFromSavingsOperation {
+    const NAME: &'static str = "transfer_from_savings";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_transfer(&self.from, &self.to, &self.amount, &self.memo)
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.from);
+        buf.extend_from_slice(&self.request_id.to_le_bytes());
+        pack_string(buf, &self.to);
+        self.amount.pack(buf);
+        pack_string(buf, &self.memo);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(TransferFromSavingsOperation {
+            from: unpacker.string()?,
+            request_id: unpacker.u32()?,
+            to: unpacker.string()?,
+            amount: unpacker.asset()?,
+            memo: unpacker.string()?,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::operations::*;
+    use serde_json::json;
+
+    fn deposit() -> TransferToSavingsOperation {
+        TransferToSavingsOperation {
+            from: "alice".to_string(),
+            to: "alice".to_string(),
+            amount: HiveAsset::new(10000, AssetSymbol::Hbd),
+            memo: "rainy day".to_string(),
+        }
+    }
+
+    fn withdrawal() -> TransferFromSavingsOperation {
+        TransferFromSavingsOperation {
+            from: "alice".to_string(),
+            request_id: 101,
+            to: "bob".to_string(),
+            amount: HiveAsset::new(2500, AssetSymbol::Hive),
+            memo: String::new(),
+        }
+    }
+
+    #[test]
+    fn packs_savings_transfers() {
+        let mut buf = vec![];
+        Operation::from(deposit()).pack(&mut buf).unwrap();
+        let expected = [
+            vec![32, 5], b"alice".to_vec(), vec![5], b"alice".to_vec(),
+            10000i64.to_le_bytes().to_vec(), vec![3], b"SBD\0\0\0\0".to_vec(), vec![9], b"rainy day".to_vec(),
+        ].concat();
+        assert_eq!(expected, buf);
+
+        let mut buf = vec![];
+        Operation::from(withdrawal()).pack(&mut buf).unwrap();
+        let expected = [
+            vec![33, 5], b"alice".to_vec(), vec![101, 0, 0, 0], vec![3], b"bob".to_vec(),
+            2500i64.to_le_bytes().to_vec(), vec![3], b"STEEM\0\0".to_vec(), vec![0],
+        ].concat();
+        assert_eq!(expected, buf);
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let json = json!(["transfer_from_savings", { "from": "alice", "request_id": 101, "to": "bob", "amount": "2.500 HIVE", "memo": "" }]);
+        let op: Operation = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(Operation::TransferFromSavings(withdrawal()), op);
+        #[cfg(not(feature = "nai"))]
+        assert_eq!(json, serde_json::to_value(&op).unwrap());
+    }
+
+    #[test]
+    fn symbols_are_checked() {
+        assert!(deposit().validate().is_ok());
+        assert!(withdrawal().validate().is_ok());
+
+        let vests = TransferToSavingsOperation { amount: HiveAsset::new(1000000, AssetSymbol::Vests), ..deposit() };
+        assert!(matches!(vests.validate(), Err(TransactionError::InvalidValue { field, .. }) if field == "amount"));
+
+        let vests = TransferFromSavingsOperation { amount: HiveAsset::new(1000000, AssetSymbol::Vests), ..withdrawal() };
+        assert!(vests.validate().is_err());
+
+        let long_memo = TransferToSavingsOperation { memo: "a".repeat(2048), ..deposit() };
+        assert!(long_memo.validate().is_err());
+    }
+}
@@ -0,0 +1,149 @@
+use serde::{Serialize, Deserialize};
+use crate::hive::operations::{validate_account, validate_non_negative, validate_positive, validate_symbol, TypedOperation};
+use crate::hive::packing::{pack_string, PackingError, Unpacker};
+use crate::hive::transactions::TransactionError;
+use crate::hive::types::{AssetSymbol, HiveAsset};
+
+/// Powers up HIVE, turning it into Hive Power (VESTS) of `to` right away. An
+/// empty `to` powers up `from` itself
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferToVestingOperation {
+    pub from: String,
+    pub to: String,
+    pub amount: HiveAsset,
+}
+
+/// Starts powering down `vesting_shares`, paid out as HIVE in 13 weekly
+/// installments. A new power down replaces the running one and zero VESTS
+/// stops it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WithdrawVestingOperation {
+    pub account: String,
+    pub vesting_shares: HiveAsset,
+}
+
+impl TypedOperation for TransferToVestingOperation {
+    const NAME: &'static str = "transfer_to_vesting";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_account("from", &self.from)?;
+        if !self.to.is_empty() {
+            validate_account("to", &self.to)?;
+        }
+
+        validate_symbol("amount", &self.amount, &[AssetSymbol::Hive])?;
+        validate_positive("amount", &self.amount)
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.from);
+        pack_string(buf, &self.to);
+        self.amount.pack(buf);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(TransferToVestingOperation {
+            from: unpacker.string()?,
+            to: unpacker.string()?,
+            amount: unpacker.asset()?,
+        })
+    }
+}
+
+impl TypedOperation for WithdrawVestingOperation {
+    const NAME: &'static str = "withdraw_vesting";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_account("account", &self.account)?;
+        validate_symbol("vesting_shares", &self.vesting_shares, &[AssetSymbol::Vests])?;
+        validate_non_negative("vesting_shares", &self.vesting_shares)
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.account);
+        self.vesting_shares.pack(buf);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(WithdrawVestingOperation {
+            account: unpacker.string()?,
+            vesting_shares: unpacker.asset()?,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::operations::*;
+    use serde_json::json;
+
+    fn power_up() -> TransferToVestingOperation {
+        TransferToVestingOperation { from: "alice".to_string(), to: "bob".to_string(), amount: HiveAsset::new(1000, AssetSymbol::Hive) }
+    }
+
+    fn power_down() -> WithdrawVestingOperation {
+        WithdrawVestingOperation { account: "alice".to_string(), vesting_shares: HiveAsset::new(2000000000, AssetSymbol::Vests) }
+    }
+
+    /// The expected hex in the packing tests was made with a separate
+    /// implementation of hived's serializer, not with this packer
+    fn packed_hex(op: impl Into<Operation>) -> String {
+        let mut buf = vec![];
+        op.into().pack(&mut buf).unwrap();
+
+        buf.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn packs_power_up() {
+        assert_eq!("0305616c69636503626f62e80300000000000003535445454d0000", packed_hex(power_up()));
+    }
+
+    #[test]
+    fn packs_power_down() {
+        assert_eq!("0405616c69636500943577000000000656455354530000", packed_hex(power_down()));
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let json = json!(["transfer_to_vesting", { "from": "alice", "to": "bob", "amount": "1.000 HIVE" }]);
+        let op: Operation = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(Operation::TransferToVesting(power_up()), op);
+        #[cfg(not(feature = "nai"))]
+        assert_eq!(json, serde_json::to_value(&op).unwrap());
+
+        let json = json!(["withdraw_vesting", { "account": "alice", "vesting_shares": "2000.000000 VESTS" }]);
+        let op: Operation = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(Operation::WithdrawVesting(power_down()), op);
+        #[cfg(not(feature = "nai"))]
+        assert_eq!(json, serde_json::to_value(&op).unwrap());
+    }
+
+    #[test]
+    fn power_ups_are_checked() {
+        assert!(power_up().validate().is_ok());
+        assert!(TransferToVestingOperation { to: String::new(), ..power_up() }.validate().is_ok());
+
+        let hbd = TransferToVestingOperation { amount: HiveAsset::new(1000, AssetSymbol::Hbd), ..power_up() };
+        assert!(matches!(hbd.validate(), Err(TransactionError::InvalidValue { field, .. }) if field == "amount"));
+
+        let zero = TransferToVestingOperation { amount: HiveAsset::new(0, AssetSymbol::Hive), ..power_up() };
+        assert!(zero.validate().is_err());
+
+        let invalid = TransferToVestingOperation { to: "Bob".to_string(), ..power_up() };
+        assert!(matches!(invalid.validate(), Err(TransactionError::InvalidAccountName { field, .. }) if field == "to"));
+    }
+
+    #[test]
+    fn power_downs_are_checked() {
+        assert!(power_down().validate().is_ok());
+        assert!(WithdrawVestingOperation { vesting_shares: HiveAsset::new(0, AssetSymbol::Vests), ..power_down() }.validate().is_ok());
+
+        let hive = WithdrawVestingOperation { vesting_shares: HiveAsset::new(1000, AssetSymbol::Hive), ..power_down() };
+        assert!(matches!(hive.validate(), Err(TransactionError::InvalidValue { field, .. }) if field == "vesting_shares"));
+
+        let negative = WithdrawVestingOperation { vesting_shares: HiveAsset::new(-1, AssetSymbol::Vests), ..power_down() };
+        assert!(negative.validate().is_err());
+    }
+}
@@ -0,0 +1,161 @@
+use serde::{Serialize, Deserialize};
+use crate::hive::operations::{validate_account, TypedOperation};
+use crate::hive::packing::{pack_string, PackingError, Unpacker};
+use crate::hive::transactions::TransactionError;
+
+/// The most routes an account can have
+const MAX_WITHDRAW_ROUTES: usize = 10;
+
+/// Sends `percent` of every power down payment to `to_account` instead, as
+/// Hive Power if `auto_vest` is set. A route with a `percent` of 0 removes it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetWithdrawVestingRouteOperation {
+    pub from_account: String,
+    pub to_account: String,
+    /// In hundredths of a percent
+    pub percent: u16,
+    pub auto_vest: bool,
+}
+
+/// Collects the routes for one account and checks that together they route
+/// no more than everything. Routes already on chain count towards the limit
+/// too, but the builder only knows about its own
+/// ```
+/// use tetanus::hive::operations::WithdrawVestingRouteBuilder;
+/// let routes = WithdrawVestingRouteBuilder::new("alice")
+///     .route("bob", 5000, false)
+///     .route("carol", 5000, true)
+///     .build()
+///     .unwrap();
+/// assert_eq!(2, routes.len());
+///
+/// assert!(WithdrawVestingRouteBuilder::new("alice").route("bob", 6000, false).route("carol", 5000, false).build().is_err())
+/// ```
+#[derive(Debug, Clone)]
+pub struct WithdrawVestingRouteBuilder {
+    from_account: String,
+    routes: Vec<SetWithdrawVestingRouteOperation>,
+}
+
+impl WithdrawVestingRouteBuilder {
+    pub fn new(from_account: &str) -> Self {
+        WithdrawVestingRouteBuilder { from_account: from_account.to_string(), routes: Vec::new() }
+    }
+
+    /// Adds a route, replacing any earlier one to the same account the way a
+    /// later operation would on chain
+    pub fn route(mut self, to_account: &str, percent: u16, auto_vest: bool) -> Self {
+        self.routes.retain(|route| route.to_account != to_account);
+        self.routes.push(SetWithdrawVestingRouteOperation {
+            from_account: self.from_account.clone(),
+            to_account: to_account.to_string(),
+            percent,
+            auto_vest,
+        });
+        self
+    }
+
+    /// Validates the routes and returns them as operations
+    pub fn build(self) -> Result<Vec<SetWithdrawVestingRouteOperation>, TransactionError> {
+        self.routes.iter().try_for_each(SetWithdrawVestingRouteOperation::validate)?;
+
+        let invalid = |reason: String| TransactionError::InvalidValue { field: "percent".to_string(), reason };
+        if self.routes.iter().filter(|route| route.percent > 0).count() > MAX_WITHDRAW_ROUTES {
+            return Err(invalid(format!("an account can have at most {} routes", MAX_WITHDRAW_ROUTES)))
+        }
+
+        let total: u32 = self.routes.iter().map(|route| route.percent as u32).sum();
+        if total > 10000 {
+            return Err(invalid(format!("routes add up to {}, more than 10000 (100%)", total)))
+        }
+
+        Ok(self.routes)
+    }
+}
+
+impl TypedOperation for SetWithdrawVestingRouteOperation {
+    const NAME: &'static str = "set_withdraw_vesting_route";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_account("from_account", &self.from_account)?;
+        validate_account("to_account", &self.to_account)?;
+
+        if self.percent > 10000 {
+            return Err(TransactionError::InvalidValue {
+                field: "percent".to_string(),
+                reason: "must be at most 10000 (100%)".to_string(),
+            })
+        }
+
+        Ok(())
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.from_account);
+        pack_string(buf, &self.to_account);
+        buf.extend_from_slice(&self.percent.to_le_bytes());
+        buf.push(self.auto_vest as u8);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(SetWithdrawVestingRouteOperation {
+            from_account: unpacker.string()?,
+            to_account: unpacker.string()?,
+            percent: unpacker.u16()?,
+            auto_vest: unpacker.bool()?,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::operations::*;
+    use serde_json::json;
+
+    #[test]
+    fn packs_route() {
+        let route = SetWithdrawVestingRouteOperation {
+            from_account: "alice".to_string(),
+            to_account: "bob".to_string(),
+            percent: 2500,
+            auto_vest: true,
+        };
+
+        let mut buf = vec![];
+        Operation::from(route.clone()).pack(&mut buf).unwrap();
+        assert_eq!([vec![20, 5], b"alice".to_vec(), vec![3], b"bob".to_vec(), vec![0xc4, 0x09, 1]].concat(), buf);
+
+        let json = json!(["set_withdraw_vesting_route", { "from_account": "alice", "to_account": "bob", "percent": 2500, "auto_vest": true }]);
+        assert_eq!(Operation::SetWithdrawVestingRoute(route), serde_json::from_value(json).unwrap());
+    }
+
+    #[test]
+    fn full_route_to_self() {
+        let routes = WithdrawVestingRouteBuilder::new("alice").route("alice", 10000, true).build().unwrap();
+
+        assert_eq!(1, routes.len());
+        assert!(routes[0].validate().is_ok());
+    }
+
+    #[test]
+    fn split_routes() {
+        let routes = WithdrawVestingRouteBuilder::new("alice").route("bob", 5000, false).route("carol", 5000, true).build().unwrap();
+
+        assert_eq!(vec!["bob", "carol"], routes.iter().map(|route| route.to_account.as_str()).collect::<Vec<_>>());
+        assert!(routes.iter().all(|route| route.from_account == "alice" && route.percent == 5000));
+    }
+
+    #[test]
+    fn routes_are_checked() {
+        assert!(WithdrawVestingRouteBuilder::new("alice").route("bob", 10001, false).build().is_err());
+        assert!(WithdrawVestingRouteBuilder::new("alice").route("bob", 5000, false).route("carol", 5001, false).build().is_err());
+
+        // a second route to the same account replaces the first
+        let routes = WithdrawVestingRouteBuilder::new("alice").route("bob", 8000, false).route("bob", 2000, true).route("carol", 8000, false);
+        assert_eq!(2, routes.build().unwrap().len());
+
+        let many = (0..11).fold(WithdrawVestingRouteBuilder::new("alice"), |builder, i| builder.route(&format!("bob{}", i), 100, false));
+        assert!(many.build().is_err());
+    }
+}
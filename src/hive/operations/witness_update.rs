@@ -0,0 +1,185 @@
+use serde::{Serialize, Deserialize};
+use crate::hive::operations::{validate_account, validate_non_negative, validate_symbol, TypedOperation};
+use crate::hive::packing::{pack_string, PackingError, Unpacker};
+use crate::hive::transactions::TransactionError;
+use crate::hive::types::{public_key_bytes, AssetSymbol, HiveAsset};
+use crate::keys::public::PublicKey;
+
+/// The longest witness url hived accepts
+const MAX_URL_LENGTH: usize = 2048;
+/// The smallest `maximum_block_size` a witness can vote for, in bytes
+const MIN_BLOCK_SIZE_LIMIT: u32 = 65536;
+
+/// The chain parameters a witness votes for, the median of the top witnesses'
+/// votes is what the chain uses
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainProperties {
+    pub account_creation_fee: HiveAsset,
+    /// In bytes
+    pub maximum_block_size: u32,
+    /// Yearly interest on HBD savings, in hundredths of a percent
+    #[serde(alias = "sbd_interest_rate")]
+    pub hbd_interest_rate: u16,
+}
+
+/// Registers `owner` as a witness or updates its settings. A signing key of
+/// `STM1111111111111111111111111111111114T1Anm` stops the witness producing
+/// blocks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WitnessUpdateOperation {
+    pub owner: String,
+    pub url: String,
+    pub block_signing_key: String,
+    pub props: ChainProperties,
+    /// Ignored by hived, but still has to be a HIVE amount
+    pub fee: HiveAsset,
+}
+
+impl ChainProperties {
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_symbol("account_creation_fee", &self.account_creation_fee, &[AssetSymbol::Hive])?;
+        validate_non_negative("account_creation_fee", &self.account_creation_fee)?;
+
+        if self.maximum_block_size < MIN_BLOCK_SIZE_LIMIT {
+            return Err(TransactionError::InvalidValue {
+                field: "maximum_block_size".to_string(),
+                reason: format!("must be at least {}", MIN_BLOCK_SIZE_LIMIT),
+            })
+        }
+        if self.hbd_interest_rate > 10000 {
+            return Err(TransactionError::InvalidValue {
+                field: "hbd_interest_rate".to_string(),
+                reason: "must be at most 10000 (100%)".to_string(),
+            })
+        }
+
+        Ok(())
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        self.account_creation_fee.pack(buf);
+        buf.extend_from_slice(&self.maximum_block_size.to_le_bytes());
+        buf.extend_from_slice(&self.hbd_interest_rate.to_le_bytes());
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(ChainProperties {
+            account_creation_fee: unpacker.asset()?,
+            maximum_block_size: unpacker.u32()?,
+            hbd_interest_rate: unpacker.u16()?,
+        })
+    }
+}
+
+impl TypedOperation for WitnessUpdateOperation {
+    const NAME: &'static str = "witness_update";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_account("owner", &self.owner)?;
+
+        if self.url.is_empty() || self.url.len() > MAX_URL_LENGTH {
+            return Err(TransactionError::InvalidValue {
+                field: "url".to_string(),
+                reason: format!("must be 1 to {} bytes", MAX_URL_LENGTH),
+            })
+        }
+        if self.block_signing_key.parse::<PublicKey>().is_err() {
+            return Err(TransactionError::InvalidValue {
+                field: "block_signing_key".to_string(),
+                reason: "must be a public key".to_string(),
+            })
+        }
+
+        self.props.validate()?;
+        validate_symbol("fee", &self.fee, &[AssetSymbol::Hive])?;
+        validate_non_negative("fee", &self.fee)
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.owner);
+        pack_string(buf, &self.url);
+        buf.extend_from_slice(&public_key_bytes(&self.block_signing_key));
+        self.props.pack(buf);
+        self.fee.pack(buf);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(WitnessUpdateOperation {
+            owner: unpacker.string()?,
+            url: unpacker.string()?,
+            block_signing_key: unpacker.public_key()?,
+            props: ChainProperties::unpack(unpacker)?,
+            fee: unpacker.asset()?,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::operations::*;
+    use crate::keys::{private::PrivateKey, public::PublicKey};
+    use serde_json::json;
+
+    fn key() -> String {
+        PrivateKey::from_login("alice", "password", "witness").to_public().to_string(None)
+    }
+
+    fn update() -> WitnessUpdateOperation {
+        WitnessUpdateOperation {
+            owner: "alice".to_string(),
+            url: "https://hive.blog/@alice".to_string(),
+            block_signing_key: key(),
+            props: ChainProperties {
+                account_creation_fee: HiveAsset::new(3000, AssetSymbol::Hive),
+                maximum_block_size: 65536,
+                hbd_interest_rate: 2000,
+            },
+            fee: HiveAsset::new(0, AssetSymbol::Hive),
+        }
+    }
+
+    #[test]
+    fn packs_witness_update() {
+        let mut buf = vec![];
+        Operation::from(update()).pack(&mut buf).unwrap();
+
+        let expected = [
+            vec![11, 5], b"alice".to_vec(), vec![24], b"https://hive.blog/@alice".to_vec(),
+            key().parse::<PublicKey>().unwrap().as_bytes().to_vec(),
+            3000i64.to_le_bytes().to_vec(), vec![3], b"STEEM\0\0".to_vec(), vec![0, 0, 1, 0], vec![0xd0, 0x07],
+            0i64.to_le_bytes().to_vec(), vec![3], b"STEEM\0\0".to_vec(),
+        ].concat();
+        assert_eq!(expected, buf);
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let json = json!(["witness_update", {
+            "owner": "alice", "url": "https://hive.blog/@alice", "block_signing_key": key(),
+            "props": { "account_creation_fee": "3.000 HIVE", "maximum_block_size": 65536, "sbd_interest_rate": 2000 },
+            "fee": "0.000 HIVE"
+        }]);
+        let op: Operation = serde_json::from_value(json).unwrap();
+
+        assert_eq!(Operation::WitnessUpdate(update()), op);
+        assert_eq!(2000, serde_json::to_value(&op).unwrap()[1]["props"]["hbd_interest_rate"]);
+    }
+
+    #[test]
+    fn witness_updates_are_checked() {
+        assert!(update().validate().is_ok());
+
+        let disabled = WitnessUpdateOperation { block_signing_key: "STM1111111111111111111111111111111114T1Anm".to_string(), ..update() };
+        assert!(disabled.validate().is_ok());
+
+        let bad_key = WitnessUpdateOperation { block_signing_key: "alice".to_string(), ..update() };
+        assert!(matches!(bad_key.validate(), Err(TransactionError::InvalidValue { field, .. }) if field == "block_signing_key"));
+
+        let small_blocks = WitnessUpdateOperation { props: ChainProperties { maximum_block_size: 1024, ..update().props }, ..update() };
+        assert!(small_blocks.validate().is_err());
+
+        let hbd_fee = WitnessUpdateOperation { fee: HiveAsset::new(0, AssetSymbol::Hbd), ..update() };
+        assert!(hbd_fee.validate().is_err());
+    }
+}
@@ -0,0 +1,157 @@
+use serde::{Serialize, Deserialize};
+use crate::hive::operations::{validate_account, TypedOperation};
+use crate::hive::packing::{pack_string, PackingError, Unpacker};
+use crate::hive::transactions::TransactionError;
+
+/// Votes for a witness, or takes the vote back with `approve` false. An
+/// account has at most 30 witness votes and none while it has a proxy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountWitnessVoteOperation {
+    pub account: String,
+    pub witness: String,
+    pub approve: bool,
+}
+
+/// Lets `proxy` cast the account's witness votes, replacing the votes it has
+/// made itself. An empty `proxy` removes the proxy again
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountWitnessProxyOperation {
+    pub account: String,
+    pub proxy: String,
+}
+
+impl TypedOperation for AccountWitnessVoteOperation {
+    const NAME: &'static str = "account_witness_vote";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_account("account", &self.account)?;
+        validate_account("witness", &self.witness)?;
+        if self.account == self.witness {
+            return Err(TransactionError::InvalidValue {
+                field: "witness".to_string(),
+                reason: "must not be the voting account".to_string(),
+            })
+        }
+
+        Ok(())
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.account);
+        pack_string(buf, &self.witness);
+        buf.push(self.approve as u8);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(AccountWitnessVoteOperation {
+            account: unpacker.string()?,
+            witness: unpacker.string()?,
+            approve: unpacker.bool()?,
+        })
+    }
+}
+
+impl TypedOperation for AccountWitnessProxyOperation {
+    const NAME: &'static str = "account_witness_proxy";
+
+    fn validate(&self) -> Result<(), TransactionError> {
+        validate_account("account", &self.account)?;
+        if self.proxy.is_empty() {
+            return Ok(())
+        }
+
+        validate_account("proxy", &self.proxy)?;
+        if self.account == self.proxy {
+            return Err(TransactionError::InvalidValue {
+                field: "proxy".to_string(),
+                reason: "must not be the account itself".to_string(),
+            })
+        }
+
+        Ok(())
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        pack_string(buf, &self.account);
+        pack_string(buf, &self.proxy);
+    }
+
+    fn unpack(unpacker: &mut Unpacker) -> Result<Self, PackingError> {
+        Ok(AccountWitnessProxyOperation {
+            account: unpacker.string()?,
+            proxy: unpacker.string()?,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::operations::*;
+    use serde_json::json;
+
+    fn vote() -> AccountWitnessVoteOperation {
+        AccountWitnessVoteOperation { account: "alice".to_string(), witness: "bob".to_string(), approve: true }
+    }
+
+    fn proxy() -> AccountWitnessProxyOperation {
+        AccountWitnessProxyOperation { account: "alice".to_string(), proxy: "bob".to_string() }
+    }
+
+    #[test]
+    fn packs_witness_vote() {
+        let mut buf = vec![];
+        Operation::from(vote()).pack(&mut buf).unwrap();
+
+        let expected = [vec![12, 5], b"alice".to_vec(), vec![3], b"bob".to_vec(), vec![1]].concat();
+        assert_eq!(expected, buf);
+    }
+
+    #[test]
+    fn packs_witness_proxy() {
+        let mut buf = vec![];
+        Operation::from(proxy()).pack(&mut buf).unwrap();
+        assert_eq!([vec![13, 5], b"alice".to_vec(), vec![3], b"bob".to_vec()].concat(), buf);
+
+        let mut buf = vec![];
+        Operation::from(AccountWitnessProxyOperation { proxy: String::new(), ..proxy() }).pack(&mut buf).unwrap();
+        assert_eq!([vec![13, 5], b"alice".to_vec(), vec![0]].concat(), buf);
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let json = json!(["account_witness_vote", { "account": "alice", "witness": "bob", "approve": true }]);
+        let op: Operation = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(Operation::AccountWitnessVote(vote()), op);
+        assert_eq!(json, serde_json::to_value(&op).unwrap());
+
+        let json = json!(["account_witness_proxy", { "account": "alice", "proxy": "bob" }]);
+        let op: Operation = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(Operation::AccountWitnessProxy(proxy()), op);
+        assert_eq!(json, serde_json::to_value(&op).unwrap());
+    }
+
+    #[test]
+    fn witness_votes_are_checked() {
+        assert!(vote().validate().is_ok());
+        assert!(AccountWitnessVoteOperation { approve: false, ..vote() }.validate().is_ok());
+
+        let own = AccountWitnessVoteOperation { witness: "alice".to_string(), ..vote() };
+        assert!(matches!(own.validate(), Err(TransactionError::InvalidValue { field, .. }) if field == "witness"));
+
+        let invalid = AccountWitnessVoteOperation { witness: "Bob".to_string(), ..vote() };
+        assert!(matches!(invalid.validate(), Err(TransactionError::InvalidAccountName { field, .. }) if field == "witness"));
+    }
+
+    #[test]
+    fn witness_proxies_are_checked() {
+        assert!(proxy().validate().is_ok());
+        assert!(AccountWitnessProxyOperation { proxy: String::new(), ..proxy() }.validate().is_ok());
+
+        let own = AccountWitnessProxyOperation { proxy: "alice".to_string(), ..proxy() };
+        assert!(matches!(own.validate(), Err(TransactionError::InvalidValue { field, .. }) if field == "proxy"));
+
+        let invalid = AccountWitnessProxyOperation { proxy: "b".to_string(), ..proxy() };
+        assert!(matches!(invalid.validate(), Err(TransactionError::InvalidAccountName { field, .. }) if field == "proxy"));
+    }
+}
@@ -0,0 +1,311 @@
+//! Primitives of hive's binary serialization, which is what transactions are
+//! signed over.
+use thiserror::Error;
+use crate::hive::transactions::format_time;
+use crate::hive::types::HiveAsset;
+use crate::keys::public::PublicKey;
+
+/// An error packing or unpacking hive binary data
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PackingError {
+    /// The data ended in the middle of a value
+    #[error("unexpected end of data")]
+    UnexpectedEnd,
+    #[error("invalid utf-8 in string: {0}")]
+    InvalidUtf8(std::str::Utf8Error),
+    /// An operation type id hived doesn't define, or whose fields can't be
+    /// unpacked yet
+    #[error("unknown operation type {0}")]
+    UnknownOperationType(u8),
+    /// An asset whose symbol is unknown or doesn't go with its precision
+    #[error("asset symbol does not match its precision")]
+    AssetSymbolMismatch,
+    /// A varint longer than the 10 bytes a u64 takes
+    #[error("varint does not fit a u64")]
+    OverflowInVarint,
+    /// A length prefix claiming more bytes than are left, or data left over
+    /// after the value
+    #[error("length does not match the data")]
+    InvalidLength,
+    /// An operation extension the crate has no type for
+    #[error("unsupported extension")]
+    UnsupportedExtension,
+}
+
+/// Appends `value` as an unsigned LEB128 varint, 7 bits per byte starting with
+/// the lowest and the top bit set on every byte but the last. Lengths and
+/// counts are packed this way
+/// ```
+/// use tetanus::hive::packing::write_varint;
+/// let mut buf = vec![];
+/// write_varint(&mut buf, 300);
+/// assert_eq!(vec![0xac, 0x02], buf);
+/// ```
+pub fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+
+    buf.push(value as u8);
+}
+
+/// Reads a varint from the start of `buf`, returning it with the number of
+/// bytes it took
+/// ```
+/// use tetanus::hive::packing::{read_varint, PackingError};
+/// assert_eq!(Ok((300, 2)), read_varint(&[0xac, 0x02, 0xff]));
+/// assert_eq!(Err(PackingError::UnexpectedEnd), read_varint(&[0xac]));
+/// ```
+pub fn read_varint(buf: &[u8]) -> Result<(u64, usize), PackingError> {
+    let mut value = 0u64;
+
+    for (i, byte) in buf.iter().enumerate() {
+        let bits = (byte & 0x7f) as u64;
+        // the tenth byte only has room for the top bit of a u64
+        if i == 9 && bits > 1 || i > 9 {
+            return Err(PackingError::OverflowInVarint)
+        }
+
+        value |= bits << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1))
+        }
+    }
+
+    Err(PackingError::UnexpectedEnd)
+}
+
+/// Appends `s` as its varint byte length followed by its utf-8 bytes, with no
+/// terminator
+/// ```
+/// use tetanus::hive::packing::pack_string;
+/// let mut buf = vec![];
+/// pack_string(&mut buf, "hive");
+/// assert_eq!(b"\x04hive".to_vec(), buf);
+/// ```
+pub fn pack_string(buf: &mut Vec<u8>, s: &str) {
+    write_varint(buf, s.len() as u64);
+    buf.extend_from_slice(s.as_bytes());
+}
+
+/// Reads a string packed at `offset` in `buf`, returning it with the offset
+/// just past it
+/// ```
+/// use tetanus::hive::packing::unpack_string;
+/// assert_eq!(Ok(("hive".to_string(), 6)), unpack_string(b"\x00\x04hive", 1));
+/// ```
+pub fn unpack_string(buf: &[u8], offset: usize) -> Result<(String, usize), PackingError> {
+    let rest = buf.get(offset..).ok_or(PackingError::UnexpectedEnd)?;
+    let (len, len_size) = read_varint(rest)?;
+
+    let start = offset + len_size;
+    let end = usize::try_from(len).ok().and_then(|len| start.checked_add(len)).ok_or(PackingError::InvalidLength)?;
+    let bytes = buf.get(start..end).ok_or(PackingError::InvalidLength)?;
+    let string = core::str::from_utf8(bytes).map_err(PackingError::InvalidUtf8)?;
+
+    Ok((string.to_string(), end))
+}
+
+/// Reads the `N` bytes at `offset`, returning them with the offset just past
+/// them. Fixed size fields like integers and keys are packed as is
+/// ```
+/// use tetanus::hive::packing::read_array;
+/// let (bytes, offset) = read_array::<2>(&[0, 0x39, 0x05, 0], 1).unwrap();
+/// assert_eq!((1337, 3), (u16::from_le_bytes(bytes), offset));
+/// ```
+pub fn read_array<const N: usize>(buf: &[u8], offset: usize) -> Result<([u8; N], usize), PackingError> {
+    let end = offset.checked_add(N).ok_or(PackingError::UnexpectedEnd)?;
+    let bytes = buf.get(offset..end).ok_or(PackingError::UnexpectedEnd)?;
+
+    Ok((bytes.try_into().expect("slice is N bytes"), end))
+}
+
+/// Reads packed values one after another from the start of `buf`
+/// ```
+/// use tetanus::hive::packing::{pack_string, Unpacker};
+/// let mut buf = vec![];
+/// pack_string(&mut buf, "alice");
+/// buf.extend_from_slice(&1337u32.to_le_bytes());
+///
+/// let mut unpacker = Unpacker::new(&buf);
+/// assert_eq!("alice", unpacker.string().unwrap());
+/// assert_eq!(1337, unpacker.u32().unwrap());
+/// assert!(unpacker.finish().is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Unpacker<'a> {
+    buf: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Unpacker<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Unpacker { buf, offset: 0 }
+    }
+
+    /// How many bytes have been read so far
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Checks that everything has been read
+    pub fn finish(&self) -> Result<(), PackingError> {
+        match self.offset == self.buf.len() {
+            true => Ok(()),
+            false => Err(PackingError::InvalidLength),
+        }
+    }
+
+    pub fn varint(&mut self) -> Result<u64, PackingError> {
+        let (value, len) = read_varint(self.buf.get(self.offset..).unwrap_or_default())?;
+        self.offset += len;
+        Ok(value)
+    }
+
+    /// A varint count of the items that follow
+    pub fn count(&mut self) -> Result<usize, PackingError> {
+        let count = self.varint()?;
+        // every item takes at least a byte
+        match usize::try_from(count) {
+            Ok(count) if count <= self.buf.len() - self.offset => Ok(count),
+            _ => Err(PackingError::InvalidLength),
+        }
+    }
+
+    pub fn string(&mut self) -> Result<String, PackingError> {
+        let (string, offset) = unpack_string(self.buf, self.offset)?;
+        self.offset = offset;
+        Ok(string)
+    }
+
+    pub fn array<const N: usize>(&mut self) -> Result<[u8; N], PackingError> {
+        let (bytes, offset) = read_array(self.buf, self.offset)?;
+        self.offset = offset;
+        Ok(bytes)
+    }
+
+    /// A single byte, anything but 0 is true
+    pub fn bool(&mut self) -> Result<bool, PackingError> {
+        Ok(self.array::<1>()?[0] != 0)
+    }
+
+    pub fn u16(&mut self) -> Result<u16, PackingError> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+
+    pub fn i16(&mut self) -> Result<i16, PackingError> {
+        Ok(i16::from_le_bytes(self.array()?))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, PackingError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    pub fn asset(&mut self) -> Result<HiveAsset, PackingError> {
+        let (asset, offset) = HiveAsset::unpack(self.buf, self.offset)?;
+        self.offset = offset;
+        Ok(asset)
+    }
+
+    /// A u32 of seconds since the unix epoch, as a `YYYY-MM-DDTHH:MM:SS` time
+    pub fn time(&mut self) -> Result<String, PackingError> {
+        Ok(format_time(self.u32()? as u64))
+    }
+
+    /// A 33 byte compressed key, as an `STM` public key string
+    pub fn public_key(&mut self) -> Result<String, PackingError> {
+        Ok(PublicKey::new(self.array::<33>()?.to_vec()).to_string(None))
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::packing::*;
+    use quickcheck::quickcheck;
+
+    quickcheck! {
+        fn prop_varint_roundtrips(value: u64) -> bool {
+            let mut buf = vec![];
+            write_varint(&mut buf, value);
+
+            read_varint(&buf) == Ok((value, buf.len()))
+        }
+    }
+
+    #[test]
+    fn varint_edges() {
+        let cases: &[(u64, &[u8])] = &[
+            (0, &[0]),
+            (127, &[0x7f]),
+            (128, &[0x80, 0x01]),
+            (16383, &[0xff, 0x7f]),
+            (16384, &[0x80, 0x80, 0x01]),
+            (u32::MAX as u64, &[0xff, 0xff, 0xff, 0xff, 0x0f]),
+            (u64::MAX, &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]),
+        ];
+
+        for (value, bytes) in cases {
+            let mut buf = vec![];
+            write_varint(&mut buf, *value);
+            assert_eq!(bytes, &buf);
+            assert_eq!(Ok((*value, bytes.len())), read_varint(bytes));
+        }
+
+        for shift in 0..64 {
+            let mut buf = vec![];
+            write_varint(&mut buf, 1 << shift);
+            assert_eq!(Ok((1 << shift, buf.len())), read_varint(&buf));
+        }
+    }
+
+    #[test]
+    fn strings_roundtrip() {
+        let long = "x".repeat(300);
+        let cases: &[(&str, &[u8])] = &[
+            ("", &[0]),
+            ("hive", &[4]),
+            ("héllo wörld", &[13]),
+            ("ハイブ", &[9]),
+            (&long, &[0xac, 0x02]),
+        ];
+
+        for (string, prefix) in cases {
+            let mut buf = vec![0xff];
+            pack_string(&mut buf, string);
+            assert_eq!([&[0xff], *prefix, string.as_bytes()].concat(), buf);
+            assert_eq!(Ok((string.to_string(), buf.len())), unpack_string(&buf, 1));
+        }
+
+        // strings follow each other without any separator
+        let mut buf = vec![];
+        pack_string(&mut buf, "alice");
+        pack_string(&mut buf, "");
+        pack_string(&mut buf, "bob");
+        let (alice, offset) = unpack_string(&buf, 0).unwrap();
+        let (empty, offset) = unpack_string(&buf, offset).unwrap();
+        let (bob, offset) = unpack_string(&buf, offset).unwrap();
+        assert_eq!(("alice", "", "bob", buf.len()), (alice.as_str(), empty.as_str(), bob.as_str(), offset));
+    }
+
+    #[test]
+    fn string_errors() {
+        assert_eq!(Err(PackingError::InvalidLength), unpack_string(b"\x05abc", 0));
+        assert_eq!(Err(PackingError::UnexpectedEnd), unpack_string(b"\x00", 2));
+        assert!(matches!(unpack_string(&[2, 0xc3, 0x28], 0), Err(PackingError::InvalidUtf8(_))));
+
+        let mut huge = vec![];
+        write_varint(&mut huge, u64::MAX);
+        assert_eq!(Err(PackingError::InvalidLength), unpack_string(&huge, 0));
+    }
+
+    #[test]
+    fn varint_errors() {
+        assert_eq!(Err(PackingError::UnexpectedEnd), read_varint(&[]));
+        assert_eq!(Err(PackingError::UnexpectedEnd), read_varint(&[0x80, 0x80]));
+        assert_eq!(Err(PackingError::OverflowInVarint), read_varint(&[0xff; 10]));
+        assert_eq!(Err(PackingError::OverflowInVarint), read_varint(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02]));
+        assert_eq!(Err(PackingError::OverflowInVarint), read_varint(&[0x80; 11]));
+    }
+}
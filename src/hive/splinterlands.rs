@@ -0,0 +1,77 @@
+//! Builders for common Splinterlands game actions, which the game reads from
+//! `custom_json` operations signed with the player's posting authority.
+//!
+//! These are community contributions following the formats the game used when
+//! they were written, they are not maintained by Splinterlands and may fall
+//! behind game updates. Check the json against what the game client sends
+//! before relying on them.
+use serde_json::json;
+use crate::hive::operations::CustomJsonOperation;
+
+/// A reward `sm_claim_reward` can claim
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reward {
+    /// The chest of a completed daily quest, by quest id
+    Quest(String),
+    /// The end of season reward of a ranked season, by season number
+    Season(u32),
+}
+
+/// Submits a team for the battle matched by the `trx_id` of the player's
+/// find match transaction. Every card is given by its uid, the summoner
+/// first. The team is revealed right away rather than as a hash to be
+/// revealed later, so `secret` is only passed through
+pub fn sm_submit_team(account: &str, trx_id: &str, summoner: &str, monsters: &[&str], secret: &str) -> CustomJsonOperation {
+    let team = json!({ "trx_id": trx_id, "summoner": summoner, "monsters": monsters, "secret": secret });
+
+    CustomJsonOperation::posting(account, "sm_submit_team", &team)
+}
+
+/// Forfeits the battle the player queued with the transaction `battle_queue_id`
+/// ```
+/// use tetanus::hive::splinterlands::sm_surrender;
+/// let op = sm_surrender("alice", "5a8c2e1f0d3b4a6c7e9f1b2d4c6a8e0f2b4d6c8a");
+/// assert_eq!(("sm_surrender", vec!["alice".to_string()]), (op.id.as_str(), op.required_posting_auths));
+/// assert_eq!(r#"{"battle_queue_id":"5a8c2e1f0d3b4a6c7e9f1b2d4c6a8e0f2b4d6c8a"}"#, op.json)
+/// ```
+pub fn sm_surrender(account: &str, battle_queue_id: &str) -> CustomJsonOperation {
+    CustomJsonOperation::posting(account, "sm_surrender", &json!({ "battle_queue_id": battle_queue_id }))
+}
+
+/// Claims a quest or season reward
+pub fn sm_claim_reward(account: &str, reward: &Reward) -> CustomJsonOperation {
+    let claim = match reward {
+        Reward::Quest(quest_id) => json!({ "type": "quest", "quest_id": quest_id }),
+        Reward::Season(season) => json!({ "type": "league_season", "season": season }),
+    };
+
+    CustomJsonOperation::posting(account, "sm_claim_reward", &claim)
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::splinterlands::*;
+    use crate::hive::operations::TypedOperation;
+    use serde_json::Value;
+
+    fn payload(op: &CustomJsonOperation) -> Value {
+        serde_json::from_str(&op.json).unwrap()
+    }
+
+    #[test]
+    fn builds_game_operations() {
+        let team = sm_submit_team("alice", "trx", "C1-1-ABC", &["C1-2-DEF", "C1-3-GHI"], "s3cret");
+        assert_eq!("sm_submit_team", team.id);
+        assert_eq!(json!({ "trx_id": "trx", "summoner": "C1-1-ABC", "monsters": ["C1-2-DEF", "C1-3-GHI"], "secret": "s3cret" }), payload(&team));
+
+        let quest = sm_claim_reward("alice", &Reward::Quest("quest-1".to_string()));
+        assert_eq!(json!({ "type": "quest", "quest_id": "quest-1" }), payload(&quest));
+        let season = sm_claim_reward("alice", &Reward::Season(68));
+        assert_eq!(json!({ "type": "league_season", "season": 68 }), payload(&season));
+
+        for op in [team, quest, season, sm_surrender("alice", "trx")] {
+            assert!(op.validate().is_ok());
+        }
+    }
+}
@@ -1,41 +1,1010 @@
-use wasm_bindgen::prelude::*;
-use crate::{keys::private::PrivateKey, signatures::SignatureWrapper};
-use serde::{Serialize, Deserialize};
-
-#[derive(Serialize, Deserialize)]
-pub struct OperationData;
-
-#[wasm_bindgen]
-#[derive(Serialize, Deserialize)]
-pub struct Operation(String, OperationData);
-
-
-
-
-#[wasm_bindgen]
-#[derive(Serialize, Deserialize)]
-pub struct Transaction { 
-    ref_block_num: u64,
-    ref_block_prefix: u64,
-    expiration: String,
-    operations: Vec<Operation>,
-    extensions: Vec<String>,
-}
-
-#[wasm_bindgen]
-impl Transaction {
-    pub fn new(val: JsValue) -> Transaction {
-        serde_wasm_bindgen::from_value(val).unwrap()
-    }
-
-    pub fn digest_sign(&self, key: &str) -> SignatureWrapper {
-        let private = PrivateKey::from_string(key);
-
-        let json_str = serde_json::to_string(&self).unwrap();
-
-        private.sign_message(&json_str)
-    }
-}
-
-
-
+use std::{cmp::Reverse, collections::BTreeMap, fmt, hash::{Hash, Hasher}, time::{Duration, SystemTime, UNIX_EPOCH}};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "wasm")]
+use crate::hive::wasm::WasmOperation;
+use crate::{keys::private::PrivateKey, signatures::SignatureWrapper};
+use crate::hive::packing::{write_varint, PackingError, Unpacker};
+use crate::hive::types::{AuthRole, AuthorityError, DynamicGlobalProperties, HiveAccount, HiveAsset, RcResourceParams};
+pub use crate::hive::operations::Operation;
+use crate::keys::public::PublicKey;
+use crate::hive::utils::{now_seconds, AccountNameError};
+use crate::types::keys::PrivateKeyError;
+use crate::types::signature::SignatureError;
+use crate::utils::sha256;
+use serde::{de, Serialize, Serializer, Deserialize, Deserializer};
+use serde_json::Value;
+use thiserror::Error;
+
+/// The chain id of the Hive mainnet, signatures cover it so they cannot be
+/// replayed on other chains
+pub const HIVE_CHAIN_ID: [u8; 32] = [
+    0xbe, 0xea, 0xb0, 0xde, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+];
+
+/// Size of a packed signature
+const SIGNATURE_SIZE: usize = 65;
+
+/// Blocks in the 5 day window over which resource credits regenerate
+const RC_REGENERATION_BLOCKS: u64 = 5 * 24 * 60 * 20;
+
+/// An error involving a hive transaction.
+#[derive(Debug, Error)]
+pub enum TransactionError {
+    #[error("transaction has no operations")]
+    NoOperations,
+    /// The expiration is not a `YYYY-MM-DDTHH:MM:SS` utc time
+    #[error("invalid expiration: {0}")]
+    InvalidExpiration(String),
+    #[error("ref_block_num must not be zero")]
+    ZeroRefBlockNum,
+    #[error("unknown operation: {0}")]
+    UnknownOperation(String),
+    /// An operation field holding an account name that hived would reject
+    #[error("invalid account name in {field}: {error}")]
+    InvalidAccountName { field: String, error: AccountNameError },
+    /// An operation field holding an authority that hived would reject
+    #[error("invalid authority in {field}: {error}")]
+    InvalidAuthority { field: String, error: AuthorityError },
+    /// Hive defines no transaction extensions, so any extension is rejected
+    #[error("unsupported extension: {0}")]
+    UnsupportedExtension(String),
+    /// An operation that has no binary packing yet
+    #[error("cannot pack operation: {0}")]
+    UnsupportedOperation(String),
+    /// An operation field that is missing or does not fit its binary type
+    #[error("invalid operation field: {0}")]
+    InvalidField(String),
+    /// An operation field whose value hived would reject
+    #[error("invalid {field}: {reason}")]
+    InvalidValue { field: String, reason: String },
+    #[error(transparent)]
+    Packing(#[from] PackingError),
+    #[error(transparent)]
+    Signature(#[from] SignatureError),
+    #[error("invalid private key: {0}")]
+    InvalidKey(#[from] PrivateKeyError),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.is_ascii() || !hex.len().is_multiple_of(2) {
+        return None
+    }
+
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok()).collect()
+}
+
+/// Writes signatures the way nodes send them, as hex with the recovery id
+/// first. The wrapper keeps it last
+fn serialize_signatures<S: Serializer>(signatures: &[SignatureWrapper], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(signatures.iter().map(|sig| {
+        let mut bytes = sig.sig();
+        bytes.rotate_right(1);
+        to_hex(&bytes)
+    }))
+}
+
+/// Reads signatures written by `serialize_signatures`
+fn deserialize_signatures<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<SignatureWrapper>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|hex| match from_hex(hex) {
+            Some(mut bytes) if bytes.len() == 65 => {
+                bytes.rotate_left(1);
+                Ok(SignatureWrapper::new(bytes))
+            },
+            _ => Err(de::Error::custom(format!("invalid signature {}", hex))),
+        })
+        .collect()
+}
+
+/// Rewrites every NAI asset object inside `value` to its legacy string form
+pub(crate) fn legacy_assets(value: &mut Value) -> Result<(), TransactionError> {
+    match value {
+        Value::Object(object) if object.contains_key("nai") => {
+            let asset = HiveAsset::from_value(value).map_err(|_| TransactionError::InvalidField(value.to_string()))?;
+            *value = Value::String(asset.to_string());
+        },
+        Value::Object(object) => object.values_mut().try_for_each(legacy_assets)?,
+        Value::Array(values) => values.iter_mut().try_for_each(legacy_assets)?,
+        _ => {},
+    }
+
+    Ok(())
+}
+
+
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transaction { 
+    ref_block_num: u64,
+    ref_block_prefix: u64,
+    expiration: String,
+    operations: Vec<Operation>,
+    extensions: Vec<String>,
+    /// Signatures collected so far, not part of what gets signed. Left out of
+    /// the json while there are none
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_signatures",
+        deserialize_with = "deserialize_signatures"
+    )]
+    signatures: Vec<SignatureWrapper>,
+    /// Prepended to the packed transaction when signing, see `with_chain_id`
+    #[serde(skip)]
+    chain_id: Option<[u8; 32]>,
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+impl Transaction {
+    pub fn new(val: JsValue) -> Transaction {
+        serde_wasm_bindgen::from_value(val).unwrap()
+    }
+
+    /// Appends an operation made by one of the `hive::wasm` builders
+    pub fn push_operation(&mut self, op: &WasmOperation) {
+        self.operations.push(op.clone().into());
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Transaction {
+    pub fn digest_sign(&self, key: &str) -> SignatureWrapper {
+        let private = PrivateKey::from_string(key);
+
+        let json_str = serde_json::to_string(&self).unwrap();
+
+        private.sign_message(&json_str)
+    }
+}
+
+impl Transaction {
+    /// Checks the transaction is well formed before it is signed: it has at
+    /// least one operation and each of them validates, the expiration is a
+    /// valid time, `ref_block_num` is set and there are no extensions. The
+    /// first problem found is returned
+    pub fn validate(&self) -> Result<(), TransactionError> {
+        if self.operations.is_empty() {
+            return Err(TransactionError::NoOperations)
+        }
+        if parse_time(&self.expiration).is_none() {
+            return Err(TransactionError::InvalidExpiration(self.expiration.clone()))
+        }
+        if self.ref_block_num == 0 {
+            return Err(TransactionError::ZeroRefBlockNum)
+        }
+        if let Some(extension) = self.extensions.first() {
+            return Err(TransactionError::UnsupportedExtension(extension.clone()))
+        }
+
+        self.operations.iter().try_for_each(Operation::validate)
+    }
+
+    /// The expiration as a `SystemTime`
+    /// ```
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// use tetanus::hive::transactions::Transaction;
+    /// let tx: Transaction = serde_json::from_str(r#"{"ref_block_num":1,"ref_block_prefix":2,"expiration":"2016-04-06T08:29:27","operations":[],"extensions":[]}"#).unwrap();
+    /// assert_eq!(UNIX_EPOCH + Duration::from_secs(1459931367), tx.expiration_as_datetime().unwrap())
+    /// ```
+    pub fn expiration_as_datetime(&self) -> Result<SystemTime, TransactionError> {
+        let seconds = parse_time(&self.expiration).ok_or_else(|| TransactionError::InvalidExpiration(self.expiration.clone()))?;
+
+        Ok(UNIX_EPOCH + Duration::from_secs(seconds))
+    }
+
+    /// Whether the expiration has passed by the system clock, nodes reject
+    /// expired transactions so they have to be rebuilt and signed again. A
+    /// transaction whose expiration does not parse counts as expired
+    pub fn is_expired(&self) -> bool {
+        parse_time(&self.expiration).is_none_or(|seconds| seconds <= now_seconds())
+    }
+
+    /// Packs the transaction into the hive binary format. Only the header
+    /// fields are bounds checked here, call `validate` for the rest
+    pub fn to_bytes(&self) -> Result<Vec<u8>, TransactionError> {
+        let expiration = parse_time(&self.expiration)
+            .and_then(|time| u32::try_from(time).ok())
+            .ok_or_else(|| TransactionError::InvalidExpiration(self.expiration.clone()))?;
+        let mut buf = Vec::new();
+
+        // ref_block_num is the low 16 bits of the block number
+        buf.extend_from_slice(&(self.ref_block_num as u16).to_le_bytes());
+        buf.extend_from_slice(&(self.ref_block_prefix as u32).to_le_bytes());
+        buf.extend_from_slice(&expiration.to_le_bytes());
+
+        write_varint(&mut buf, self.operations.len() as u64);
+        for operation in &self.operations {
+            operation.pack(&mut buf)?;
+        }
+
+        if let Some(extension) = self.extensions.first() {
+            return Err(TransactionError::UnsupportedExtension(extension.clone()))
+        }
+        write_varint(&mut buf, 0);
+
+        Ok(buf)
+    }
+
+    /// Reads a transaction packed by `to_bytes`, e.g. from a node's
+    /// `get_transaction_hex` with the signatures cut off. Operations without a
+    /// typed struct come back raw, with their assets as legacy strings
+    /// ```
+    /// use tetanus::hive::transactions::Transaction;
+    /// let tx: Transaction = serde_json::from_str(r#"{"ref_block_num":34294,"ref_block_prefix":3707022213,"expiration":"2016-04-06T08:29:27","operations":[["convert",{"owner":"alice","requestid":1,"amount":"1.000 HBD"}]],"extensions":[]}"#).unwrap();
+    /// assert_eq!(tx, Transaction::from_bytes(&tx.to_bytes().unwrap()).unwrap())
+    /// ```
+    pub fn from_bytes(data: &[u8]) -> Result<Transaction, PackingError> {
+        let mut unpacker = Unpacker::new(data);
+
+        let ref_block_num = unpacker.u16()? as u64;
+        let ref_block_prefix = unpacker.u32()? as u64;
+        let expiration = unpacker.time()?;
+        let operations = (0..unpacker.count()?).map(|_| Operation::unpack(&mut unpacker)).collect::<Result<_, _>>()?;
+
+        // hive defines no transaction extensions so there is nothing to read
+        if unpacker.varint()? != 0 {
+            return Err(PackingError::InvalidLength)
+        }
+        unpacker.finish()?;
+
+        Ok(Transaction {
+            ref_block_num,
+            ref_block_prefix,
+            expiration,
+            operations,
+            extensions: vec![],
+            signatures: vec![],
+            chain_id: None,
+        })
+    }
+
+    /// Estimated size of the signed transaction, the packed transaction plus
+    /// 65 bytes for each signature and the varint that counts them. Counts the
+    /// signatures already added, or one if there are none, so add the other
+    /// signatures of a multisig transaction first. Fails like `to_bytes`
+    pub fn estimated_bytes(&self) -> Result<usize, TransactionError> {
+        let signature_count = self.expected_signatures();
+        let mut count = vec![];
+        write_varint(&mut count, signature_count as u64);
+
+        Ok(self.to_bytes()?.len() + count.len() + signature_count * SIGNATURE_SIZE)
+    }
+
+    /// The signatures the cost estimates count: the ones already added, or
+    /// one for a transaction that is not signed yet
+    fn expected_signatures(&self) -> usize {
+        self.signatures.len().max(1)
+    }
+
+    /// A rough estimate of the resource credits the transaction consumes for
+    /// its size. Accounts regenerate their full mana, which equals their vests,
+    /// over 5 days so the whole vesting supply buys every byte of block space
+    /// in that window. The estimate prices `estimated_bytes` at that rate and
+    /// leaves out the execution and state costs the node also charges, so
+    /// treat it as a lower bound
+    pub fn estimated_rc_cost(&self, global_props: &DynamicGlobalProperties) -> Result<u64, TransactionError> {
+        let bytes = self.estimated_bytes()? as u128;
+        let total_vests = global_props.total_vesting_shares.amount.max(0) as u128;
+        let block_space = global_props.maximum_block_size.max(1) as u128 * RC_REGENERATION_BLOCKS as u128;
+
+        Ok((bytes * total_vests / block_space) as u64)
+    }
+
+    /// The resource credits the transaction costs at the current prices,
+    /// `params` and `pools` come from `rc_api.get_resource_params` and
+    /// `rc_api.get_resource_pool`. Counts the history bytes of
+    /// `estimated_bytes` and the execution time of the transaction and each
+    /// operation, operations the node lists no time for count as free. State
+    /// bytes and account creation are left out, so like `estimated_rc_cost`
+    /// this is a lower bound
+    pub fn rc_cost(
+        &self,
+        params: &RcResourceParams,
+        pools: &BTreeMap<String, i64>,
+        global_props: &DynamicGlobalProperties,
+    ) -> Result<u64, TransactionError> {
+        let execution_time = |name: &str| params.size_info.resource_execution_time.get(name).copied().unwrap_or(0).max(0) as u64;
+        let time = self.operations.iter().map(|op| execution_time(&format!("{}_time", op.name()))).sum::<u64>();
+
+        let usage = [
+            ("resource_history_bytes", self.estimated_bytes()? as u64),
+            ("resource_execution_time", execution_time("transaction_time") + time),
+        ];
+        let rc_regen = global_props.total_vesting_shares.amount.max(0) as u64 / RC_REGENERATION_BLOCKS;
+
+        Ok(usage.iter().filter_map(|(resource, count)| {
+            let param = params.resource_params.get(*resource)?;
+            let pool = pools.get(*resource).copied().unwrap_or(0);
+
+            Some(param.price_curve_params.cost(pool, count * param.resource_dynamics_params.resource_unit, rc_regen))
+        }).sum())
+    }
+
+    /// Appends an operation, typed operation structs convert into `Operation`
+    pub fn add_operation(mut self, op: impl Into<Operation>) -> Self {
+        self.operations.push(op.into());
+        self
+    }
+
+    /// Appends an operation from its name and json fields, for operations
+    /// added before the crate had typed structs. `op_data` has to be a json
+    /// object. Like `Operation::new`, known operations are parsed into their
+    /// typed struct
+    #[deprecated(note = "use `add_operation` with a typed operation, or with `Operation::new` for operations without one")]
+    pub fn add_operation_raw(self, op_name: &str, op_data: Value) -> Result<Self, TransactionError> {
+        match op_data {
+            Value::Object(data) => Ok(self.add_operation(Operation::new(op_name, data))),
+            other => Err(TransactionError::InvalidValue {
+                field: op_name.to_string(),
+                reason: format!("operation data must be a json object, got {}", other),
+            }),
+        }
+    }
+
+    /// Sets the chain the transaction is signed for, usually `HIVE_CHAIN_ID`.
+    /// Without a chain id the packed transaction is signed on its own
+    pub fn with_chain_id(mut self, chain_id: [u8; 32]) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// The digest signers sign, the sha256 of the chain id (if any) followed
+    /// by the packed transaction
+    pub fn digest(&self) -> Result<[u8; 32], TransactionError> {
+        let bytes = self.to_bytes()?;
+
+        Ok(match self.chain_id {
+            Some(chain_id) => sha256([&chain_id[..], &bytes].concat()),
+            None => sha256(bytes),
+        })
+    }
+
+    /// The transaction id hive nodes report, the hex encoded first 20 bytes of
+    /// the sha256 of the packed transaction. Neither signatures nor the chain
+    /// id are part of it
+    pub fn id(&self) -> Result<String, TransactionError> {
+        let hash = sha256(self.to_bytes()?);
+
+        Ok(to_hex(&hash[..20]))
+    }
+
+    /// Serializes the transaction in the schema older libraries such as
+    /// steem-js and beem use: operations as `["name", {...}]` pairs, assets as
+    /// `"1.000 HIVE"` strings even if they were given as NAI objects, and the
+    /// signatures as hex with the recovery id first
+    pub fn to_legacy_json(&self) -> Result<String, TransactionError> {
+        let mut json = serde_json::to_value(self)?;
+
+        if let Some(operations) = json.get_mut("operations") {
+            legacy_assets(operations)?;
+        }
+        if json.get("signatures").is_none() {
+            json["signatures"] = Value::Array(vec![]);
+        }
+
+        Ok(serde_json::to_string(&json)?)
+    }
+
+    /// Validates the transaction, parses `key` and then signs it with
+    /// `PrivateKey::sign_transaction`, errors are returned rather than
+    /// panicking
+    pub fn try_sign(&self, key: &str) -> Result<SignatureWrapper, TransactionError> {
+        self.validate()?;
+
+        key.parse::<PrivateKey>()?.sign_transaction(self)
+    }
+
+    /// The signatures collected so far
+    pub fn signatures(&self) -> &[SignatureWrapper] {
+        &self.signatures
+    }
+
+    /// Adds a signature produced by one of the signers, e.g. with `try_sign`
+    /// on another machine. Signatures that are already present are ignored so
+    /// collected sets can be merged freely
+    pub fn add_signature(&mut self, sig: SignatureWrapper) {
+        if !self.signatures.contains(&sig) {
+            self.signatures.push(sig);
+        }
+    }
+
+    /// Checks the collected signatures offline, before the transaction is
+    /// broadcast: for each signature, in order, whether it recovers to one of
+    /// `public_keys`. A signature that recovers to no key at all, or to a key
+    /// for a different chain id, is `false`
+    pub fn verify_signatures(&self, public_keys: &[&str]) -> Result<Vec<bool>, TransactionError> {
+        let keys = public_keys
+            .iter()
+            .map(|key| key.parse::<PublicKey>().map_err(|_| TransactionError::InvalidValue {
+                field: "public_keys".to_string(),
+                reason: format!("{} is not a public key", key),
+            }))
+            .collect::<Result<Vec<_>, _>>()?;
+        let digest = self.digest()?;
+
+        Ok(self.signatures
+            .iter()
+            .map(|sig| PublicKey::recover_prehashed(sig, digest).is_ok_and(|signer| keys.contains(&signer)))
+            .collect())
+    }
+
+    /// Returns the keys of `account`'s `key_role` authority that should sign,
+    /// the heaviest keys first until their weights reach the threshold. Account
+    /// auths are not followed since that needs the other accounts' authorities,
+    /// if the keys alone cannot reach the threshold all of them are returned
+    pub fn required_signers(&self, account: &HiveAccount, key_role: AuthRole) -> Vec<String> {
+        let authority = account.authority(key_role);
+        let mut keys = authority.key_auths.clone();
+        keys.sort_by_key(|(_, weight)| Reverse(*weight));
+
+        let mut weight = 0u32;
+        keys.into_iter()
+            .take_while(|(_, key_weight)| {
+                let needed = weight < authority.weight_threshold;
+                weight += *key_weight as u32;
+                needed
+            })
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    /// Whether the collected signatures satisfy `account`'s `key_role`
+    /// authority, i.e. the weights of the keys that signed reach its threshold
+    pub fn is_fully_signed(&self, account: &HiveAccount, key_role: AuthRole) -> bool {
+        let digest = match self.digest() {
+            Ok(digest) => digest,
+            Err(_) => return false,
+        };
+        let signers: Vec<String> = self.signatures
+            .iter()
+            .filter_map(|sig| PublicKey::recover_prehashed(sig, digest).ok())
+            .map(|public| public.to_string(None))
+            .collect();
+
+        let authority = account.authority(key_role);
+        let weight: u32 = authority.key_auths
+            .iter()
+            .filter(|(key, _)| signers.contains(key))
+            .map(|(_, weight)| *weight as u32)
+            .sum();
+
+        weight >= authority.weight_threshold
+    }
+}
+
+/// Hashes the transaction `id`, which packs and hashes the whole transaction, so
+/// keying maps by a precomputed `id` is cheaper than hashing the transaction
+/// repeatedly. Transactions that differ only in their signatures hash the same
+impl Hash for Transaction {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id().ok().hash(state);
+    }
+}
+
+/// A one line summary for logs, e.g. `Transaction { ref_block: 34294/3707022213,
+/// expiration: 2016-04-06T08:29:27, ops: [vote], sigs: 1 }`
+impl fmt::Display for Transaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ops: Vec<String> = self.operations.iter().map(Operation::to_string).collect();
+
+        write!(
+            f,
+            "Transaction {{ ref_block: {}/{}, expiration: {}, ops: [{}], sigs: {} }}",
+            self.ref_block_num,
+            self.ref_block_prefix,
+            self.expiration,
+            ops.join(", "),
+            self.signatures.len()
+        )
+    }
+}
+
+/// The last year packed expirations, `u32` seconds, reach into
+const MAX_YEAR: u64 = 2106;
+
+/// Parses a hive `YYYY-MM-DDTHH:MM:SS` utc time into seconds since the unix
+/// epoch. Years after 2106 are rejected since they don't fit a packed
+/// expiration
+pub(crate) fn parse_time(time: &str) -> Option<u64> {
+    let (date, clock) = time.split_once('T')?;
+    let date: Vec<u64> = date.split('-').map(|part| part.parse().ok()).collect::<Option<_>>()?;
+    let clock: Vec<u64> = clock.split(':').map(|part| part.parse().ok()).collect::<Option<_>>()?;
+
+    let (year, month, day) = match date[..] { [y, m, d] => (y, m, d), _ => return None };
+    let (hour, minute, second) = match clock[..] { [h, m, s] => (h, m, s), _ => return None };
+
+    let months = month_days(year);
+
+    if !(1970..=MAX_YEAR).contains(&year) || !(1..=12).contains(&month) || day == 0 || day > months[month as usize - 1]
+        || hour > 23 || minute > 59 || second > 59 {
+        return None
+    }
+
+    let days = (1970..year).map(|y| month_days(y).iter().sum::<u64>()).sum::<u64>()
+        + months[..month as usize - 1].iter().sum::<u64>()
+        + day - 1;
+
+    Some(days * 86400 + hour * 3600 + minute * 60 + second)
+}
+
+/// Formats seconds since the unix epoch as a hive `YYYY-MM-DDTHH:MM:SS` utc
+/// time, the reverse of `parse_time`
+pub(crate) fn format_time(seconds: u64) -> String {
+    let (mut days, clock) = (seconds / 86400, seconds % 86400);
+
+    let mut year = 1970;
+    while days >= month_days(year).iter().sum::<u64>() {
+        days -= month_days(year).iter().sum::<u64>();
+        year += 1;
+    }
+
+    let mut month = 0;
+    while days >= month_days(year)[month] {
+        days -= month_days(year)[month];
+        month += 1;
+    }
+
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}", year, month + 1, days + 1, clock / 3600, clock % 3600 / 60, clock % 60)
+}
+
+fn month_days(year: u64) -> [u64; 12] {
+    let leap = (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400);
+
+    [31, if leap { 29 } else { 28 }, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::transactions::*;
+    use crate::hive::operations::*;
+    use crate::hive::types::{AssetSymbol, Authority};
+    use quickcheck::quickcheck;
+    use serde_json::{json, Map};
+
+    fn vote() -> Transaction {
+        let data = json!({ "voter": "alice", "author": "bob", "permlink": "a-post", "weight": 10000 });
+
+        Transaction {
+            ref_block_num: 1234,
+            ref_block_prefix: 5678,
+            expiration: String::from("2024-01-01T12:00:00"),
+            operations: vec![Operation::new("vote", data.as_object().unwrap().clone())],
+            extensions: vec![],
+            signatures: vec![],
+            chain_id: None,
+        }
+    }
+
+    #[test]
+    fn valid_transaction_signs() {
+        let private = PrivateKey::from_login("test", "test", "owner");
+
+        assert!(vote().validate().is_ok());
+        assert_eq!(private.sign_transaction(&vote()).unwrap(), vote().try_sign(&private.to_string()).unwrap());
+    }
+
+    #[test]
+    fn signatures_round_trip_json() {
+        let mut tx = vote();
+        for role in ["owner", "active"] {
+            tx.add_signature(PrivateKey::from_login("test", "test", role).sign_transaction(&tx).unwrap());
+        }
+
+        let json = serde_json::to_value(&tx).unwrap();
+        let mut first = tx.signatures[0].sig();
+        first.rotate_right(1);
+        assert_eq!(json!(to_hex(&first)), json["signatures"][0]);
+        assert_eq!(tx, serde_json::from_value(json).unwrap());
+        assert_eq!(tx, serde_json::from_str(&tx.to_legacy_json().unwrap()).unwrap());
+
+        // unsigned transactions leave the field out, and read back without it
+        assert!(serde_json::to_value(vote()).unwrap().get("signatures").is_none());
+        assert_eq!(vote(), serde_json::from_value(serde_json::to_value(vote()).unwrap()).unwrap());
+
+        let mut bad = serde_json::to_value(&tx).unwrap();
+        bad["signatures"][0] = json!("00");
+        assert!(serde_json::from_value::<Transaction>(bad).is_err());
+    }
+
+    #[test]
+    fn bad_keys_are_errors() {
+        let wif = PrivateKey::from_login("test", "test", "owner").to_string();
+        let last = if wif.ends_with('2') { "3" } else { "2" };
+        let corrupt = wif[..wif.len() - 1].to_string() + last;
+
+        assert!(matches!(vote().try_sign(&corrupt), Err(TransactionError::InvalidKey(PrivateKeyError::InvalidChecksum))));
+        assert!(matches!(vote().try_sign("not a key"), Err(TransactionError::InvalidKey(_))));
+    }
+
+    #[test]
+    fn chain_id_is_signed() {
+        let private = PrivateKey::from_login("test", "test", "owner");
+        let tx = vote().with_chain_id(HIVE_CHAIN_ID);
+        let sig = private.sign_transaction(&tx).unwrap();
+
+        let digest = sha256([&HIVE_CHAIN_ID[..], &tx.to_bytes().unwrap()].concat());
+        assert_eq!(digest, tx.digest().unwrap());
+        assert_eq!(private.to_public(), PublicKey::recover_prehashed(&sig, digest).unwrap());
+        assert_ne!(sig, private.sign_transaction(&vote()).unwrap());
+    }
+
+    #[test]
+    fn first_error_is_returned() {
+        let mut tx = vote();
+        tx.ref_block_num = 0;
+        tx.expiration = String::from("2024-02-30T12:00:00");
+        assert!(matches!(tx.validate(), Err(TransactionError::InvalidExpiration(_))));
+
+        tx.operations.clear();
+        assert!(matches!(tx.try_sign(""), Err(TransactionError::NoOperations)));
+
+        let mut tx = vote();
+        tx.ref_block_num = 0;
+        assert!(matches!(tx.validate(), Err(TransactionError::ZeroRefBlockNum)));
+
+        let mut tx = vote();
+        tx.extensions.push(String::from("foo"));
+        assert!(matches!(tx.validate(), Err(TransactionError::UnsupportedExtension(_))));
+    }
+
+    #[test]
+    fn operations_validate() {
+        let mut tx = vote();
+        tx.operations.push(Operation::new("upvote", Map::new()));
+        assert!(matches!(tx.validate(), Err(TransactionError::UnknownOperation(name)) if name == "upvote"));
+
+        let data = json!({ "from": "alice", "to": "Bob", "amount": "1.000 HIVE", "memo": "" });
+        let op = Operation::new("transfer", data.as_object().unwrap().clone());
+        assert!(matches!(
+            op.validate(),
+            Err(TransactionError::InvalidAccountName { field, error: AccountNameError::InvalidCharacter('B') }) if field == "to"
+        ));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn raw_operations_are_appended() {
+        let mut tx = vote();
+        tx.operations.clear();
+        let tx = tx
+            .add_operation_raw("vote", json!({ "voter": "alice", "author": "bob", "permlink": "a-post", "weight": 10000 })).unwrap()
+            .add_operation_raw("recurrent_transfer", json!({ "from": "alice", "to": "bob" })).unwrap();
+
+        assert_eq!(vote().operations[0], tx.operations[0]);
+        assert_eq!(Operation::Raw("recurrent_transfer".to_string(), json!({ "from": "alice", "to": "bob" }).as_object().unwrap().clone()), tx.operations[1]);
+
+        for data in [json!("not an object"), json!(null), json!([1, 2])] {
+            assert!(matches!(
+                tx.clone().add_operation_raw("upvote", data),
+                Err(TransactionError::InvalidValue { field, .. }) if field == "upvote"
+            ));
+        }
+    }
+
+    #[test]
+    fn packs_known_vote() {
+        // reference transaction from the steem-js serializer tests
+        let mut tx = vote();
+        tx.ref_block_num = 34294;
+        tx.ref_block_prefix = 3707022213;
+        tx.expiration = String::from("2016-04-06T08:29:27");
+        let data = json!({ "voter": "foo", "author": "bar", "permlink": "baz", "weight": 10000 });
+        tx.operations = vec![Operation::new("vote", data.as_object().unwrap().clone())];
+
+        let expected = [
+            0xf6, 0x85, 0x85, 0xab, 0xf4, 0xdc, 0xe7, 0xc8, 0x04, 0x57, 0x01, 0x00,
+            0x03, 0x66, 0x6f, 0x6f, 0x03, 0x62, 0x61, 0x72, 0x03, 0x62, 0x61, 0x7a, 0x10, 0x27, 0x00,
+        ];
+        assert_eq!(expected.to_vec(), tx.to_bytes().unwrap());
+        assert_eq!(expected.len() + 66, tx.estimated_bytes().unwrap());
+
+        for role in ["owner", "active"] {
+            tx.add_signature(PrivateKey::from_login("test", "test", role).sign_transaction(&tx).unwrap());
+        }
+        assert_eq!(expected.len() + 1 + 2 * 65, tx.estimated_bytes().unwrap());
+    }
+
+    #[test]
+    fn packs_transfer_asset() {
+        let mut tx = vote();
+        let data = json!({ "from": "foo", "to": "bar", "amount": "1.000 HIVE", "memo": "" });
+        tx.operations = vec![Operation::new("transfer", data.as_object().unwrap().clone())];
+
+        let bytes = tx.to_bytes().unwrap();
+        let asset = [0xe8, 0x03, 0, 0, 0, 0, 0, 0, 0x03, b'S', b'T', b'E', b'E', b'M', 0, 0];
+        assert_eq!(0x02, bytes[11]);
+        assert_eq!(asset, bytes[20..36]);
+
+        let data = json!({ "from": "foo", "to": "bar", "amount": { "amount": "1000", "precision": 3, "nai": "@@000000021" }, "memo": "" });
+        tx.operations = vec![Operation::new("transfer", data.as_object().unwrap().clone())];
+        assert_eq!(bytes, tx.to_bytes().unwrap());
+
+        tx.operations = vec![Operation::new("pow", Map::new())];
+        assert!(matches!(tx.to_bytes(), Err(TransactionError::UnsupportedOperation(_))));
+    }
+
+    #[test]
+    fn rc_cost_scales_with_size() {
+        let props: DynamicGlobalProperties = serde_json::from_value(json!({
+            "head_block_number": 1, "head_block_id": "", "time": "2024-01-01T00:00:00", "current_witness": "gtg",
+            "current_supply": "1.000 HIVE", "current_hbd_supply": "1.000 HBD", "total_vesting_fund_hive": "1.000 HIVE",
+            "total_vesting_shares": "288111908748.970313 VESTS", "hbd_interest_rate": 2000,
+            "last_irreversible_block_num": 1, "maximum_block_size": 65536
+        })).unwrap();
+
+        let small = vote().estimated_rc_cost(&props).unwrap();
+        let mut tx = vote();
+        tx.operations.push(Operation::new("vote", tx.operations[0].data().clone()));
+
+        let bytes = vote().estimated_bytes().unwrap() as u128;
+        assert_eq!((bytes * 288111908748970313 / (65536 * 144000)) as u64, small);
+        assert!(tx.estimated_rc_cost(&props).unwrap() > small);
+
+        // a multisig transaction is priced with all of its signatures
+        let mut signed = vote();
+        for role in ["owner", "active"] {
+            let sig = PrivateKey::from_login("test", "test", role).sign_transaction(&signed).unwrap();
+            signed.add_signature(sig);
+        }
+        let bytes = signed.estimated_bytes().unwrap() as u128;
+        assert_eq!(vote().estimated_bytes().unwrap() as u128 + 65, bytes);
+        assert_eq!((bytes * 288111908748970313 / (65536 * 144000)) as u64, signed.estimated_rc_cost(&props).unwrap());
+    }
+
+    #[test]
+    fn rc_cost_prices_bytes_and_execution_time() {
+        let props: DynamicGlobalProperties = serde_json::from_value(json!({
+            "head_block_number": 1, "head_block_id": "", "time": "2024-01-01T00:00:00", "current_witness": "gtg",
+            "current_supply": "1.000 HIVE", "current_hbd_supply": "1.000 HBD", "total_vesting_fund_hive": "1.000 HIVE",
+            "total_vesting_shares": "0.144000 VESTS", "hbd_interest_rate": 2000,
+            "last_irreversible_block_num": 1, "maximum_block_size": 65536
+        })).unwrap();
+        let curve = json!({ "coeff_a": 1, "coeff_b": 1, "shift": 0 });
+        let params: RcResourceParams = serde_json::from_value(json!({
+            "resource_names": ["resource_history_bytes", "resource_execution_time"],
+            "resource_params": {
+                "resource_history_bytes": { "resource_dynamics_params": { "resource_unit": 10 }, "price_curve_params": curve },
+                "resource_execution_time": { "resource_dynamics_params": { "resource_unit": 1 }, "price_curve_params": curve },
+            },
+            "size_info": { "resource_execution_time": { "transaction_time": 100, "vote_time": 20 } }
+        })).unwrap();
+        let pools = BTreeMap::from([("resource_history_bytes".to_string(), 1), ("resource_execution_time".to_string(), 1)]);
+
+        // a regen of 1 per block makes each unit cost 2 over a denominator of 2, plus 1 per resource
+        let bytes = vote().estimated_bytes().unwrap() as u64;
+        assert_eq!(bytes * 10 + 1 + 120 + 1, vote().rc_cost(&params, &pools, &props).unwrap());
+
+        // operations without a listed time cost nothing to execute
+        let mut tx = vote();
+        let transfer = json!({ "from": "alice", "to": "bob", "amount": "1.000 HIVE", "memo": "" });
+        tx.operations = vec![Operation::new("transfer", transfer.as_object().unwrap().clone())];
+        let bytes = tx.estimated_bytes().unwrap() as u64;
+        assert_eq!(bytes * 10 + 1 + 100 + 1, tx.rc_cost(&params, &pools, &props).unwrap());
+    }
+
+    fn multisig_account(threshold: u32, keys: &[(&PrivateKey, u16)]) -> HiveAccount {
+        let authority = json!({
+            "weight_threshold": threshold,
+            "account_auths": [],
+            "key_auths": keys.iter().map(|(key, weight)| json!([key.to_public().to_string(None), weight])).collect::<Vec<_>>(),
+        });
+
+        serde_json::from_value(json!({
+            "id": 1, "name": "alice", "owner": authority, "active": authority, "posting": authority,
+            "memo_key": "", "json_metadata": "", "posting_json_metadata": "", "proxy": "", "recovery_account": "",
+            "created": "2020-03-20T14:15:06", "post_count": 0,
+            "voting_manabar": { "current_mana": "0", "last_update_time": 0 },
+            "balance": "0.000 HIVE", "savings_balance": "0.000 HIVE", "hbd_balance": "0.000 HBD",
+            "savings_hbd_balance": "0.000 HBD", "vesting_shares": "0.000000 VESTS",
+            "delegated_vesting_shares": "0.000000 VESTS", "received_vesting_shares": "0.000000 VESTS",
+            "reward_hive_balance": "0.000 HIVE", "reward_hbd_balance": "0.000 HBD", "reward_vesting_balance": "0.000000 VESTS"
+        })).unwrap()
+    }
+
+    #[test]
+    fn collects_multisig_signatures() {
+        let alice = PrivateKey::from_login("alice", "password", "active");
+        let bob = PrivateKey::from_login("bob", "password", "active");
+        let carol = PrivateKey::from_login("carol", "password", "active");
+        let account = multisig_account(3, &[(&alice, 1), (&bob, 2), (&carol, 1)]);
+
+        let expected = vec![bob.to_public().to_string(None), alice.to_public().to_string(None)];
+        assert_eq!(expected, vote().required_signers(&account, AuthRole::Active));
+
+        let mut tx = vote();
+        tx.add_signature(tx.try_sign(&bob.to_string()).unwrap());
+        assert!(!tx.is_fully_signed(&account, AuthRole::Active));
+
+        tx.add_signature(tx.try_sign(&bob.to_string()).unwrap());
+        assert_eq!(1, tx.signatures().len());
+
+        tx.add_signature(tx.try_sign(&carol.to_string()).unwrap());
+        assert!(tx.is_fully_signed(&account, AuthRole::Active));
+    }
+
+    #[test]
+    fn unreachable_threshold_requires_every_key() {
+        let alice = PrivateKey::from_login("alice", "password", "posting");
+        let account = multisig_account(2, &[(&alice, 1)]);
+
+        assert_eq!(vec![alice.to_public().to_string(None)], vote().required_signers(&account, AuthRole::Posting));
+    }
+
+    #[test]
+    fn transactions_hash_by_id() {
+        use std::collections::HashSet;
+
+        let id = vote().id().unwrap();
+        assert_eq!(40, id.len());
+        assert_eq!(id, vote().with_chain_id(HIVE_CHAIN_ID).id().unwrap());
+
+        let mut other = vote();
+        other.ref_block_num = 2;
+        assert_ne!(id, other.id().unwrap());
+
+        let set: HashSet<Transaction> = [vote(), vote(), other].into_iter().collect();
+        assert_eq!(2, set.len());
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(Some(0), parse_time("1970-01-01T00:00:00"));
+        assert_eq!(Some(1704110400), parse_time("2024-01-01T12:00:00"));
+        assert_eq!(Some(1709164800), parse_time("2024-02-29T00:00:00"));
+        assert_eq!(None, parse_time("2023-02-29T00:00:00"));
+        assert_eq!(None, parse_time("2024-01-01 12:00:00"));
+        assert_eq!(None, parse_time("2024-01-01T24:00:00"));
+        assert_eq!(Some(4323283199), parse_time("2106-12-31T23:59:59"));
+        assert_eq!(None, parse_time("2107-01-01T00:00:00"));
+        // would otherwise loop once per year
+        assert_eq!(None, parse_time("99999999999-01-01T00:00:00"));
+    }
+
+    /// An operation of `kind` filled in from the rest of the arguments, covering
+    /// raw and typed operations and every field type
+    fn generated_operation(kind: u8, text: &str, number: u32, amount: i64, flag: bool) -> Operation {
+        let keys = ["STM5jixkNBqJXNtX9vy2GjaqpX2d5jXrcjRXgh1WU5fXZhnDJrLM8", "STM1111111111111111111111111111111114T1Anm"];
+        let key = keys[number as usize % keys.len()].to_string();
+        let raw = |name: &str, data: serde_json::Value| Operation::new(name, data.as_object().unwrap().clone());
+        let authority = Authority { weight_threshold: number, account_auths: vec![(text.to_string(), number as u16)], key_auths: vec![(key.clone(), 1)] };
+
+        match kind % 9 {
+            0 => raw("vote", json!({ "voter": text, "author": "bob", "permlink": text, "weight": number as i16 })),
+            1 => raw("transfer", json!({ "from": text, "to": "bob", "amount": HiveAsset::new(amount, AssetSymbol::Hive).to_string(), "memo": text })),
+            2 => raw("custom_json", json!({ "required_auths": [text], "required_posting_auths": [], "id": "follow", "json": text })),
+            3 => ConvertOperation { owner: text.to_string(), requestid: number, amount: HiveAsset::new(amount, AssetSymbol::Hbd) }.into(),
+            4 => EscrowTransferOperation {
+                from: text.to_string(),
+                to: "bob".to_string(),
+                agent: "carol".to_string(),
+                escrow_id: number,
+                hbd_amount: HiveAsset::new(amount, AssetSymbol::Hbd),
+                hive_amount: HiveAsset::new(amount.wrapping_neg(), AssetSymbol::Hive),
+                fee: HiveAsset::new(amount, AssetSymbol::Vests),
+                ratification_deadline: format_time(number as u64),
+                escrow_expiration: format_time(u32::MAX as u64 - number as u64),
+                json_meta: text.to_string(),
+            }.into(),
+            5 => LimitOrderCreateOperation {
+                owner: text.to_string(),
+                orderid: number,
+                amount_to_sell: HiveAsset::new(amount, AssetSymbol::Hive),
+                min_to_receive: HiveAsset::new(amount, AssetSymbol::Hbd),
+                fill_or_kill: flag,
+                expiration: format_time(number as u64),
+            }.into(),
+            6 => CommentOptionsOperation {
+                author: text.to_string(),
+                permlink: text.to_string(),
+                max_accepted_payout: HiveAsset::new(amount, AssetSymbol::Hbd),
+                percent_hbd: number as u16,
+                allow_votes: flag,
+                allow_curation_rewards: !flag,
+                extensions: vec![CommentOptionsExtension::CommentPayoutBeneficiaries {
+                    beneficiaries: vec![Beneficiary { account: text.to_string(), weight: number as u16 }],
+                }],
+            }.into(),
+            7 => AccountCreateOperation {
+                fee: HiveAsset::new(amount, AssetSymbol::Hive),
+                creator: "alice".to_string(),
+                new_account_name: text.to_string(),
+                owner: authority.clone(),
+                active: authority.clone(),
+                posting: authority,
+                memo_key: key,
+                json_metadata: text.to_string(),
+            }.into(),
+            _ => WitnessUpdateOperation {
+                owner: text.to_string(),
+                url: text.to_string(),
+                block_signing_key: key,
+                props: ChainProperties {
+                    account_creation_fee: HiveAsset::new(amount, AssetSymbol::Hive),
+                    maximum_block_size: number,
+                    hbd_interest_rate: number as u16,
+                },
+                fee: HiveAsset::new(amount, AssetSymbol::Hive),
+            }.into(),
+        }
+    }
+
+    quickcheck! {
+        fn prop_bytes_roundtrip(ref_block_num: u16, ref_block_prefix: u32, expiration: u32, operations: Vec<(u8, String, u32, i64, bool)>) -> bool {
+            let tx = Transaction {
+                ref_block_num: ref_block_num as u64,
+                ref_block_prefix: ref_block_prefix as u64,
+                expiration: format_time(expiration as u64),
+                operations: operations.iter().map(|(kind, text, number, amount, flag)| generated_operation(*kind, text, *number, *amount, *flag)).collect(),
+                extensions: vec![],
+                signatures: vec![],
+                chain_id: None,
+            };
+
+            Transaction::from_bytes(&tx.to_bytes().unwrap()).unwrap() == tx
+        }
+    }
+
+    #[test]
+    fn expiration_times() {
+        let mut tx = vote();
+        assert_eq!(UNIX_EPOCH + Duration::from_secs(1704110400), tx.expiration_as_datetime().unwrap());
+        assert!(tx.is_expired());
+
+        tx.expiration = String::from("2100-01-01T00:00:00");
+        assert!(!tx.is_expired());
+
+        tx.expiration = String::from("2024-13-01T00:00:00");
+        assert!(matches!(tx.expiration_as_datetime(), Err(TransactionError::InvalidExpiration(_))));
+        assert!(tx.is_expired());
+    }
+
+    #[test]
+    fn displays_summary() {
+        let mut tx: Transaction = serde_json::from_value(json!({
+            "ref_block_num": 34294, "ref_block_prefix": 3707022213u32, "expiration": "2016-04-06T08:29:27",
+            "operations": [["transfer", { "from": "alice", "to": "bob", "amount": "1.000 HIVE", "memo": "" }]],
+            "extensions": []
+        })).unwrap();
+        assert_eq!("Transaction { ref_block: 34294/3707022213, expiration: 2016-04-06T08:29:27, ops: [transfer], sigs: 0 }", tx.to_string());
+
+        tx.operations.push(ConvertOperation { owner: "alice".to_string(), requestid: 1, amount: HiveAsset::new(1000, AssetSymbol::Hbd) }.into());
+        tx.signatures.push(PrivateKey::from_login("alice", "password", "active").sign_message("helloworld"));
+        assert_eq!("Transaction { ref_block: 34294/3707022213, expiration: 2016-04-06T08:29:27, ops: [transfer, convert], sigs: 1 }", tx.to_string());
+    }
+
+    #[test]
+    fn times_roundtrip() {
+        for time in ["1970-01-01T00:00:00", "2000-02-29T23:59:59", "2024-12-31T12:30:00", "2106-02-07T06:28:15"] {
+            assert_eq!(time, format_time(parse_time(time).unwrap()));
+        }
+    }
+
+    #[test]
+    fn from_bytes_errors() {
+        let bytes = vote().to_bytes().unwrap();
+        assert_eq!(vote(), Transaction::from_bytes(&bytes).unwrap());
+
+        assert_eq!(Err(PackingError::UnexpectedEnd), Transaction::from_bytes(&bytes[..bytes.len() - 1]));
+        assert_eq!(Err(PackingError::InvalidLength), Transaction::from_bytes(&[&bytes[..], &[0]].concat()));
+
+        let mut unknown = bytes.clone();
+        unknown[11] = 50;
+        assert_eq!(Err(PackingError::UnknownOperationType(50)), Transaction::from_bytes(&unknown));
+
+        // pow is a real operation, just not one that can be unpacked
+        unknown[11] = 14;
+        assert_eq!(Err(PackingError::UnknownOperationType(14)), Transaction::from_bytes(&unknown));
+    }
+}
@@ -0,0 +1,1420 @@
+//! Typed responses returned by the `HiveClient` query methods.
+use std::{collections::BTreeMap, fmt::{self, Display}, str::FromStr};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
+use thiserror::Error;
+use crate::hive::operations::ChainProperties;
+use crate::hive::transactions::parse_time;
+use crate::hive::packing::{pack_string, read_array, write_varint, PackingError, Unpacker};
+use crate::hive::utils::{now_seconds, validate_account_name, AccountNameError};
+use crate::keys::public::PublicKey;
+use crate::types::chain::KeyType;
+
+/// Asset identifier of HIVE in the HF24+ NAI json format
+pub const HIVE_NAI: &str = "@@000000021";
+/// Asset identifier of HBD in the HF24+ NAI json format
+pub const HBD_NAI: &str = "@@000000013";
+/// Asset identifier of VESTS in the HF24+ NAI json format
+pub const VESTS_NAI: &str = "@@000000037";
+
+/// The symbols a `HiveAsset` amount can be denominated in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetSymbol {
+    Hive,
+    Hbd,
+    Vests,
+}
+
+impl AssetSymbol {
+    /// Number of decimal places amounts of this symbol are expressed with
+    pub fn precision(&self) -> u8 {
+        match self {
+            AssetSymbol::Hive | AssetSymbol::Hbd => 3,
+            AssetSymbol::Vests => 6,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AssetSymbol::Hive => "HIVE",
+            AssetSymbol::Hbd => "HBD",
+            AssetSymbol::Vests => "VESTS",
+        }
+    }
+
+    /// The symbol's NAI, e.g. `HIVE_NAI`
+    pub fn nai(&self) -> &'static str {
+        match self {
+            AssetSymbol::Hive => HIVE_NAI,
+            AssetSymbol::Hbd => HBD_NAI,
+            AssetSymbol::Vests => VESTS_NAI,
+        }
+    }
+
+    /// Looks up the symbol with the given NAI
+    pub fn from_nai(nai: &str) -> Result<Self, AssetError> {
+        match nai {
+            HIVE_NAI => Ok(AssetSymbol::Hive),
+            HBD_NAI => Ok(AssetSymbol::Hbd),
+            VESTS_NAI => Ok(AssetSymbol::Vests),
+            _ => Err(AssetError::UnknownSymbol(nai.to_string())),
+        }
+    }
+}
+
+impl FromStr for AssetSymbol {
+    type Err = AssetError;
+
+    /// Also accepts the pre-fork `STEEM` and `SBD` names, which are still
+    /// used by the binary format and by some older nodes
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "HIVE" | "STEEM" => Ok(AssetSymbol::Hive),
+            "HBD" | "SBD" => Ok(AssetSymbol::Hbd),
+            "VESTS" => Ok(AssetSymbol::Vests),
+            _ => Err(AssetError::UnknownSymbol(s.to_string())),
+        }
+    }
+}
+
+/// An error involving parsing an asset string.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AssetError {
+    /// The string is not of the form `<amount> <symbol>`
+    #[error("invalid asset string {0}")]
+    InvalidFormat(String),
+    /// The symbol is not one of HIVE, HBD or VESTS
+    #[error("unknown asset symbol {0}")]
+    UnknownSymbol(String),
+}
+
+/// An amount of HIVE, HBD or VESTS stored as an integer number of the
+/// smallest unit, i.e. `1.000 HIVE` is an `amount` of 1000 with a `precision`
+/// of 3
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HiveAsset {
+    pub amount: i64,
+    pub precision: u8,
+    pub symbol: AssetSymbol,
+}
+
+impl HiveAsset {
+    /// Creates an asset from an amount in the smallest unit of `symbol`
+    /// ```
+    /// use tetanus::hive::types::{AssetSymbol, HiveAsset};
+    /// let asset = HiveAsset::new(1000, AssetSymbol::Hive);
+    /// assert_eq!("1.000 HIVE", asset.to_string())
+    /// ```
+    pub fn new(amount: i64, symbol: AssetSymbol) -> Self {
+        HiveAsset { amount, precision: symbol.precision(), symbol }
+    }
+
+    /// Serializes the asset to the HF24+ NAI json format
+    /// ```
+    /// use tetanus::hive::types::{AssetSymbol, HiveAsset};
+    /// let asset = HiveAsset::new(1000, AssetSymbol::Hive);
+    /// assert_eq!(r#"{"amount":"1000","precision":3,"nai":"@@000000021"}"#, asset.to_nai());
+    /// assert_eq!(asset, asset.to_nai().parse().unwrap())
+    /// ```
+    pub fn to_nai(&self) -> String {
+        self.nai_value().to_string()
+    }
+
+    fn nai_value(&self) -> Value {
+        json!({ "amount": self.amount.to_string(), "precision": self.precision, "nai": self.symbol.nai() })
+    }
+
+    /// Reads an asset from either of the forms nodes use, a legacy
+    /// `"1.000 HIVE"` string or a NAI object
+    pub(crate) fn from_value(value: &Value) -> Result<Self, AssetError> {
+        match value {
+            Value::String(s) => s.parse(),
+            Value::Object(object) => {
+                let invalid = || AssetError::InvalidFormat(value.to_string());
+                let symbol = AssetSymbol::from_nai(object.get("nai").and_then(Value::as_str).ok_or_else(invalid)?)?;
+                let precision = object.get("precision").and_then(Value::as_u64).ok_or_else(invalid)?;
+                // the amount is a string so 64 bit values survive javascript
+                let amount = match object.get("amount") {
+                    Some(Value::String(amount)) => amount.parse().map_err(|_| invalid())?,
+                    Some(Value::Number(amount)) => amount.as_i64().ok_or_else(invalid)?,
+                    _ => return Err(invalid()),
+                };
+
+                if precision != symbol.precision() as u64 {
+                    return Err(invalid())
+                }
+
+                Ok(HiveAsset::new(amount, symbol))
+            }
+            _ => Err(AssetError::InvalidFormat(value.to_string())),
+        }
+    }
+
+    /// Packs the asset into its 16 byte binary form, the amount followed by
+    /// the precision and the symbol padded to 7 bytes. HIVE and HBD keep their
+    /// pre-fork `STEEM` and `SBD` names here so signatures stay compatible
+    pub fn pack(&self, buf: &mut Vec<u8>) {
+        let symbol = match self.symbol {
+            AssetSymbol::Hive => "STEEM",
+            AssetSymbol::Hbd => "SBD",
+            AssetSymbol::Vests => "VESTS",
+        };
+
+        buf.extend_from_slice(&self.amount.to_le_bytes());
+        buf.push(self.precision);
+        buf.extend_from_slice(symbol.as_bytes());
+        buf.resize(buf.len() + 7 - symbol.len(), 0);
+    }
+
+    /// Reads an asset packed by `pack` at `offset`, returning it with the
+    /// offset just past it
+    pub fn unpack(buf: &[u8], offset: usize) -> Result<(HiveAsset, usize), PackingError> {
+        let (amount, offset) = read_array::<8>(buf, offset)?;
+        let (precision, offset) = read_array::<1>(buf, offset)?;
+        let (symbol, offset) = read_array::<7>(buf, offset)?;
+
+        let symbol = core::str::from_utf8(&symbol).map_err(PackingError::InvalidUtf8)?;
+        let symbol: AssetSymbol = symbol.trim_end_matches('\0').parse().map_err(|_| PackingError::AssetSymbolMismatch)?;
+        if precision[0] != symbol.precision() {
+            return Err(PackingError::AssetSymbolMismatch)
+        }
+
+        Ok((HiveAsset::new(i64::from_le_bytes(amount), symbol), offset))
+    }
+}
+
+impl fmt::Display for HiveAsset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scale = 10u64.pow(self.precision as u32);
+        let sign = if self.amount < 0 { "-" } else { "" };
+        let amount = self.amount.unsigned_abs();
+
+        write!(
+            f,
+            "{}{}.{:0width$} {}",
+            sign,
+            amount / scale,
+            amount % scale,
+            self.symbol.as_str(),
+            width = self.precision as usize
+        )
+    }
+}
+
+impl FromStr for HiveAsset {
+    type Err = AssetError;
+
+    /// Parses the legacy `"1.000 HIVE"` form, the amount may have fewer
+    /// decimals than the symbol's precision but not more. NAI json strings are
+    /// accepted as well
+    /// ```
+    /// use tetanus::hive::types::{AssetSymbol, HiveAsset};
+    /// let asset: HiveAsset = "12.5 HBD".parse().unwrap();
+    /// assert_eq!(HiveAsset::new(12500, AssetSymbol::Hbd), asset);
+    ///
+    /// let nai = r#"{"amount":"12500","precision":3,"nai":"@@000000013"}"#;
+    /// assert_eq!(asset, nai.parse().unwrap())
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim_start().starts_with('{') {
+            let value: Value = serde_json::from_str(s).map_err(|_| AssetError::InvalidFormat(s.to_string()))?;
+            return HiveAsset::from_value(&value)
+        }
+
+        let invalid = || AssetError::InvalidFormat(s.to_string());
+        let (amount, symbol) = s.trim().split_once(' ').ok_or_else(invalid)?;
+        let symbol: AssetSymbol = symbol.parse()?;
+        let precision = symbol.precision() as usize;
+
+        let (negative, amount) = match amount.strip_prefix('-') {
+            Some(amount) => (true, amount),
+            None => (false, amount),
+        };
+        let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+        let digits = |d: &str| d.chars().all(|c| c.is_ascii_digit());
+        if whole.is_empty() || !digits(whole) || !digits(fraction) || fraction.len() > precision {
+            return Err(invalid())
+        }
+
+        // the sign is parsed with the digits so the most negative amount fits
+        let units = format!("{}{}{:0<width$}", if negative { "-" } else { "" }, whole, fraction, width = precision);
+        let amount: i64 = units.parse().map_err(|_| invalid())?;
+
+        Ok(HiveAsset::new(amount, symbol))
+    }
+}
+
+/// Serializes to the legacy `"1.000 HIVE"` string, or to a NAI object with
+/// the `nai` feature
+impl Serialize for HiveAsset {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg(feature = "nai")]
+        return self.nai_value().serialize(serializer);
+
+        #[cfg(not(feature = "nai"))]
+        serializer.collect_str(self)
+    }
+}
+
+/// Accepts both the legacy string and the NAI object form
+impl<'de> Deserialize<'de> for HiveAsset {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        HiveAsset::from_value(&value).map_err(de::Error::custom)
+    }
+}
+
+/// Voting or resource credit mana, regenerating over time from
+/// `last_update_time`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Manabar {
+    #[serde(deserialize_with = "string_or_number")]
+    pub current_mana: i64,
+    pub last_update_time: u64,
+}
+
+/// Resource credit state of an account as returned by `rc_api.find_rc_accounts`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RcAccount {
+    pub account: String,
+    pub rc_manabar: Manabar,
+    /// The most resource credits the account can have, including what it
+    /// received and excluding what it delegated
+    #[serde(deserialize_with = "string_or_number")]
+    pub max_rc: u64,
+    /// Resource credits the account delegated to others
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub delegated_rc: u64,
+    /// Resource credits others delegated to the account
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub received_delegated_rc: u64,
+}
+
+/// Resource credits regenerate in full over five days
+const RC_REGENERATION_SECONDS: u64 = 5 * 24 * 60 * 60;
+
+impl RcAccount {
+    /// How many more transactions costing `tx_cost` resource credits the
+    /// account can afford right now, counting what its manabar regenerated
+    /// since `last_update_time`. A free transaction is always affordable
+    pub fn estimated_transactions_remaining(&self, tx_cost: u64) -> u64 {
+        if tx_cost == 0 {
+            return u64::MAX
+        }
+
+        self.current_mana() / tx_cost
+    }
+
+    /// The resource credits the account has right now, its manabar plus what
+    /// regenerated since `last_update_time`, capped at `max_rc`
+    pub fn current_mana(&self) -> u64 {
+        let elapsed = now_seconds().saturating_sub(self.rc_manabar.last_update_time);
+        let regenerated = (self.max_rc as u128 * elapsed as u128 / RC_REGENERATION_SECONDS as u128) as u64;
+
+        (self.rc_manabar.current_mana.max(0) as u64).saturating_add(regenerated).min(self.max_rc)
+    }
+}
+
+/// The price curve of a resource, resource credits get more expensive as the
+/// resource's pool drains
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RcPriceCurve {
+    #[serde(deserialize_with = "string_or_number")]
+    pub coeff_a: u64,
+    #[serde(deserialize_with = "string_or_number")]
+    pub coeff_b: u64,
+    pub shift: u8,
+}
+
+impl RcPriceCurve {
+    /// The resource credits `count` units of the resource cost while its pool
+    /// holds `pool`, where `rc_regen` is the resource credits the whole chain
+    /// regenerates per block. This is hived's `compute_rc_cost_of_resource`
+    pub fn cost(&self, pool: i64, count: u64, rc_regen: u64) -> u64 {
+        if count == 0 {
+            return 0
+        }
+
+        let num = ((rc_regen as u128 * self.coeff_a as u128) >> self.shift) + 1;
+        let denom = (self.coeff_b as u128 + pool.max(0) as u128).max(1);
+
+        ((num * count as u128 / denom) as u64).saturating_add(1)
+    }
+}
+
+/// How a resource is metered, only the unit its usage is counted in is kept
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RcResourceDynamics {
+    #[serde(deserialize_with = "string_or_number")]
+    pub resource_unit: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RcResourceParam {
+    pub resource_dynamics_params: RcResourceDynamics,
+    pub price_curve_params: RcPriceCurve,
+}
+
+/// The resource sizes the node charges for, here only the execution times
+/// such as `transaction_time` and `transfer_time`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct RcSizeInfo {
+    #[serde(default)]
+    pub resource_execution_time: BTreeMap<String, i64>,
+}
+
+/// The priced resources as returned by `rc_api.get_resource_params`, keyed by
+/// names such as `resource_history_bytes`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RcResourceParams {
+    pub resource_names: Vec<String>,
+    pub resource_params: BTreeMap<String, RcResourceParam>,
+    #[serde(default)]
+    pub size_info: RcSizeInfo,
+}
+
+/// Whether an account can afford a transaction, see
+/// `HiveClientTrait::estimate_rc_cost`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RcEstimate {
+    /// The resource credits the transaction costs
+    pub cost: u64,
+    /// The resource credits the account has right now
+    pub available: u64,
+    pub sufficient: bool,
+}
+
+/// A reward pool as returned by `condenser_api.get_reward_fund`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RewardFund {
+    pub name: String,
+    pub reward_balance: HiveAsset,
+    /// A u128 sent as a string
+    pub recent_claims: String,
+    pub content_constant: String,
+}
+
+impl RewardFund {
+    /// Converts `rshares` into the share of `reward_balance` they currently
+    /// claim, using the linear reward curve in effect since HF21. Negative
+    /// rshares pay out nothing
+    /// ```
+    /// use tetanus::hive::types::{AssetSymbol, HiveAsset, RewardFund};
+    /// let fund = RewardFund {
+    ///     name: "post".to_string(),
+    ///     reward_balance: HiveAsset::new(1_000_000, AssetSymbol::Hive),
+    ///     recent_claims: "4000000".to_string(),
+    ///     content_constant: "2000000000000".to_string(),
+    /// };
+    /// assert_eq!(HiveAsset::new(250_000, AssetSymbol::Hive), fund.reward_per_rshare(1_000_000))
+    /// ```
+    pub fn reward_per_rshare(&self, rshares: i64) -> HiveAsset {
+        let recent_claims: u128 = self.recent_claims.parse().unwrap_or(0);
+        if rshares <= 0 || recent_claims == 0 || self.reward_balance.amount <= 0 {
+            return HiveAsset::new(0, self.reward_balance.symbol)
+        }
+
+        let payout = self.reward_balance.amount as u128 * rshares as u128 / recent_claims;
+
+        HiveAsset::new(payout.min(i64::MAX as u128) as i64, self.reward_balance.symbol)
+    }
+}
+
+/// An exchange rate between two assets, `base` per `quote`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct Price {
+    pub base: HiveAsset,
+    pub quote: HiveAsset,
+}
+
+/// The witness price feed as returned by `condenser_api.get_feed_history`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FeedHistory {
+    pub current_median_history: Price,
+    pub price_history: Vec<Price>,
+}
+
+/// An unfilled order on the internal market as returned by
+/// `condenser_api.get_open_orders`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct OpenOrder {
+    pub id: u64,
+    pub created: String,
+    pub expiration: String,
+    pub seller: String,
+    /// The id the seller gave the order, used to cancel it
+    pub orderid: u32,
+    /// What is left to sell, in satoshis of `sell_price.base`
+    #[serde(deserialize_with = "string_or_number")]
+    pub for_sale: u64,
+    pub sell_price: Price,
+    /// The price as a decimal string, HBD per HIVE
+    pub real_price: String,
+}
+
+impl OpenOrder {
+    /// Whether the expiration has passed by the system clock, the chain
+    /// removes expired orders and refunds what was left. An expiration that
+    /// does not parse counts as expired
+    pub fn is_expired(&self) -> bool {
+        parse_time(&self.expiration).is_none_or(|seconds| seconds <= now_seconds())
+    }
+}
+
+/// The internal market's HIVE/HBD summary of the last day as returned by
+/// `market_history_api.get_ticker`, prices are HBD per HIVE
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct MarketTicker {
+    pub latest: String,
+    pub lowest_ask: String,
+    pub highest_bid: String,
+    pub percent_change: String,
+    #[serde(alias = "steem_volume")]
+    pub hive_volume: HiveAsset,
+    #[serde(alias = "sbd_volume")]
+    pub hbd_volume: HiveAsset,
+}
+
+/// One price level of the internal market as returned by
+/// `condenser_api.get_order_book`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct OrderBookEntry {
+    pub created: String,
+    pub order_price: Price,
+    /// The price as a decimal string, HBD per HIVE
+    pub real_price: String,
+    /// The HIVE on offer, in satoshis
+    #[serde(alias = "steem", deserialize_with = "string_or_number")]
+    pub hive: u64,
+    /// The HBD on offer, in satoshis
+    #[serde(alias = "sbd", deserialize_with = "string_or_number")]
+    pub hbd: u64,
+}
+
+/// The best bids and asks of the internal market, best price first
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct OrderBook {
+    pub bids: Vec<OrderBookEntry>,
+    pub asks: Vec<OrderBookEntry>,
+}
+
+/// A filled order of the internal market as returned by
+/// `market_history_api.get_trade_history`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Trade {
+    pub date: String,
+    /// What the taker of the order paid
+    pub current_pays: HiveAsset,
+    /// What the maker of the order paid
+    pub open_pays: HiveAsset,
+}
+
+#[cfg(any(feature = "hive-client", test))]
+#[derive(Deserialize)]
+pub(crate) struct Trades { pub trades: Vec<Trade> }
+
+#[cfg(any(feature = "hive-client", test))]
+#[derive(Deserialize)]
+pub(crate) struct BucketSizes { pub bucket_sizes: Vec<u32> }
+
+/// An account's raw reputation as returned by
+/// `condenser_api.get_account_reputations`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AccountReputation {
+    pub account: String,
+    /// The reputation in rshares, negative for accounts that were mostly
+    /// downvoted
+    #[serde(deserialize_with = "string_or_number")]
+    pub reputation: i64,
+}
+
+impl AccountReputation {
+    /// The reputation on the scale front-ends show, where new accounts
+    /// start at 25 and every tenfold of rshares beyond 10^9 adds 9
+    /// ```
+    /// use tetanus::hive::types::AccountReputation;
+    /// let rep = AccountReputation { account: "alice".to_string(), reputation: 95720068393725 };
+    /// assert_eq!(69, rep.to_display_score())
+    /// ```
+    pub fn to_display_score(&self) -> i64 {
+        let magnitude = ((self.reputation.unsigned_abs() as f64).log10() - 9.0).max(0.0);
+        let score = if self.reputation < 0 { -magnitude } else { magnitude };
+
+        (score * 9.0 + 25.0) as i64
+    }
+}
+
+/// Which of an account's authorities a transaction needs, lower roles can be
+/// signed for by the roles above them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuthRole {
+    Owner,
+    Active,
+    Posting,
+}
+
+/// A weighted set of keys and accounts, any combination whose weights add up
+/// to `weight_threshold` satisfies the authority
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Authority {
+    pub weight_threshold: u32,
+    pub account_auths: Vec<(String, u16)>,
+    pub key_auths: Vec<(String, u16)>,
+}
+
+/// The reason an `Authority` was rejected
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AuthorityError {
+    #[error("invalid account {account}: {error}")]
+    InvalidAccount { account: String, error: AccountNameError },
+    /// The key is not a K1 public key string
+    #[error("invalid key {0}")]
+    InvalidKey(String),
+    /// Listed more than once, hive keeps authorities as maps
+    #[error("duplicate entry {0}")]
+    Duplicate(String),
+    /// Even every key and account together can't reach the threshold, so
+    /// nothing could ever sign for the authority
+    #[error("weights add up to {total_weight}, below the threshold of {weight_threshold}")]
+    Impossible { weight_threshold: u32, total_weight: u64 },
+}
+
+impl Authority {
+    /// Checks the account names and keys, and that the authority can be
+    /// satisfied at all
+    /// ```
+    /// use tetanus::hive::types::{Authority, AuthorityError};
+    /// let authority = Authority {
+    ///     weight_threshold: 2,
+    ///     account_auths: vec![("alice".to_string(), 1)],
+    ///     key_auths: vec![("STM5jixkNBqJXNtX9vy2GjaqpX2d5jXrcjRXgh1WU5fXZhnDJrLM8".to_string(), 1)],
+    /// };
+    /// assert_eq!(Ok(()), authority.validate());
+    ///
+    /// let locked = Authority { weight_threshold: 3, ..authority };
+    /// assert_eq!(Err(AuthorityError::Impossible { weight_threshold: 3, total_weight: 2 }), locked.validate());
+    /// ```
+    pub fn validate(&self) -> Result<(), AuthorityError> {
+        for (i, (account, _)) in self.account_auths.iter().enumerate() {
+            validate_account_name(account)
+                .map_err(|error| AuthorityError::InvalidAccount { account: account.clone(), error })?;
+            if self.account_auths[..i].iter().any(|(other, _)| other == account) {
+                return Err(AuthorityError::Duplicate(account.clone()))
+            }
+        }
+
+        for (i, (key, _)) in self.key_auths.iter().enumerate() {
+            match key.parse::<PublicKey>() {
+                Ok(public) if public.key_type() == KeyType::K1 => {},
+                _ => return Err(AuthorityError::InvalidKey(key.clone())),
+            }
+            if self.key_auths[..i].iter().any(|(other, _)| other == key) {
+                return Err(AuthorityError::Duplicate(key.clone()))
+            }
+        }
+
+        let total_weight: u64 = self.account_auths.iter().chain(&self.key_auths).map(|(_, weight)| *weight as u64).sum();
+        if total_weight < self.weight_threshold as u64 {
+            return Err(AuthorityError::Impossible { weight_threshold: self.weight_threshold, total_weight })
+        }
+
+        Ok(())
+    }
+
+    /// Packs the authority in the order hived keeps it, accounts sorted by
+    /// name and keys by their compressed bytes
+    pub(crate) fn pack(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.weight_threshold.to_le_bytes());
+
+        let mut accounts: Vec<_> = self.account_auths.iter().collect();
+        accounts.sort();
+        write_varint(buf, accounts.len() as u64);
+        for (account, weight) in accounts {
+            pack_string(buf, account);
+            buf.extend_from_slice(&weight.to_le_bytes());
+        }
+
+        let mut keys: Vec<_> = self.key_auths.iter().map(|(key, weight)| (public_key_bytes(key), weight)).collect();
+        keys.sort();
+        write_varint(buf, keys.len() as u64);
+        for (key, weight) in keys {
+            buf.extend_from_slice(&key);
+            buf.extend_from_slice(&weight.to_le_bytes());
+        }
+    }
+
+    /// Reads an authority packed by `pack`, so with its entries sorted
+    pub(crate) fn unpack(unpacker: &mut Unpacker) -> Result<Authority, PackingError> {
+        let weight_threshold = unpacker.u32()?;
+        let account_auths = (0..unpacker.count()?)
+            .map(|_| Ok((unpacker.string()?, unpacker.u16()?)))
+            .collect::<Result<_, PackingError>>()?;
+        let key_auths = (0..unpacker.count()?)
+            .map(|_| Ok((unpacker.public_key()?, unpacker.u16()?)))
+            .collect::<Result<_, PackingError>>()?;
+
+        Ok(Authority { weight_threshold, account_auths, key_auths })
+    }
+}
+
+/// The packed form of a public key string, all zeros if it doesn't parse
+pub(crate) fn public_key_bytes(key: &str) -> [u8; 33] {
+    key.parse::<PublicKey>()
+        .ok()
+        .and_then(|public| public.as_bytes().try_into().ok())
+        .unwrap_or([0; 33])
+}
+
+/// Chain wide state as returned by `condenser_api.get_dynamic_global_properties`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DynamicGlobalProperties {
+    pub head_block_number: u32,
+    pub head_block_id: String,
+    pub time: String,
+    pub current_witness: String,
+    pub current_supply: HiveAsset,
+    pub current_hbd_supply: HiveAsset,
+    pub total_vesting_fund_hive: HiveAsset,
+    pub total_vesting_shares: HiveAsset,
+    pub hbd_interest_rate: u16,
+    pub last_irreversible_block_num: u32,
+    /// Largest block witnesses will produce, in bytes
+    pub maximum_block_size: u32,
+}
+
+/// An account as returned by `condenser_api.get_accounts` or
+/// `database_api.find_accounts`. The database_api sends assets as NAI objects
+/// and leaves out the condenser extras such as `reputation` and
+/// `vesting_balance`, both read into the same fields
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct HiveAccount {
+    pub id: u64,
+    pub name: String,
+    pub owner: Authority,
+    pub active: Authority,
+    pub posting: Authority,
+    pub memo_key: String,
+    pub json_metadata: String,
+    pub posting_json_metadata: String,
+    pub proxy: String,
+    pub recovery_account: String,
+    pub created: String,
+    pub post_count: u64,
+    pub voting_manabar: Manabar,
+    pub balance: HiveAsset,
+    pub savings_balance: HiveAsset,
+    pub hbd_balance: HiveAsset,
+    pub savings_hbd_balance: HiveAsset,
+    pub vesting_shares: HiveAsset,
+    pub delegated_vesting_shares: HiveAsset,
+    pub received_vesting_shares: HiveAsset,
+    pub reward_hive_balance: HiveAsset,
+    pub reward_hbd_balance: HiveAsset,
+    pub reward_vesting_balance: HiveAsset,
+}
+
+impl HiveAccount {
+    /// Returns the authority for `role`
+    pub fn authority(&self, role: AuthRole) -> &Authority {
+        match role {
+            AuthRole::Owner => &self.owner,
+            AuthRole::Active => &self.active,
+            AuthRole::Posting => &self.posting,
+        }
+    }
+}
+
+#[cfg(any(feature = "hive-client", test))]
+#[derive(Deserialize)]
+pub(crate) struct DatabaseAccounts { pub accounts: Vec<HiveAccount> }
+
+/// A block as returned by `condenser_api.get_block`, transactions are kept as
+/// raw json
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Block {
+    pub block_id: String,
+    pub previous: String,
+    pub timestamp: String,
+    pub witness: String,
+    pub transaction_merkle_root: String,
+    pub witness_signature: String,
+    pub signing_key: String,
+    pub transactions: Vec<serde_json::Value>,
+    pub transaction_ids: Vec<String>,
+}
+
+/// A post or comment as returned by `condenser_api.get_content`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Content {
+    pub id: u64,
+    pub author: String,
+    pub permlink: String,
+    pub category: String,
+    pub parent_author: String,
+    pub parent_permlink: String,
+    pub title: String,
+    pub body: String,
+    pub json_metadata: String,
+    pub created: String,
+    pub last_update: String,
+    pub depth: u32,
+    pub children: u32,
+    #[serde(deserialize_with = "string_or_number")]
+    pub net_rshares: i64,
+    #[serde(deserialize_with = "string_or_number")]
+    pub author_reputation: i64,
+    pub pending_payout_value: HiveAsset,
+    pub total_payout_value: HiveAsset,
+    pub curator_payout_value: HiveAsset,
+    pub url: String,
+}
+
+/// The untyped result of `condenser_api.get_state`, the data a condenser page
+/// at a path such as `@alice` or `trending/hive` needs. The members are
+/// - `accounts`, accounts by name
+/// - `content`, posts and comments by `author/permlink`
+/// - `discussion_idx`, the permlinks listed under each tag and sort order
+/// - `tags`, `tag_idx`, `feed_price`, `props` and `current_route`
+///
+/// Which ones are filled depends on the path
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct StateResponse(pub Value);
+
+impl StateResponse {
+    /// The account called `name`, if the state has it
+    pub fn get_account(&self, name: &str) -> Option<HiveAccount> {
+        serde_json::from_value(self.0.get("accounts")?.get(name)?.clone()).ok()
+    }
+
+    /// The post or comment at `@author/permlink`, if the state has it
+    pub fn get_content(&self, author: &str, permlink: &str) -> Option<Content> {
+        serde_json::from_value(self.0.get("content")?.get(format!("{author}/{permlink}"))?.clone()).ok()
+    }
+}
+
+/// A post on an account's blog as returned by `follow_api.get_blog`, either
+/// one the account wrote or one it reblogged
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BlogEntry {
+    /// The account whose blog this is
+    pub blog: String,
+    /// Position on the blog, counting up from 0 for the oldest entry
+    pub entry_id: u32,
+    pub comment: Content,
+    /// When the post was reblogged, empty if `blog` wrote it
+    #[serde(rename = "reblogged_on", deserialize_with = "time_or_epoch")]
+    pub reblog_on: String,
+}
+
+#[cfg(any(feature = "hive-client", test))]
+#[derive(Deserialize)]
+pub(crate) struct Blog { pub blog: Vec<BlogEntry> }
+
+/// The kind of follow, following a blog or muting (ignoring) an account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FollowType {
+    Blog,
+    Ignore,
+}
+
+/// One follow relation as returned by `follow_api.get_followers` and
+/// `follow_api.get_following`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FollowEntry {
+    pub follower: String,
+    pub following: String,
+    pub what: Vec<FollowType>,
+}
+
+#[cfg(any(feature = "hive-client", test))]
+#[derive(Deserialize)]
+pub(crate) struct Followers { pub followers: Vec<FollowEntry> }
+
+#[cfg(any(feature = "hive-client", test))]
+#[derive(Deserialize)]
+pub(crate) struct Following { pub following: Vec<FollowEntry> }
+
+/// The result of `condenser_api.broadcast_transaction_synchronous`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BroadcastResult {
+    pub id: String,
+    pub block_num: u32,
+    pub trx_num: u32,
+    pub expired: bool,
+}
+
+/// The witness production schedule as returned by
+/// `condenser_api.get_witness_schedule`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct WitnessSchedule {
+    /// A u128 sent as a string
+    pub current_virtual_time: String,
+    pub next_shuffle_block_num: u32,
+    /// The witnesses producing the current round, in order
+    pub current_shuffled_witnesses: Vec<String>,
+    pub num_scheduled_witnesses: u8,
+}
+
+/// A vote cast by an account as returned by `condenser_api.get_account_votes`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AccountVote {
+    /// The voted post as `author/permlink`
+    pub authorperm: String,
+    #[serde(deserialize_with = "string_or_number")]
+    pub weight: u64,
+    #[serde(deserialize_with = "string_or_number")]
+    pub rshares: i64,
+    pub time: String,
+}
+
+/// A vote on a post as returned by `condenser_api.get_active_votes`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Vote {
+    pub voter: String,
+    /// The vote's share of the curation rewards, 0 for downvotes
+    #[serde(deserialize_with = "string_or_number")]
+    pub weight: u64,
+    #[serde(deserialize_with = "string_or_number")]
+    pub rshares: i64,
+    /// The vote strength in basis points, -10000 to 10000
+    pub percent: i16,
+    /// The voter's reputation in rshares
+    #[serde(deserialize_with = "string_or_number")]
+    pub reputation: i64,
+    pub time: String,
+}
+
+impl Vote {
+    /// Whether the vote is a flag, removed votes (0%) are neither up nor down
+    pub fn is_downvote(&self) -> bool {
+        self.percent < 0
+    }
+
+    /// How much of the voter's voting power the vote used, as a percentage
+    /// ```
+    /// use tetanus::hive::types::Vote;
+    /// let vote = Vote { voter: "alice".to_string(), weight: 0, rshares: -52087, percent: -2550, reputation: 0, time: String::new() };
+    /// assert_eq!(-25.5, vote.voting_power_percentage());
+    /// assert!(vote.is_downvote());
+    /// ```
+    pub fn voting_power_percentage(&self) -> f64 {
+        self.percent as f64 / 100.0
+    }
+}
+
+/// A witness as returned by `condenser_api.get_witnesses_by_vote`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Witness {
+    pub owner: String,
+    pub signing_key: String,
+    pub url: String,
+    pub props: ChainProperties,
+    /// The summed vesting shares of the accounts voting for it
+    #[serde(deserialize_with = "string_or_number")]
+    pub votes: u64,
+    /// The scheduling state, u128s sent as strings
+    pub virtual_last_update: String,
+    pub virtual_position: String,
+    pub virtual_scheduled_time: String,
+    pub running_version: String,
+}
+
+/// One entry of `condenser_api.get_account_history`, nodes send these as
+/// `[sequence, {...}]` pairs
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(from = "(u64, HistoryItem)")]
+pub struct AccountHistoryEntry {
+    /// Position in the account's history, starting from 0
+    pub sequence: u64,
+    pub block: u32,
+    /// All zeros for virtual operations
+    pub trx_id: String,
+    pub timestamp: String,
+    /// The operation as a `["name", {...}]` pair
+    pub op: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct HistoryItem { block: u32, trx_id: String, timestamp: String, op: serde_json::Value }
+
+impl From<(u64, HistoryItem)> for AccountHistoryEntry {
+    fn from((sequence, item): (u64, HistoryItem)) -> Self {
+        AccountHistoryEntry { sequence, block: item.block, trx_id: item.trx_id, timestamp: item.timestamp, op: item.op }
+    }
+}
+
+#[cfg(any(feature = "hive-client", test))]
+#[derive(Deserialize)]
+pub(crate) struct RcAccounts { pub rc_accounts: Vec<RcAccount> }
+
+#[cfg(any(feature = "hive-client", test))]
+#[derive(Deserialize)]
+pub(crate) struct RcPool { #[serde(deserialize_with = "string_or_number")] pub pool: i64 }
+
+#[cfg(any(feature = "hive-client", test))]
+#[derive(Deserialize)]
+pub(crate) struct RcResourcePool { pub resource_pool: BTreeMap<String, RcPool> }
+
+/// Where a proposal is in its funding period
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProposalStatus {
+    /// Not started yet
+    Inactive,
+    /// Within its funding period, paid if it has enough votes
+    Active,
+    /// Past its end date
+    Expired,
+}
+
+/// A Decentralized Hive Fund proposal as returned by
+/// `database_api.find_proposals`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Proposal {
+    pub id: u64,
+    /// Equal to `id` unless the proposal has been updated
+    pub proposal_id: u64,
+    pub creator: String,
+    /// The account the daily pay goes to
+    pub receiver: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub daily_pay: HiveAsset,
+    pub subject: String,
+    pub status: ProposalStatus,
+    /// The summed vesting shares of the voters, a u128 sent as a string
+    #[serde(deserialize_with = "string_or_number")]
+    pub total_votes: String,
+}
+
+#[cfg(any(feature = "hive-client", test))]
+#[derive(Deserialize)]
+pub(crate) struct Proposals { pub proposals: Vec<Proposal> }
+
+/// Nodes send 64 bit integers as json strings to avoid precision loss in
+/// javascript, but not consistently, so both forms are accepted
+pub(crate) fn string_or_number<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrNumber {
+        String(String),
+        Number(serde_json::Number),
+    }
+
+    match StringOrNumber::deserialize(deserializer)? {
+        StringOrNumber::String(s) => s.parse().map_err(de::Error::custom),
+        StringOrNumber::Number(n) => n.to_string().parse().map_err(de::Error::custom),
+    }
+}
+
+/// Nodes send the unix epoch for times that were never set, which reads
+/// better as an empty string
+fn time_or_epoch<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let time = String::deserialize(deserializer)?;
+
+    Ok(if time == "1970-01-01T00:00:00" { String::new() } else { time })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::hive::types::*;
+
+    #[test]
+    fn displays_assets() {
+        let vectors = [
+            (0, AssetSymbol::Hive, "0.000 HIVE"),
+            (1, AssetSymbol::Hive, "0.001 HIVE"),
+            (1000, AssetSymbol::Hive, "1.000 HIVE"),
+            (i64::MAX, AssetSymbol::Hive, "9223372036854775.807 HIVE"),
+            (i64::MIN, AssetSymbol::Hive, "-9223372036854775.808 HIVE"),
+            (0, AssetSymbol::Hbd, "0.000 HBD"),
+            (1, AssetSymbol::Hbd, "0.001 HBD"),
+            (i64::MAX, AssetSymbol::Hbd, "9223372036854775.807 HBD"),
+            (i64::MIN, AssetSymbol::Hbd, "-9223372036854775.808 HBD"),
+            (0, AssetSymbol::Vests, "0.000000 VESTS"),
+            (1, AssetSymbol::Vests, "0.000001 VESTS"),
+            (1_000_000_000_000, AssetSymbol::Vests, "1000000.000000 VESTS"),
+            (i64::MAX, AssetSymbol::Vests, "9223372036854.775807 VESTS"),
+            (i64::MIN, AssetSymbol::Vests, "-9223372036854.775808 VESTS"),
+        ];
+
+        for (amount, symbol, expected) in vectors {
+            let asset = HiveAsset::new(amount, symbol);
+            assert_eq!(expected, asset.to_string());
+            assert_eq!(Ok(asset), expected.parse());
+        }
+    }
+
+    #[test]
+    fn nai_assets() {
+        let vests: HiveAsset = serde_json::from_str(r#"{"amount":"288111908748970313","precision":6,"nai":"@@000000037"}"#).unwrap();
+        assert_eq!(HiveAsset::new(288111908748970313, AssetSymbol::Vests), vests);
+        assert_eq!(vests, serde_json::from_str(r#""288111908748.970313 VESTS""#).unwrap());
+        assert_eq!(r#"{"amount":"-5","precision":3,"nai":"@@000000013"}"#, HiveAsset::new(-5, AssetSymbol::Hbd).to_nai());
+
+        assert_eq!(Err(AssetError::UnknownSymbol("@@000000099".to_string())), r#"{"amount":"1","precision":3,"nai":"@@000000099"}"#.parse::<HiveAsset>());
+        assert!(r#"{"amount":"1","precision":6,"nai":"@@000000021"}"#.parse::<HiveAsset>().is_err());
+        assert!(r#"{"amount":"1.5","precision":3,"nai":"@@000000021"}"#.parse::<HiveAsset>().is_err());
+
+        #[cfg(feature = "nai")]
+        assert_eq!(serde_json::json!({ "amount": "1000", "precision": 3, "nai": HIVE_NAI }), serde_json::to_value(HiveAsset::new(1000, AssetSymbol::Hive)).unwrap());
+        #[cfg(not(feature = "nai"))]
+        assert_eq!(serde_json::json!("1.000 HIVE"), serde_json::to_value(HiveAsset::new(1000, AssetSymbol::Hive)).unwrap());
+    }
+
+    #[test]
+    fn packed_assets() {
+        for asset in [HiveAsset::new(1000, AssetSymbol::Hive), HiveAsset::new(-5, AssetSymbol::Hbd), HiveAsset::new(i64::MAX, AssetSymbol::Vests)] {
+            let mut buf = vec![0xff];
+            asset.pack(&mut buf);
+            assert_eq!(Ok((asset, 17)), HiveAsset::unpack(&buf, 1));
+        }
+
+        let mut buf = vec![];
+        HiveAsset::new(1000, AssetSymbol::Hive).pack(&mut buf);
+        assert_eq!(Err(PackingError::UnexpectedEnd), HiveAsset::unpack(&buf[..15], 0));
+
+        buf[8] = 6;
+        assert_eq!(Err(PackingError::AssetSymbolMismatch), HiveAsset::unpack(&buf, 0));
+        buf[8] = 3;
+        buf[9..16].copy_from_slice(b"BTC\0\0\0\0");
+        assert_eq!(Err(PackingError::AssetSymbolMismatch), HiveAsset::unpack(&buf, 0));
+    }
+
+    #[test]
+    fn rc_accounts_fixture() {
+        let fixture = r#"{"rc_accounts":[{"account":"alice","rc_manabar":{"current_mana":"24873553627","last_update_time":1656030729},"max_rc_creation_adjustment":{"amount":"2020748973","precision":6,"nai":"@@000000037"},"max_rc":"25262654523","delegated_rc":0,"received_delegated_rc":"5000000000"}]}"#;
+        let accounts: RcAccounts = serde_json::from_str(fixture).unwrap();
+
+        assert_eq!(vec![RcAccount {
+            account: "alice".to_string(),
+            rc_manabar: Manabar { current_mana: 24873553627, last_update_time: 1656030729 },
+            max_rc: 25262654523,
+            delegated_rc: 0,
+            received_delegated_rc: 5000000000,
+        }], accounts.rc_accounts)
+    }
+
+    #[test]
+    fn rc_transactions_remaining() {
+        let now = now_seconds();
+        let account = RcAccount {
+            account: "alice".to_string(),
+            rc_manabar: Manabar { current_mana: 1_000_000, last_update_time: now },
+            max_rc: 10_000_000,
+            delegated_rc: 0,
+            received_delegated_rc: 0,
+        };
+
+        assert_eq!(3, account.estimated_transactions_remaining(300_000));
+        assert_eq!(u64::MAX, account.estimated_transactions_remaining(0));
+
+        // a day regenerates a fifth of max_rc, and mana never exceeds it
+        let yesterday = RcAccount { rc_manabar: Manabar { current_mana: 1_000_000, last_update_time: now - 24 * 60 * 60 }, ..account.clone() };
+        assert_eq!(10, yesterday.estimated_transactions_remaining(300_000));
+        let idle = RcAccount { rc_manabar: Manabar { current_mana: 1_000_000, last_update_time: 0 }, ..account };
+        assert_eq!(33, idle.estimated_transactions_remaining(300_000));
+    }
+
+    #[test]
+    fn rc_resource_params_fixture() {
+        let fixture = r#"{"resource_names":["resource_history_bytes","resource_execution_time"],"resource_params":{"resource_history_bytes":{"resource_dynamics_params":{"resource_unit":1,"budget_per_time_unit":347222,"pool_eq":"216404314004","max_pool_size":"432808628007","decay_params":{"decay_per_time_unit":3613026481,"decay_per_time_unit_denom_shift":51},"min_decay":0},"price_curve_params":{"coeff_a":"12981647055416481792","coeff_b":"1690658703","shift":49}},"resource_execution_time":{"resource_dynamics_params":{"resource_unit":1,"budget_per_time_unit":1200000000},"price_curve_params":{"coeff_a":"14469186638119171584","coeff_b":"10883390500","shift":64}}},"size_info":{"resource_execution_time":{"transaction_time":2435,"transfer_time":1613}}}"#;
+        let params: RcResourceParams = serde_json::from_str(fixture).unwrap();
+
+        let history = &params.resource_params["resource_history_bytes"];
+        assert_eq!(RcPriceCurve { coeff_a: 12981647055416481792, coeff_b: 1690658703, shift: 49 }, history.price_curve_params);
+        assert_eq!(1, history.resource_dynamics_params.resource_unit);
+        assert_eq!(Some(&1613), params.size_info.resource_execution_time.get("transfer_time"));
+
+        let pools: RcResourcePool = serde_json::from_str(r#"{"resource_pool":{"resource_history_bytes":{"pool":"199156798102"},"resource_execution_time":{"pool":0}}}"#).unwrap();
+        assert_eq!(199156798102, pools.resource_pool["resource_history_bytes"].pool);
+        assert_eq!(0, pools.resource_pool["resource_execution_time"].pool);
+    }
+
+    #[test]
+    fn rc_price_curve() {
+        let curve = RcPriceCurve { coeff_a: 1 << 10, coeff_b: 10, shift: 10 };
+
+        // (1000 * 2^10 >> 10) + 1 per unit over a denominator of 10 + 9990
+        assert_eq!(0, curve.cost(9990, 0, 1000));
+        assert_eq!(2, curve.cost(9990, 10, 1000));
+        assert_eq!(1002, curve.cost(9990, 10_000, 1000));
+        // a drained pool prices from coeff_b alone
+        assert_eq!(curve.cost(0, 10, 1000), curve.cost(-5, 10, 1000));
+        assert_eq!(1002, curve.cost(0, 10, 1000));
+    }
+
+    #[test]
+    fn reward_fund_fixture() {
+        let fixture = r#"{"id":0,"name":"post","reward_balance":"794092.370 HIVE","recent_claims":"524581605275614642","last_update":"2022-06-24T00:32:12","content_constant":"2000000000000","percent_curation_rewards":5000,"percent_content_rewards":10000,"author_reward_curve":"linear","curation_reward_curve":"linear"}"#;
+        let fund: RewardFund = serde_json::from_str(fixture).unwrap();
+
+        assert_eq!(HiveAsset::new(794092370, AssetSymbol::Hive), fund.reward_balance);
+        assert_eq!("524581605275614642", fund.recent_claims);
+        // 1.5e12 rshares of a 794k HIVE pool with 5.2e17 recent claims
+        assert_eq!(HiveAsset::new(2270, AssetSymbol::Hive), fund.reward_per_rshare(1_500_000_000_000));
+        assert_eq!(HiveAsset::new(0, AssetSymbol::Hive), fund.reward_per_rshare(-1_500_000_000_000));
+    }
+
+    #[test]
+    fn feed_history_fixture() {
+        let fixture = r#"{"id":0,"current_median_history":{"base":"0.293 HBD","quote":"1.000 HIVE"},"market_median_history":{"base":"0.293 HBD","quote":"1.000 HIVE"},"current_min_history":{"base":"0.288 HBD","quote":"1.000 HIVE"},"current_max_history":{"base":"0.301 HBD","quote":"1.000 HIVE"},"price_history":[{"base":"0.292 HBD","quote":"1.000 HIVE"},{"base":"0.295 HBD","quote":"1.000 HIVE"}]}"#;
+        let feed: FeedHistory = serde_json::from_str(fixture).unwrap();
+        let price = |base| Price { base: HiveAsset::new(base, AssetSymbol::Hbd), quote: HiveAsset::new(1000, AssetSymbol::Hive) };
+
+        assert_eq!(price(293), feed.current_median_history);
+        assert_eq!(vec![price(292), price(295)], feed.price_history);
+    }
+
+    #[test]
+    fn dynamic_global_properties_fixture() {
+        let fixture = r#"{"head_block_number":65812108,"head_block_id":"03ec3a8c9f3bd0c3e8e0b8c14a8c2c1b1e2d3c4a","time":"2022-07-01T12:00:00","current_witness":"gtg","total_pow":514415,"num_pow_witnesses":172,"virtual_supply":"464751366.545 HIVE","current_supply":"379258140.249 HIVE","init_hbd_supply":"0.000 HBD","current_hbd_supply":"19868462.153 HBD","total_vesting_fund_hive":"157540383.419 HIVE","total_vesting_shares":"288111908748.970313 VESTS","hbd_interest_rate":2000,"hbd_print_rate":10000,"last_irreversible_block_num":65812091,"maximum_block_size":65536}"#;
+        let props: DynamicGlobalProperties = serde_json::from_str(fixture).unwrap();
+
+        assert_eq!(65812108, props.head_block_number);
+        assert_eq!(HiveAsset::new(288111908748970313, AssetSymbol::Vests), props.total_vesting_shares);
+        assert_eq!(HiveAsset::new(19868462153, AssetSymbol::Hbd), props.current_hbd_supply);
+    }
+
+    #[test]
+    fn witness_schedule_fixture() {
+        let fixture = r#"{"id":0,"current_virtual_time":"749232586319258835801069014","next_shuffle_block_num":65812119,"current_shuffled_witnesses":["gtg","blocktrades","roelandp"],"num_scheduled_witnesses":21,"elected_weight":1,"timeshare_weight":5,"miner_weight":1,"witness_pay_normalization_factor":25,"median_props":{"account_creation_fee":"3.000 HIVE","maximum_block_size":65536,"hbd_interest_rate":2000},"majority_version":"1.25.0","max_voted_witnesses":20,"max_miner_witnesses":0,"max_runner_witnesses":1,"hardfork_required_witnesses":17}"#;
+        let schedule: WitnessSchedule = serde_json::from_str(fixture).unwrap();
+
+        assert_eq!(21, schedule.num_scheduled_witnesses);
+        assert_eq!(65812119, schedule.next_shuffle_block_num);
+        assert_eq!("gtg", schedule.current_shuffled_witnesses[0]);
+    }
+
+    #[test]
+    fn account_votes_fixture() {
+        let fixture = r#"[{"authorperm":"hiveio/a-post","weight":"1241309323","rshares":"8210887741","percent":10000,"time":"2022-07-01T12:00:00"},{"authorperm":"gtg/witness-update","weight":0,"rshares":-52087,"percent":-100,"time":"2022-07-02T08:30:00"}]"#;
+        let votes: Vec<AccountVote> = serde_json::from_str(fixture).unwrap();
+
+        assert_eq!("hiveio/a-post", votes[0].authorperm);
+        assert_eq!(1241309323, votes[0].weight);
+        assert_eq!(8210887741, votes[0].rshares);
+        assert_eq!(-52087, votes[1].rshares);
+        assert_eq!("2022-07-02T08:30:00", votes[1].time);
+    }
+
+    #[test]
+    fn active_votes_fixture() {
+        let fixture = r#"[{"percent":10000,"reputation":"95720068393725","rshares":"8210887741","time":"2022-07-01T12:00:00","voter":"gtg","weight":"1241309323"},{"percent":-100,"reputation":0,"rshares":-52087,"time":"2022-07-02T08:30:00","voter":"spaminator","weight":0}]"#;
+        let votes: Vec<Vote> = serde_json::from_str(fixture).unwrap();
+
+        assert_eq!("gtg", votes[0].voter);
+        assert_eq!(1241309323, votes[0].weight);
+        assert_eq!(95720068393725, votes[0].reputation);
+        assert_eq!(100.0, votes[0].voting_power_percentage());
+        assert!(!votes[0].is_downvote());
+        assert_eq!(-52087, votes[1].rshares);
+        assert_eq!(-1.0, votes[1].voting_power_percentage());
+        assert!(votes[1].is_downvote());
+    }
+
+    #[test]
+    fn proposals_fixture() {
+        let fixture = r#"{"proposals":[{"id":0,"proposal_id":0,"creator":"hive.fund","receiver":"hive.fund","start_date":"2019-08-27T00:00:00","end_date":"2029-12-31T23:59:59","daily_pay":{"amount":"240000000000","precision":3,"nai":"@@000000013"},"subject":"Return Proposal","permlink":"return-proposal","total_votes":"45087341887297865","status":"active"},{"id":7,"proposal_id":7,"creator":"alice","receiver":"bob","start_date":"2020-01-01T00:00:00","end_date":"2020-02-01T00:00:00","daily_pay":"10.000 HBD","subject":"Tooling","permlink":"tooling","total_votes":0,"status":"expired"}]}"#;
+        let proposals: Proposals = serde_json::from_str(fixture).unwrap();
+
+        assert_eq!(HiveAsset::new(240000000000, AssetSymbol::Hbd), proposals.proposals[0].daily_pay);
+        assert_eq!("45087341887297865", proposals.proposals[0].total_votes);
+        assert_eq!(ProposalStatus::Active, proposals.proposals[0].status);
+        assert_eq!("0", proposals.proposals[1].total_votes);
+        assert_eq!(ProposalStatus::Expired, proposals.proposals[1].status);
+    }
+
+    #[test]
+    fn witnesses_fixture() {
+        let fixture = r#"[{"id":1,"owner":"gtg","created":"2016-06-01T00:00:00","url":"https://gtg.openhive.network","votes":"132489621357951019","virtual_last_update":"749232586319258835801069014","virtual_position":"0","virtual_scheduled_time":"749232587221300212473746187","total_missed":982,"last_aslot":67219438,"last_confirmed_block_num":67000000,"signing_key":"STM5F9tCbND6zWPwksy1rEN24WjPiQWSU2vwGgegQVjAcYDe1a3oP","props":{"account_creation_fee":"3.000 HIVE","maximum_block_size":65536,"hbd_interest_rate":2000,"account_subsidy_budget":797,"account_subsidy_decay":347321},"hbd_exchange_rate":{"base":"0.293 HBD","quote":"1.000 HIVE"},"last_hbd_exchange_update":"2022-07-01T12:00:00","running_version":"1.25.0","hardfork_version_vote":"1.25.0","hardfork_time_vote":"2021-06-30T15:00:00"}]"#;
+        let witnesses: Vec<Witness> = serde_json::from_str(fixture).unwrap();
+
+        assert_eq!("gtg", witnesses[0].owner);
+        assert_eq!(132489621357951019, witnesses[0].votes);
+        assert_eq!(HiveAsset::new(3000, AssetSymbol::Hive), witnesses[0].props.account_creation_fee);
+        assert_eq!(2000, witnesses[0].props.hbd_interest_rate);
+        assert_eq!("1.25.0", witnesses[0].running_version);
+    }
+
+    #[test]
+    fn blog_fixture() {
+        let comment = |author: &str| format!(
+            r#"{{"id":1,"author":"{}","permlink":"a-post","category":"hive","parent_author":"","parent_permlink":"hive","title":"A post","body":"...","json_metadata":"{{}}","created":"2022-07-01T12:00:00","last_update":"2022-07-01T12:00:00","depth":0,"children":2,"net_rshares":"125","author_reputation":"95720068393725","pending_payout_value":"1.250 HBD","total_payout_value":"0.000 HBD","curator_payout_value":"0.000 HBD","url":"/hive/@{}/a-post"}}"#,
+            author, author
+        );
+        let fixture = format!(
+            r#"{{"blog":[{{"comment":{},"blog":"alice","entry_id":42,"reblogged_on":"1970-01-01T00:00:00"}},{{"comment":{},"blog":"alice","entry_id":41,"reblogged_on":"2022-07-02T08:30:00"}}]}}"#,
+            comment("alice"), comment("bob")
+        );
+        let blog = serde_json::from_str::<Blog>(&fixture).unwrap().blog;
+
+        assert_eq!(42, blog[0].entry_id);
+        assert_eq!("", blog[0].reblog_on);
+        assert_eq!("alice", blog[1].blog);
+        assert_eq!("bob", blog[1].comment.author);
+        assert_eq!("2022-07-02T08:30:00", blog[1].reblog_on);
+    }
+
+    #[test]
+    fn follows_fixture() {
+        let fixture = r#"{"followers":[{"follower":"alice","following":"hiveio","what":["blog"]},{"follower":"bob","following":"hiveio","what":["ignore"]}]}"#;
+        let follows = serde_json::from_str::<Followers>(fixture).unwrap().followers;
+
+        assert_eq!("alice", follows[0].follower);
+        assert_eq!(vec![FollowType::Blog], follows[0].what);
+        assert_eq!(vec![FollowType::Ignore], follows[1].what);
+        assert_eq!(serde_json::json!("ignore"), serde_json::to_value(FollowType::Ignore).unwrap());
+
+        let fixture = r#"{"following":[{"follower":"hiveio","following":"alice","what":["blog"]}]}"#;
+        assert_eq!("alice", serde_json::from_str::<Following>(fixture).unwrap().following[0].following);
+    }
+
+    #[test]
+    fn ticker_fixture() {
+        let fixture = r#"{"latest":"0.30120481927710843","lowest_ask":"0.30150000000000000","highest_bid":"0.30120481927710843","percent_change":"-1.53","hive_volume":{"amount":"1020541806","precision":3,"nai":"@@000000021"},"hbd_volume":{"amount":"307264215","precision":3,"nai":"@@000000013"}}"#;
+        let ticker: MarketTicker = serde_json::from_str(fixture).unwrap();
+
+        assert_eq!("-1.53", ticker.percent_change);
+        assert_eq!(HiveAsset::new(1020541806, AssetSymbol::Hive), ticker.hive_volume);
+        assert_eq!(HiveAsset::new(307264215, AssetSymbol::Hbd), ticker.hbd_volume);
+
+        let legacy = r#"{"latest":"0.301","lowest_ask":"0.302","highest_bid":"0.301","percent_change":"0.00","steem_volume":"1.000 HIVE","sbd_volume":"0.301 HBD"}"#;
+        assert_eq!(HiveAsset::new(301, AssetSymbol::Hbd), serde_json::from_str::<MarketTicker>(legacy).unwrap().hbd_volume);
+    }
+
+    #[test]
+    fn order_book_fixture() {
+        let fixture = r#"{"bids":[{"created":"2022-07-01T11:59:57","order_price":{"base":"30.120 HBD","quote":"100.000 HIVE"},"real_price":"0.30120000000000002","hive":100000,"hbd":30120}],"asks":[{"created":"2022-07-01T11:58:03","order_price":{"base":"250.000 HIVE","quote":"75.375 HBD"},"real_price":"0.30149999999999999","hive":"250000","hbd":"75375"}]}"#;
+        let book: OrderBook = serde_json::from_str(fixture).unwrap();
+
+        assert_eq!(30120, book.bids[0].hbd);
+        assert_eq!(HiveAsset::new(100000, AssetSymbol::Hive), book.bids[0].order_price.quote);
+        assert_eq!(250000, book.asks[0].hive);
+        assert_eq!("0.30149999999999999", book.asks[0].real_price);
+    }
+
+    #[test]
+    fn trade_history_fixture() {
+        let fixture = r#"{"trades":[{"date":"2022-07-01T12:00:03","current_pays":{"amount":"3012","precision":3,"nai":"@@000000013"},"open_pays":{"amount":"10000","precision":3,"nai":"@@000000021"}},{"date":"2022-07-01T12:00:39","current_pays":"5.000 HIVE","open_pays":"1.506 HBD"}]}"#;
+        let trades: Trades = serde_json::from_str(fixture).unwrap();
+
+        assert_eq!(2, trades.trades.len());
+        assert_eq!(HiveAsset::new(3012, AssetSymbol::Hbd), trades.trades[0].current_pays);
+        assert_eq!(HiveAsset::new(1506, AssetSymbol::Hbd), trades.trades[1].open_pays);
+
+        let buckets: BucketSizes = serde_json::from_str(r#"{"bucket_sizes":[15,60,300,3600,86400]}"#).unwrap();
+        assert_eq!(vec![15, 60, 300, 3600, 86400], buckets.bucket_sizes);
+    }
+
+    #[test]
+    fn reputations_fixture() {
+        let fixture = r#"[{"account":"alice","reputation":"95720068393725"},{"account":"bob","reputation":0},{"account":"spam","reputation":"-10000000000"}]"#;
+        let reputations: Vec<AccountReputation> = serde_json::from_str(fixture).unwrap();
+
+        assert_eq!(95720068393725, reputations[0].reputation);
+        let scores: Vec<i64> = reputations.iter().map(AccountReputation::to_display_score).collect();
+        assert_eq!(vec![69, 25, 16], scores);
+
+        let score = |reputation| AccountReputation { account: "alice".to_string(), reputation }.to_display_score();
+        assert_eq!(25, score(999_999_999));
+        assert_eq!(34, score(10_000_000_000));
+        assert_eq!(-64, score(i64::MIN));
+    }
+
+    #[test]
+    fn open_orders_fixture() {
+        let fixture = r#"[{"id":3392541,"created":"2022-07-01T12:00:00","expiration":"2022-07-29T12:00:00","seller":"alice","orderid":1656676800,"for_sale":10000,"sell_price":{"base":"10.000 HIVE","quote":"3.000 HBD"},"real_price":"0.30000000000000","rewarded":false},{"id":3392542,"created":"2022-07-01T12:00:00","expiration":"2100-01-01T00:00:00","seller":"alice","orderid":2,"for_sale":"2500","sell_price":{"base":"2.500 HBD","quote":"10.000 HIVE"},"real_price":"0.25000000000000","rewarded":false}]"#;
+        let orders: Vec<OpenOrder> = serde_json::from_str(fixture).unwrap();
+
+        assert_eq!(1656676800, orders[0].orderid);
+        assert_eq!(HiveAsset::new(3000, AssetSymbol::Hbd), orders[0].sell_price.quote);
+        assert_eq!(2500, orders[1].for_sale);
+        assert!(orders[0].is_expired());
+        assert!(!orders[1].is_expired());
+    }
+
+    #[test]
+    fn account_history_fixture() {
+        let fixture = r#"[[41,{"trx_id":"c8bd4e4b1bfeb6b0e5d3e4b1f53f7a4c8b0e1d2a","block":65812108,"trx_in_block":3,"op_in_trx":0,"virtual_op":false,"timestamp":"2022-07-01T12:00:00","op":["vote",{"voter":"test","author":"hiveio","permlink":"a-post","weight":10000}]}]]"#;
+        let history: Vec<AccountHistoryEntry> = serde_json::from_str(fixture).unwrap();
+
+        assert_eq!(41, history[0].sequence);
+        assert_eq!(65812108, history[0].block);
+        assert_eq!("vote", history[0].op[0]);
+    }
+
+    #[test]
+    fn account_fixture() {
+        let key = "STM5jixkNBqJXNtX9vy2GjaqpX2d5jXrcjRXgh1WU5fXZhnDJrLM8";
+        let fixture = format!(r#"{{"id":1370484,"name":"test","owner":{{"weight_threshold":1,"account_auths":[],"key_auths":[["{key}",1]]}},"active":{{"weight_threshold":1,"account_auths":[],"key_auths":[["{key}",1]]}},"posting":{{"weight_threshold":1,"account_auths":[["ecency.app",1]],"key_auths":[["{key}",1]]}},"memo_key":"{key}","json_metadata":"","posting_json_metadata":"","proxy":"","recovery_account":"hiveio","created":"2020-03-20T14:15:06","post_count":12,"voting_manabar":{{"current_mana":"9800000000","last_update_time":1656674400}},"balance":"10.000 HIVE","savings_balance":"0.000 HIVE","hbd_balance":"1.234 HBD","savings_hbd_balance":"0.000 HBD","vesting_shares":"20000.000000 VESTS","delegated_vesting_shares":"0.000000 VESTS","received_vesting_shares":"0.000000 VESTS","reward_hive_balance":"0.001 HIVE","reward_hbd_balance":"0.002 HBD","reward_vesting_balance":"3.000000 VESTS","reputation":0}}"#);
+        let account: HiveAccount = serde_json::from_str(&fixture).unwrap();
+
+        assert_eq!("test", account.name);
+        assert_eq!(vec![("ecency.app".to_string(), 1)], account.posting.account_auths);
+        assert_eq!(vec![(key.to_string(), 1)], account.owner.key_auths);
+        assert_eq!(HiveAsset::new(1234, AssetSymbol::Hbd), account.hbd_balance);
+    }
+
+    #[test]
+    fn database_account_fixture() {
+        let key = "STM5jixkNBqJXNtX9vy2GjaqpX2d5jXrcjRXgh1WU5fXZhnDJrLM8";
+        let authority = format!(r#"{{"weight_threshold":1,"account_auths":[],"key_auths":[["{key}",1]]}}"#);
+        let asset = |amount: &str, precision: u8, nai: &str| format!(r#"{{"amount":"{amount}","precision":{precision},"nai":"{nai}"}}"#);
+        let hive = |amount| asset(amount, 3, "@@000000021");
+        let hbd = |amount| asset(amount, 3, "@@000000013");
+        let vests = |amount| asset(amount, 6, "@@000000037");
+        let fixture = format!(
+            r#"{{"accounts":[{{"id":1370484,"name":"test","owner":{authority},"active":{authority},"posting":{authority},"memo_key":"{key}","json_metadata":"","posting_json_metadata":"","proxy":"","previous_owner_update":"1970-01-01T00:00:00","last_owner_update":"1970-01-01T00:00:00","last_account_update":"2022-06-01T10:00:00","created":"2020-03-20T14:15:06","mined":false,"recovery_account":"hiveio","last_account_recovery":"1970-01-01T00:00:00","reset_account":"null","comment_count":0,"lifetime_vote_count":0,"post_count":12,"can_vote":true,"voting_manabar":{{"current_mana":"9800000000","last_update_time":1656674400}},"downvote_manabar":{{"current_mana":"2450000000","last_update_time":1656674400}},"balance":{},"savings_balance":{},"hbd_balance":{},"savings_hbd_balance":{},"vesting_shares":{},"delegated_vesting_shares":{},"received_vesting_shares":{},"vesting_withdraw_rate":{},"reward_hbd_balance":{},"reward_hive_balance":{},"reward_vesting_balance":{},"reward_vesting_hive":{},"open_recurrent_transfers":0}}]}}"#,
+            hive("10000"), hive("0"), hbd("1234"), hbd("0"), vests("20000000000"), vests("0"), vests("0"), vests("0"),
+            hbd("2"), hive("1"), vests("3000000"), hive("1"),
+        );
+        let accounts: DatabaseAccounts = serde_json::from_str(&fixture).unwrap();
+        let account = &accounts.accounts[0];
+
+        assert_eq!("test", account.name);
+        assert_eq!(vec![(key.to_string(), 1)], account.owner.key_auths);
+        assert_eq!(HiveAsset::new(1234, AssetSymbol::Hbd), account.hbd_balance);
+        assert_eq!(HiveAsset::new(20000000000, AssetSymbol::Vests), account.vesting_shares);
+        assert_eq!(HiveAsset::new(1, AssetSymbol::Hive), account.reward_hive_balance);
+    }
+
+    #[test]
+    fn state_fixture() {
+        let key = "STM5jixkNBqJXNtX9vy2GjaqpX2d5jXrcjRXgh1WU5fXZhnDJrLM8";
+        let authority = json!({ "weight_threshold": 1, "account_auths": [], "key_auths": [[key, 1]] });
+        let state: StateResponse = serde_json::from_value(json!({
+            "current_route": "@test",
+            "props": {},
+            "accounts": { "test": {
+                "id": 1370484, "name": "test", "owner": authority, "active": authority, "posting": authority,
+                "memo_key": key, "json_metadata": "", "posting_json_metadata": "", "proxy": "", "recovery_account": "hiveio",
+                "created": "2020-03-20T14:15:06", "post_count": 12, "voting_manabar": { "current_mana": "9800000000", "last_update_time": 1656674400 },
+                "balance": "10.000 HIVE", "savings_balance": "0.000 HIVE", "hbd_balance": "1.234 HBD", "savings_hbd_balance": "0.000 HBD",
+                "vesting_shares": "20000.000000 VESTS", "delegated_vesting_shares": "0.000000 VESTS", "received_vesting_shares": "0.000000 VESTS",
+                "reward_hive_balance": "0.001 HIVE", "reward_hbd_balance": "0.002 HBD", "reward_vesting_balance": "3.000000 VESTS",
+            }, "partial": { "name": "partial" } },
+            "content": { "test/hello": {
+                "id": 1, "author": "test", "permlink": "hello", "category": "hive", "parent_author": "", "parent_permlink": "hive",
+                "title": "Hello", "body": "world", "json_metadata": "{}", "created": "2022-07-01T12:00:00", "last_update": "2022-07-01T12:00:00",
+                "depth": 0, "children": 0, "net_rshares": 0, "author_reputation": "0", "pending_payout_value": "0.000 HBD",
+                "total_payout_value": "0.000 HBD", "curator_payout_value": "0.000 HBD", "url": "/hive/@test/hello",
+            } },
+        })).unwrap();
+
+        assert_eq!("@test", state.0["current_route"]);
+        assert_eq!(Some(1370484), state.get_account("test").map(|account| account.id));
+        assert_eq!(None, state.get_account("partial"));
+        assert_eq!(None, state.get_account("nobody"));
+        assert_eq!(Some("Hello".to_string()), state.get_content("test", "hello").map(|content| content.title));
+        assert_eq!(None, state.get_content("test", "missing"));
+        assert_eq!(None, StateResponse(json!(null)).get_account("test"));
+    }
+}
@@ -0,0 +1,135 @@
+use thiserror::Error;
+
+
+/// The reason an account name was rejected.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AccountNameError {
+    /// Names, and each `.` separated segment of a name, are at least 3 characters
+    #[error("account names must be at least 3 characters")]
+    TooShort,
+    /// Names are at most 16 characters
+    #[error("account names must be at most 16 characters")]
+    TooLong,
+    /// Only lowercase letters, digits, hyphens and dots are allowed
+    #[error("invalid character in account name: {0:?}")]
+    InvalidCharacter(char),
+    #[error("account names cannot start with a hyphen")]
+    LeadingHyphen,
+    #[error("account names cannot end with a hyphen")]
+    TrailingHyphen,
+    #[error("account names cannot contain consecutive hyphens")]
+    ConsecutiveHyphens,
+    #[error("account names must start with a letter")]
+    MustStartWithLetter,
+}
+
+/// Returns whether `name` is a valid hive account name, see
+/// `validate_account_name`
+/// ```
+/// use tetanus::hive::utils::is_valid_account_name;
+/// assert!(is_valid_account_name("hiveio"));
+/// assert!(!is_valid_account_name("Hiveio"));
+/// ```
+pub fn is_valid_account_name(name: &str) -> bool {
+    validate_account_name(name).is_ok()
+}
+
+/// Checks `name` against the rules hived applies to new accounts. Names are 3
+/// to 16 characters and may be split into `.` separated segments (e.g.
+/// `hive.fund`), each of which is at least 3 characters of lowercase letters,
+/// digits and single hyphens, starting with a letter and not ending with a
+/// hyphen
+/// ```
+/// use tetanus::hive::utils::{validate_account_name, AccountNameError};
+/// assert_eq!(Ok(()), validate_account_name("hive.fund"));
+/// assert_eq!(Err(AccountNameError::ConsecutiveHyphens), validate_account_name("a--b"));
+/// ```
+pub fn validate_account_name(name: &str) -> Result<(), AccountNameError> {
+    let length = name.chars().count();
+    if length < 3 {
+        return Err(AccountNameError::TooShort)
+    }
+    if length > 16 {
+        return Err(AccountNameError::TooLong)
+    }
+
+    if let Some(c) = name.chars().find(|c| !matches!(c, 'a'..='z' | '0'..='9' | '-' | '.')) {
+        return Err(AccountNameError::InvalidCharacter(c))
+    }
+
+    name.split('.').try_for_each(validate_segment)
+}
+
+fn validate_segment(segment: &str) -> Result<(), AccountNameError> {
+    if segment.len() < 3 {
+        return Err(AccountNameError::TooShort)
+    }
+    if segment.starts_with('-') {
+        return Err(AccountNameError::LeadingHyphen)
+    }
+    if !segment.starts_with(|c: char| c.is_ascii_lowercase()) {
+        return Err(AccountNameError::MustStartWithLetter)
+    }
+    if segment.ends_with('-') {
+        return Err(AccountNameError::TrailingHyphen)
+    }
+    if segment.contains("--") {
+        return Err(AccountNameError::ConsecutiveHyphens)
+    }
+
+    Ok(())
+}
+
+/// Seconds since the unix epoch by the system clock. `SystemTime::now` panics
+/// on `wasm32-unknown-unknown`, so there the javascript clock is read instead
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn now_seconds() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |now| now.as_secs())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now_seconds() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hive::utils::*;
+
+    #[test]
+    fn valid_names() {
+        for name in ["abc", "hiveio", "blocktrades", "peak.open", "hive.fund", "steem-dao", "a1b2c3", "abcdefghijklmnop", "ned-reggae.x12"] {
+            assert_eq!(Ok(()), validate_account_name(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn invalid_names() {
+        let cases = [
+            ("", AccountNameError::TooShort),
+            ("ab", AccountNameError::TooShort),
+            ("abcdefghijklmnopq", AccountNameError::TooLong),
+            ("Hiveio", AccountNameError::InvalidCharacter('H')),
+            ("hive_io", AccountNameError::InvalidCharacter('_')),
+            ("hive io", AccountNameError::InvalidCharacter(' ')),
+            ("héllo", AccountNameError::InvalidCharacter('é')),
+            ("-hive", AccountNameError::LeadingHyphen),
+            ("hive-", AccountNameError::TrailingHyphen),
+            ("hi--ve", AccountNameError::ConsecutiveHyphens),
+            ("1hive", AccountNameError::MustStartWithLetter),
+            ("hive.io", AccountNameError::TooShort),
+            ("hive..fund", AccountNameError::TooShort),
+            (".hive", AccountNameError::TooShort),
+            ("hive.-abc", AccountNameError::LeadingHyphen),
+            ("hive.9abc", AccountNameError::MustStartWithLetter),
+        ];
+
+        for (name, error) in cases {
+            assert_eq!(Err(error), validate_account_name(name), "{}", name);
+            assert!(!is_valid_account_name(name));
+        }
+    }
+}
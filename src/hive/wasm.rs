@@ -0,0 +1,218 @@
+//! Builders for the most common operations that javascript can fill in field
+//! by field, rather than writing the operation json by hand
+//! ```js
+//! const op = new WasmTransferOperation();
+//! op.set_from("alice");
+//! op.set_to("bob");
+//! op.set_amount("1.000 HIVE");
+//! tx.push_operation(op.build());
+//! ```
+//! Nothing is checked while the fields are set, `Transaction::validate` checks
+//! the built operations like any other.
+use serde_json::{json, Map, Value};
+use wasm_bindgen::prelude::*;
+use crate::hive::operations::{CustomJsonOperation, Operation};
+
+/// An operation built by one of the builders, ready to be added to a
+/// `Transaction`
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WasmOperation(Operation);
+
+#[wasm_bindgen]
+impl WasmOperation {
+    /// The operation's name, e.g. `"transfer"`
+    pub fn name(&self) -> String {
+        self.0.name().to_string()
+    }
+}
+
+impl From<WasmOperation> for Operation {
+    fn from(op: WasmOperation) -> Self {
+        op.0
+    }
+}
+
+fn raw(name: &str, data: Value) -> WasmOperation {
+    let data: Map<String, Value> = serde_json::from_value(data).unwrap_or_default();
+
+    WasmOperation(Operation::new(name, data))
+}
+
+/// Builds a `transfer` of HIVE or HBD
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WasmTransferOperation {
+    from: String,
+    to: String,
+    amount: String,
+    memo: String,
+}
+
+#[wasm_bindgen]
+impl WasmTransferOperation {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmTransferOperation {
+        WasmTransferOperation::default()
+    }
+
+    pub fn set_from(&mut self, from: &str) {
+        self.from = from.to_string();
+    }
+
+    pub fn set_to(&mut self, to: &str) {
+        self.to = to.to_string();
+    }
+
+    /// The amount as an asset string, e.g. `"1.000 HIVE"`
+    pub fn set_amount(&mut self, amount: &str) {
+        self.amount = amount.to_string();
+    }
+
+    pub fn set_memo(&mut self, memo: &str) {
+        self.memo = memo.to_string();
+    }
+
+    pub fn build(&self) -> WasmOperation {
+        raw("transfer", json!({ "from": self.from, "to": self.to, "amount": self.amount, "memo": self.memo }))
+    }
+}
+
+/// Builds a `vote` on a post or comment
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WasmVoteOperation {
+    voter: String,
+    author: String,
+    permlink: String,
+    weight: i16,
+}
+
+#[wasm_bindgen]
+impl WasmVoteOperation {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmVoteOperation {
+        WasmVoteOperation::default()
+    }
+
+    pub fn set_voter(&mut self, voter: &str) {
+        self.voter = voter.to_string();
+    }
+
+    pub fn set_author(&mut self, author: &str) {
+        self.author = author.to_string();
+    }
+
+    pub fn set_permlink(&mut self, permlink: &str) {
+        self.permlink = permlink.to_string();
+    }
+
+    /// The vote weight in basis points, -10000 to 10000
+    pub fn set_weight(&mut self, weight: i16) {
+        self.weight = weight;
+    }
+
+    pub fn build(&self) -> WasmOperation {
+        raw("vote", json!({ "voter": self.voter, "author": self.author, "permlink": self.permlink, "weight": self.weight }))
+    }
+}
+
+/// Builds a `comment`, a post when `parent_author` is left empty
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WasmCommentOperation {
+    parent_author: String,
+    parent_permlink: String,
+    author: String,
+    permlink: String,
+    title: String,
+    body: String,
+    json_metadata: String,
+}
+
+#[wasm_bindgen]
+impl WasmCommentOperation {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmCommentOperation {
+        WasmCommentOperation::default()
+    }
+
+    pub fn set_parent_author(&mut self, parent_author: &str) {
+        self.parent_author = parent_author.to_string();
+    }
+
+    /// The post being replied to, or the category of a new post
+    pub fn set_parent_permlink(&mut self, parent_permlink: &str) {
+        self.parent_permlink = parent_permlink.to_string();
+    }
+
+    pub fn set_author(&mut self, author: &str) {
+        self.author = author.to_string();
+    }
+
+    pub fn set_permlink(&mut self, permlink: &str) {
+        self.permlink = permlink.to_string();
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self.title = title.to_string();
+    }
+
+    pub fn set_body(&mut self, body: &str) {
+        self.body = body.to_string();
+    }
+
+    pub fn set_json_metadata(&mut self, json_metadata: &str) {
+        self.json_metadata = json_metadata.to_string();
+    }
+
+    pub fn build(&self) -> WasmOperation {
+        raw("comment", json!({
+            "parent_author": self.parent_author,
+            "parent_permlink": self.parent_permlink,
+            "author": self.author,
+            "permlink": self.permlink,
+            "title": self.title,
+            "body": self.body,
+            "json_metadata": self.json_metadata,
+        }))
+    }
+}
+
+/// Builds a `custom_json`, signers are added one at a time
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WasmCustomJsonOperation {
+    inner: CustomJsonOperation,
+}
+
+#[wasm_bindgen]
+impl WasmCustomJsonOperation {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmCustomJsonOperation {
+        WasmCustomJsonOperation::default()
+    }
+
+    /// Adds an account signing with its active authority
+    pub fn add_required_auth(&mut self, account: &str) {
+        self.inner.required_auths.push(account.to_string());
+    }
+
+    /// Adds an account signing with its posting authority
+    pub fn add_required_posting_auth(&mut self, account: &str) {
+        self.inner.required_posting_auths.push(account.to_string());
+    }
+
+    pub fn set_id(&mut self, id: &str) {
+        self.inner.id = id.to_string();
+    }
+
+    /// The payload as a json string
+    pub fn set_json(&mut self, json: &str) {
+        self.inner.json = json.to_string();
+    }
+
+    pub fn build(&self) -> WasmOperation {
+        WasmOperation(self.inner.clone().into())
+    }
+}
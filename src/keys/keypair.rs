@@ -0,0 +1,52 @@
+use crate::keys::{private::PrivateKey, public::PublicKey};
+use crate::types::keys::PrivateKeyError;
+
+/// A private key together with its public key, which is derived once up front
+/// rather than on every `to_public` call
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keypair { private: PrivateKey, public: PublicKey }
+
+impl Keypair {
+    /// Derives the public key of `private`
+    pub fn new(private: PrivateKey) -> Keypair {
+        let public = private.to_public();
+
+        Keypair { private, public }
+    }
+
+    /// The keypair for a hive login, see `PrivateKey::from_login`
+    /// ```
+    /// use tetanus::keys::keypair::Keypair;
+    /// let keypair = Keypair::from_login("test", "test", "owner");
+    /// assert_eq!("STM5jixkNBqJXNtX9vy2GjaqpX2d5jXrcjRXgh1WU5fXZhnDJrLM8", keypair.public().to_string(None));
+    /// assert_eq!("5K8AruCpTY6gVeQRMd5UpeuoVR2YheRCjUDAVFrfiahZU4bBccj", keypair.private().to_string())
+    /// ```
+    pub fn from_login(username: &str, password: &str, role: &str) -> Keypair {
+        Keypair::new(PrivateKey::from_login(username, password, role))
+    }
+
+    /// Parses a legacy wif private key, see `PrivateKey`'s `FromStr` impl
+    /// ```
+    /// use tetanus::keys::keypair::Keypair;
+    /// let keypair = Keypair::from_wif("5K8AruCpTY6gVeQRMd5UpeuoVR2YheRCjUDAVFrfiahZU4bBccj").unwrap();
+    /// assert_eq!(Keypair::from_login("test", "test", "owner"), keypair);
+    /// assert!(Keypair::from_wif("5K8AruCpTY6gVeQRMd5UpeuoVR2YheRCjUDAVFrfiahZU4bBcck").is_err())
+    /// ```
+    pub fn from_wif(wif: &str) -> Result<Keypair, PrivateKeyError> {
+        Ok(Keypair::new(wif.parse()?))
+    }
+
+    pub fn private(&self) -> &PrivateKey {
+        &self.private
+    }
+
+    pub fn public(&self) -> &PublicKey {
+        &self.public
+    }
+}
+
+impl From<PrivateKey> for Keypair {
+    fn from(private: PrivateKey) -> Self {
+        Keypair::new(private)
+    }
+}
@@ -1,2 +1,4 @@
-pub mod private;
-pub mod public;
\ No newline at end of file
+pub mod keypair;
+pub mod private;
+pub mod public;
+pub mod r1_private;
@@ -1,20 +1,50 @@
+use alloc::{borrow::ToOwned, string::{String, ToString}, vec::Vec};
+use core::{fmt, str::FromStr};
 use k256::{
-    ecdsa::{recoverable::Signature as RecoverableSignature, SigningKey, signature::digest::Digest, signature::DigestSigner},
+    ecdsa::{
+        recoverable::Signature as RecoverableSignature, Error as EcdsaError, SigningKey, signature::digest::Digest,
+        signature::{DigestSigner, RandomizedDigestSigner}
+    },
+    elliptic_curve::rand_core::{self, CryptoRng, RngCore},
     FieldBytes
 };
 use sha2::{Sha256};
-use primitive_types::U256;
+use primitive_types::{H256, U256};
+use subtle::ConstantTimeEq;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 use crate::{signatures::SignatureWrapper as Signature, utils::{decode_from_string, hash_message}};
 use crate::utils::{EncodeType, encode_to_string};
-use crate::types::signature::{Signature as CanonicalSignature};
+use crate::types::signature::{Signature as CanonicalSignature, SignatureError};
 use crate::keys::public::PublicKey;
+use crate::types::keys::PrivateKeyError;
 use crate::hash::Sha256Proxy;
+#[cfg(feature = "crypto")]
+use crate::hive::transactions::{Transaction, TransactionError};
 
 
-#[derive(Debug, Clone, PartialEq)]
-#[wasm_bindgen]
+#[derive(Clone)]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub struct PrivateKey{ key: Vec<u8> }
+
+/// Compares in constant time, so how long a comparison takes says nothing
+/// about where the keys differ
+impl PartialEq for PrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.key.ct_eq(&other.key).into()
+    }
+}
+
+impl Eq for PrivateKey {}
+
+/// Never shows the key bytes, so keys do not end up in logs or panic messages
+impl fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PrivateKey(<redacted>)")
+    }
+}
 // #[wasm_bindgen]
 impl PrivateKey {
     /// Creates a new private key instance
@@ -24,7 +54,10 @@ impl PrivateKey {
     }
 
     /// Returns a new private key instance by creating a seed with
-    /// passed in arguments
+    /// passed in arguments. The seed is the sha256 of `username + role +
+    /// password`, in that order, as in dhive's `PrivateKey.fromLogin`
+    /// (<https://github.com/openhive-network/dhive/blob/master/src/crypto.ts>)
+    /// and steem-js' `auth.toWif`, so keys match the ones wallets derive
     /// ```
     /// use tetanus::keys::private::PrivateKey;
     /// let assert_key: PrivateKey = PrivateKey::new(vec![172, 77, 224, 92, 161, 163, 181, 53, 80, 219, 255, 168, 223, 31, 231, 32, 238, 108, 150, 219, 77, 153, 8, 68, 240, 148, 105, 203, 131, 235, 219, 82]);
@@ -36,11 +69,29 @@ impl PrivateKey {
         assert!(password.is_ascii());
         assert!(role.is_ascii());
 
-        let seed = username.to_owned() + &role + &password;
+        let seed = username.to_owned() + role + password;
         let hash = Sha256::digest(seed.as_bytes());
         PrivateKey::new(hash.to_vec())
     }
 
+    /// Uses the first 32 bytes of `seed` as the key, zero padding shorter
+    /// seeds. This is not BIP32: there is no HMAC, chain code or derivation
+    /// path, so a BIP39 seed gives a different key here than in a BIP32
+    /// wallet. The seed has to be uniformly random for the key to be safe
+    /// ```
+    /// use tetanus::keys::private::PrivateKey;
+    /// let key = PrivateKey::from_seed(&[7; 64]).unwrap();
+    /// assert_eq!(PrivateKey::new(vec![7; 32]), key)
+    /// ```
+    pub fn from_seed(seed: &[u8]) -> Result<PrivateKey, PrivateKeyError> {
+        let mut key = [0u8; 32];
+        let len = seed.len().min(32);
+        key[..len].copy_from_slice(&seed[..len]);
+        SigningKey::from_bytes(&key).map_err(|_| PrivateKeyError::OutOfRange)?;
+
+        Ok(PrivateKey::new(key.to_vec()))
+    }
+
     /// Takes a legacy wif string representing a key as an argument and returns a new private key instance
     /// ```
     /// use tetanus::keys::private::PrivateKey;
@@ -49,7 +100,7 @@ impl PrivateKey {
     /// assert_eq!(private_from_string, test_private)
     /// ```
     pub fn from_string(wif: &str) -> PrivateKey {
-        let hash = decode_from_string(wif.to_string(), Some(EncodeType::Sha256x2));
+        let hash = decode_from_string(wif.to_string(), Some(EncodeType::Sha256x2)).unwrap();
 
         PrivateKey::new(hash)
     }
@@ -64,8 +115,9 @@ impl PrivateKey {
     /// let generated_wif = PrivateKey::from_login("test", "test", "owner").to_string();
     /// assert_eq!(test_wif, generated_wif)
     /// ```
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
-        assert!(&self.key.len() > &0);
+        assert!(!self.key.is_empty());
 
         encode_to_string(self.key.clone(), Some(EncodeType::Sha256x2))
     }
@@ -78,12 +130,10 @@ impl PrivateKey {
     /// assert_eq!("STM5jixkNBqJXNtX9vy2GjaqpX2d5jXrcjRXgh1WU5fXZhnDJrLM8", public.to_string(None))
     /// ```
     pub fn to_public(&self) -> PublicKey {
-        let private_key = SigningKey::from_bytes(&self.key.as_slice()).unwrap();
+        let private_key = SigningKey::from_bytes(self.key.as_slice()).unwrap();
 
         let pub_key = private_key.verifying_key();
 
-        println!("{:?}", pub_key.to_bytes().len());
-
         PublicKey::new(pub_key.to_bytes().to_vec())
     }
 
@@ -96,28 +146,207 @@ impl PrivateKey {
     /// assert_eq!("SIG_K1_JvYLntg1nfTLFTMX9mXGJB95WnbceLKwcvWTc16tVVCX1eCvFKXAtcuRs8xtRqMhH8oHFYAoWUYg8n9iV5nuLxtHojE2eo", sig.to_string())
     /// ```
     pub fn sign_message(&self, message: &str) -> Signature {
-        let private_key = SigningKey::from_bytes(&self.key.as_slice()).unwrap();
-        let hashed_message = hash_message(message);
+        let sig = self.sign_message_canonical(message).unwrap();
 
-        let sig: RecoverableSignature = private_key.sign_digest(Sha256Proxy::from(hashed_message));
+        Signature::new(sig.to_vec())
+    }
+
+    /// Same as `sign_message` but returns the canonical signature, reporting
+    /// signing failures instead of panicking
+    /// ```
+    /// use tetanus::keys::private::PrivateKey;
+    /// let private = PrivateKey::from_login("test", "test", "owner");
+    /// let sig = private.sign_message_canonical("helloworld").unwrap();
+    /// assert_eq!(private.sign_message("helloworld").sig(), sig.to_vec())
+    /// ```
+    pub fn sign_message_canonical(&self, message: &str) -> Result<CanonicalSignature, SignatureError> {
+        self.sign_prehashed(hash_message(message).0)
+    }
 
-        let v = u8::from(sig.recovery_id()) as u64 + 31;
+    /// Signs the sha256 hash of `prefix` followed by `message`, for schemes that
+    /// tag what is being signed (e.g. a login challenge) so the signature
+    /// cannot be passed off as one over a bare message or a transaction.
+    /// `Signature::recover_with_prefix` is the counterpart
+    /// ```
+    /// use tetanus::keys::{private::PrivateKey, public::PublicKey};
+    /// let private = PrivateKey::from_login("test", "test", "owner");
+    /// let sig = private.sign_message_with_prefix("challenge-1234", "myapp login:").unwrap();
+    /// let address = sig.recover_with_prefix("challenge-1234", "myapp login:").unwrap();
+    /// assert_eq!(private.to_public(), PublicKey::new(address.0.to_vec()));
+    /// assert!(sig.recover_with_prefix("challenge-1234", "otherapp login:").unwrap() != address)
+    /// ```
+    pub fn sign_message_with_prefix(&self, message: &str, prefix: &str) -> Result<CanonicalSignature, SignatureError> {
+        self.sign_message_canonical(&(prefix.to_owned() + message))
+    }
 
-        let r_bytes: FieldBytes = sig.r().into();
-        let s_bytes: FieldBytes = sig.s().into();
-        let r = U256::from_big_endian(r_bytes.as_slice());
-        let s = U256::from_big_endian(s_bytes.as_slice());
+    /// Signs a 32 byte digest as is, without hashing it again.
+    ///
+    /// The caller is responsible for applying the hashing scheme the verifier
+    /// expects (e.g. sha256 of the message, or of the chain id and packed
+    /// transaction) before calling this, whatever is passed in gets signed.
+    pub fn sign_prehashed(&self, digest: [u8; 32]) -> Result<CanonicalSignature, SignatureError> {
+        let private_key = SigningKey::from_bytes(self.key.as_slice())?;
 
-        Signature::new(CanonicalSignature{ r, s, v }.to_vec())
+        sign_hash(&private_key, H256(digest))
+    }
+
+    /// Signs `message` like `sign_message_canonical`, with `entropy` mixed into
+    /// the RFC 6979 nonce as additional data. The same key, message and entropy
+    /// always give the same signature, and all zero entropy gives exactly the
+    /// plain RFC 6979 one that `sign_message_canonical` returns
+    /// ```
+    /// use tetanus::keys::private::PrivateKey;
+    /// let private = PrivateKey::from_login("test", "test", "owner");
+    /// let sig = private.sign_with_entropy("helloworld", [7; 32]).unwrap();
+    /// assert_eq!(sig, private.sign_with_entropy("helloworld", [7; 32]).unwrap());
+    /// assert_eq!(private.sign_message_canonical("helloworld").unwrap(), private.sign_with_entropy("helloworld", [0; 32]).unwrap())
+    /// ```
+    pub fn sign_with_entropy(&self, message: &str, entropy: [u8; 32]) -> Result<CanonicalSignature, SignatureError> {
+        if entropy == [0; 32] {
+            return self.sign_message_canonical(message)
+        }
+
+        let private_key = SigningKey::from_bytes(self.key.as_slice())?;
+        let sig: RecoverableSignature = private_key.try_sign_digest_with_rng(FixedEntropy(entropy), Sha256Proxy::from(hash_message(message)))?;
+
+        Ok(to_canonical(sig))
+    }
+
+    /// Signs `tx` the way hive nodes verify it: the sha256 of its chain id (if
+    /// set) and its packed bytes, see `Transaction::digest`
+    /// ```
+    /// use tetanus::keys::private::PrivateKey;
+    /// use tetanus::hive::transactions::{Transaction, HIVE_CHAIN_ID};
+    /// let tx: Transaction = serde_json::from_str(r#"{
+    ///     "ref_block_num": 34294, "ref_block_prefix": 3707022213, "expiration": "2016-04-06T08:29:27",
+    ///     "operations": [["vote", { "voter": "foo", "author": "bar", "permlink": "baz", "weight": 10000 }]],
+    ///     "extensions": []
+    /// }"#).unwrap();
+    ///
+    /// let private = PrivateKey::from_login("foo", "password", "posting");
+    /// let sig = private.sign_transaction(&tx.with_chain_id(HIVE_CHAIN_ID)).unwrap();
+    /// assert_eq!(65, sig.sig().len())
+    /// ```
+    #[cfg(feature = "crypto")]
+    pub fn sign_transaction(&self, tx: &Transaction) -> Result<Signature, TransactionError> {
+        let sig = self.sign_prehashed(tx.digest()?)?;
+
+        Ok(Signature::new(sig.to_vec()))
+    }
+
+    /// Signs every message in `messages` with the same signing key, which is only
+    /// constructed once. With the `parallel` feature enabled the messages are
+    /// signed on the rayon thread pool
+    /// ```
+    /// use tetanus::keys::private::PrivateKey;
+    /// let private = PrivateKey::from_login("test", "test", "owner");
+    /// let sigs = private.sign_many(&["helloworld", "helloworld"]);
+    /// assert_eq!(2, sigs.len());
+    /// assert_eq!(private.sign_message("helloworld"), *sigs[0].as_ref().unwrap())
+    /// ```
+    pub fn sign_many(&self, messages: &[&str]) -> Vec<Result<Signature, SignatureError>> {
+        let private_key = match SigningKey::from_bytes(self.key.as_slice()) {
+            Ok(key) => key,
+            // `ecdsa::Error` is opaque and not `Clone`, so one is made per message
+            Err(_) => return messages.iter().map(|_| Err(EcdsaError::new().into())).collect(),
+        };
+        let sign = |message: &&str| {
+            sign_hash(&private_key, hash_message(message)).map(|sig| Signature::new(sig.to_vec()))
+        };
+
+        #[cfg(feature = "parallel")]
+        return messages.par_iter().map(sign).collect();
+
+        #[cfg(not(feature = "parallel"))]
+        messages.iter().map(sign).collect()
     }
 
 }
 
+impl FromStr for PrivateKey {
+    type Err = PrivateKeyError;
+
+    /// Parses a wif like `PrivateKey::from_string`, but checks the checksum
+    /// and that the key is usable and reports what is wrong instead of
+    /// panicking
+    /// ```
+    /// use tetanus::keys::private::PrivateKey;
+    /// use tetanus::types::keys::PrivateKeyError;
+    /// let private: PrivateKey = "5K8AruCpTY6gVeQRMd5UpeuoVR2YheRCjUDAVFrfiahZU4bBccj".parse().unwrap();
+    /// assert_eq!(PrivateKey::from_login("test", "test", "owner"), private);
+    /// assert_eq!(Err(PrivateKeyError::InvalidChecksum), "5K8AruCpTY6gVeQRMd5UpeuoVR2YheRCjUDAVFrfiahZU4bBcck".parse::<PrivateKey>())
+    /// ```
+    fn from_str(wif: &str) -> Result<Self, Self::Err> {
+        // network byte, 32 byte key and a 4 byte checksum
+        let decoded = bs58::decode(wif).into_vec().map_err(|_| PrivateKeyError::InvalidEncoding)?;
+        if decoded.len() != 37 {
+            return Err(PrivateKeyError::InvalidEncoding)
+        }
+        if decoded[0] != 0x80 {
+            return Err(PrivateKeyError::UnknownNetwork(decoded[0]))
+        }
+
+        let (payload, checksum) = decoded.split_at(33);
+        if Sha256::digest(Sha256::digest(payload).as_slice())[..4] != *checksum {
+            return Err(PrivateKeyError::InvalidChecksum)
+        }
+        SigningKey::from_bytes(&payload[1..]).map_err(|_| PrivateKeyError::OutOfRange)?;
+
+        Ok(PrivateKey::new(payload[1..].to_vec()))
+    }
+}
+
+/// Signs an already hashed message, normalizing the recovery id to the
+/// `31`/`32` notation used by Hive
+fn sign_hash(private_key: &SigningKey, hash: H256) -> Result<CanonicalSignature, SignatureError> {
+    let sig: RecoverableSignature = private_key.try_sign_digest(Sha256Proxy::from(hash))?;
+
+    Ok(to_canonical(sig))
+}
+
+fn to_canonical(sig: RecoverableSignature) -> CanonicalSignature {
+    let v = u8::from(sig.recovery_id()) as u64 + 31;
+
+    let r_bytes: FieldBytes = sig.r().into();
+    let s_bytes: FieldBytes = sig.s().into();
+    let r = U256::from_big_endian(r_bytes.as_slice());
+    let s = U256::from_big_endian(s_bytes.as_slice());
+
+    CanonicalSignature{ r, s, v }
+}
+
+/// Hands k256 the caller's entropy where it asks a random number generator
+/// for the additional data it mixes into the nonce
+struct FixedEntropy([u8; 32]);
+
+impl RngCore for FixedEntropy {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for (i, byte) in dest.iter_mut().enumerate() {
+            *byte = self.0[i % 32];
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+// only ever used to perturb an otherwise deterministic nonce
+impl CryptoRng for FixedEntropy {}
 
 
 #[cfg(test)]
 mod test {
-    use crate::keys::private::*;
+    use crate::keys::{keypair::Keypair, private::*};
     use sha2::{Sha256, Digest};
     use quickcheck::quickcheck;
     use quickcheck::TestResult;
@@ -131,6 +360,17 @@ mod test {
         }
     }
 
+    #[test]
+    fn sign_prehashed_matches_sign_message() {
+        let private = PrivateKey::from_login("test", "test", "owner");
+        let digest = crate::utils::sha256("helloworld");
+
+        assert_eq!(
+            private.sign_message_canonical("helloworld").unwrap(),
+            private.sign_prehashed(digest).unwrap()
+        )
+    }
+
     quickcheck! {
         fn prop_login_inputs(user: String, pass: String, role: String) -> TestResult {
 
@@ -138,13 +378,122 @@ mod test {
                 return TestResult::discard()
             }
 
-            let hash = Sha256::digest((user.clone() + &pass + &role).as_bytes());
+            let hash = Sha256::digest((user.clone() + &role + &pass).as_bytes());
             let priv1 = PrivateKey::new(hash.to_vec());
-            
+
             TestResult::from_bool(priv1 == PrivateKey::from_login(&user, &pass, &role))
         }
     }
 
+    #[test]
+    fn login_seed_order() {
+        // "testownertest", where the role comes before the password
+        let private = PrivateKey::from_login("test", "test", "owner");
+        assert_eq!(PrivateKey::new(Sha256::digest(b"testownertest").to_vec()), private);
+        assert!(PrivateKey::new(Sha256::digest(b"testtestowner").to_vec()) != private);
+
+        let keypair = Keypair::from_login("alice", "hunter2", "posting");
+        assert_eq!(&PrivateKey::from_login("alice", "hunter2", "posting"), keypair.private());
+        assert_eq!(PrivateKey::new(Sha256::digest(b"alicepostinghunter2").to_vec()), *keypair.private());
+    }
+
+    #[test]
+    fn entropy_changes_the_nonce() {
+        let private = PrivateKey::from_login("test", "test", "owner");
+        let first = private.sign_with_entropy("helloworld", [1; 32]).unwrap();
+        let second = private.sign_with_entropy("helloworld", [2; 32]).unwrap();
+
+        assert_eq!(first, private.sign_with_entropy("helloworld", [1; 32]).unwrap());
+        assert!(first != second && first.r != private.sign_message_canonical("helloworld").unwrap().r);
+        for sig in [first, second] {
+            assert_eq!(private.to_public(), PublicKey::recover(&Signature::new(sig.to_vec()), "helloworld").unwrap());
+        }
+    }
+
+    #[test]
+    fn debug_output_is_redacted() {
+        use crate::types::keys::PrivateAddress;
+
+        let private = PrivateKey::from_login("test", "test", "owner");
+        let debug = format!("{:?}", private);
+        assert_eq!("PrivateKey(<redacted>)", debug);
+        assert!(!debug.contains(&format!("{:?}", private.key)));
+
+        let address = PrivateAddress(private.key.clone().try_into().unwrap());
+        assert_eq!("PrivateAddress(<redacted>)", format!("{:?}", address));
+    }
+
+    #[test]
+    fn keys_compare_by_value() {
+        use crate::types::keys::PrivateAddress;
+
+        let private = PrivateKey::from_login("test", "test", "owner");
+        assert_eq!(private, PrivateKey::from_login("test", "test", "owner"));
+        assert!(private != PrivateKey::from_login("test", "test", "active"));
+        // a key differing only in its last byte
+        let mut key = private.key.clone();
+        key[31] ^= 1;
+        assert!(private != PrivateKey::new(key.clone()));
+        assert!(private != PrivateKey { key: private.key[..31].to_vec() });
+
+        let address = PrivateAddress(private.key.clone().try_into().unwrap());
+        assert!(address == PrivateAddress(private.key.clone().try_into().unwrap()));
+        assert!(address != PrivateAddress(key.try_into().unwrap()));
+    }
+
+    #[test]
+    fn wif_errors() {
+        let wif = |payload: &[u8]| crate::base58::encode([payload, &Sha256::digest(Sha256::digest(payload).as_slice())[..4]].concat());
+
+        assert_eq!(Err(PrivateKeyError::InvalidEncoding), "5K8AruCpTY6gVeQRMd5UpeuoVR2YheRCjUDAVFrfiahZU4bBcc0".parse::<PrivateKey>());
+        assert_eq!(Err(PrivateKeyError::InvalidEncoding), wif(&[0x80; 32]).parse::<PrivateKey>());
+        assert_eq!(Err(PrivateKeyError::UnknownNetwork(0x81)), wif(&[[0x81].as_slice(), &[1; 32]].concat()).parse::<PrivateKey>());
+        assert_eq!(Err(PrivateKeyError::OutOfRange), wif(&[[0x80].as_slice(), &[0; 32]].concat()).parse::<PrivateKey>());
+        assert_eq!(Err(PrivateKeyError::OutOfRange), wif(&[[0x80].as_slice(), &[0xff; 32]].concat()).parse::<PrivateKey>());
+        assert_eq!(Ok(PrivateKey::new(vec![1; 32])), wif(&[[0x80].as_slice(), &[1; 32]].concat()).parse::<PrivateKey>());
+    }
+
+    #[test]
+    fn seed_keys() {
+        let seed: Vec<u8> = (1..=64).collect();
+        let key = PrivateKey::from_seed(&seed).unwrap();
+        assert_eq!("5HpjKrb7dH5kKQQzmbjB87Mxova7mek5bXUTWfndcX6tBoqUwzm", key.to_string());
+        assert_eq!("STM5txDiyu3J4qWKD2XtAr6kZqCXofc9Uuve9NUcRkzGSZJVyWhSN", key.to_public().to_string(None));
+        assert_eq!(key, PrivateKey::from_seed(&seed[..32]).unwrap());
+
+        assert_eq!(Ok(PrivateKey::new([vec![0xab; 16], vec![0; 16]].concat())), PrivateKey::from_seed(&[0xab; 16]));
+        assert_eq!(Err(PrivateKeyError::OutOfRange), PrivateKey::from_seed(&[]));
+        assert_eq!(Err(PrivateKeyError::OutOfRange), PrivateKey::from_seed(&[0xff; 64]));
+    }
+
+    #[test]
+    fn keys_and_signatures_hash() {
+        use std::collections::{HashMap, HashSet};
+
+        let owner = PrivateKey::from_login("test", "test", "owner");
+        let active = PrivateKey::from_login("test", "test", "active");
+
+        let mut weights: HashMap<PublicKey, u64> = HashMap::new();
+        weights.insert(owner.to_public(), 1);
+        weights.insert(active.to_public(), 2);
+        weights.insert(owner.to_public(), 3);
+        assert_eq!(2, weights.len());
+        assert_eq!(Some(&3), weights.get(&owner.to_public()));
+
+        let sigs: HashSet<Signature> = [
+            owner.sign_message("helloworld"),
+            owner.sign_message("helloworld"),
+            active.sign_message("helloworld"),
+        ].into_iter().collect();
+        assert_eq!(2, sigs.len());
+
+        let canonical: HashSet<CanonicalSignature> = [
+            owner.sign_message_canonical("helloworld").unwrap(),
+            owner.sign_message_canonical("helloworld").unwrap(),
+        ].into_iter().collect();
+        assert_eq!(1, canonical.len());
+    }
+
 }
 
 
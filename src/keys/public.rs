@@ -1,28 +1,193 @@
+use alloc::{string::{String, ToString}, vec::Vec};
+use core::{convert::TryFrom, hash::{Hash, Hasher}, str::FromStr};
+use primitive_types::H256;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
-use crate::utils::{encode_to_string, EncodeType};
-use crate::types::chain::Chain;
+use crate::utils::{decode_from_string, encode_to_string, EncodeType, WifError};
+use crate::types::chain::{Chain, KeyType};
+use crate::types::keys::KeyError;
+use crate::types::signature::{RecoveryMessage, Signature as CanonicalSignature, SignatureError};
+use crate::signatures::SignatureWrapper;
+use crate::keys::private::PrivateKey;
 
-#[wasm_bindgen]
-pub struct PublicKey { key: Vec<u8> }
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey { key: Vec<u8>, key_type: KeyType }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl PublicKey {
-    /// Creates a new public key instance
+    /// Creates a new K1 public key instance
     pub fn new(key: Vec<u8>) -> PublicKey {
-        PublicKey{ key }
+        PublicKey{ key, key_type: KeyType::K1 }
     }
 
-    /// Converts a public key to a wif encoded string
+    /// Creates a new public key instance of the given type
+    pub fn new_typed(key: Vec<u8>, key_type: KeyType) -> PublicKey {
+        PublicKey{ key, key_type }
+    }
+
+    /// Returns whether this is a K1 or an R1 key
+    pub fn key_type(&self) -> KeyType {
+        self.key_type
+    }
+
+    /// Takes a wif string representing a public key (with its chain prefix) and
+    /// returns a new public key instance, see `FromStr` for the accepted forms
+    /// ```
+    /// use tetanus::keys::public::PublicKey;
+    /// let public = PublicKey::from_string("STM5jixkNBqJXNtX9vy2GjaqpX2d5jXrcjRXgh1WU5fXZhnDJrLM8");
+    /// assert_eq!("STM5jixkNBqJXNtX9vy2GjaqpX2d5jXrcjRXgh1WU5fXZhnDJrLM8", public.to_string(None))
+    /// ```
+    pub fn from_string(wif: &str) -> PublicKey {
+        wif.parse().unwrap()
+    }
+
+    /// Converts a public key to a wif encoded string with the legacy `STM` or
+    /// `EOS` prefix. R1 keys have no legacy form and always use `PUB_R1_`
     pub fn to_string(&self, chain: Option<Chain>) -> String {
+        if self.key_type == KeyType::R1 {
+            return self.to_eos_string()
+        }
+
         let prefix = match chain.unwrap_or(Chain::Hive) {
             Chain::Hive => String::from("STM"),
             Chain::Steem => String::from("STM"),
             Chain::Eos => String::from("EOS")
         };
-        
-        println!("{}", &self.key.len());
-        assert!(&self.key.len() > &0);
+
+        assert!(!self.key.is_empty());
 
         prefix + &encode_to_string(self.key.clone(), Some(EncodeType::PubKey))
     }
-}
\ No newline at end of file
+
+    /// Converts a public key to the newer EOS `PUB_K1_` or `PUB_R1_` string,
+    /// whose checksum covers the key type as well as the key
+    /// ```
+    /// use tetanus::keys::public::PublicKey;
+    /// let public = PublicKey::from_string("EOS5jixkNBqJXNtX9vy2GjaqpX2d5jXrcjRXgh1WU5fXZhnDJrLM8");
+    /// let pub_string = public.to_eos_string();
+    /// assert!(pub_string.starts_with("PUB_K1_"));
+    /// assert_eq!(public, PublicKey::from_string(&pub_string))
+    /// ```
+    pub fn to_eos_string(&self) -> String {
+        let encode_type = match self.key_type {
+            KeyType::K1 => EncodeType::K1,
+            KeyType::R1 => EncodeType::R1,
+        };
+
+        String::from(self.key_type.prefix()) + &encode_to_string(self.key.clone(), Some(encode_type))
+    }
+}
+
+/// Only the key bytes are hashed, a K1 and an R1 key never share them
+impl Hash for PublicKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = KeyError;
+
+    /// Parses `PUB_K1_` and `PUB_R1_` strings as well as the legacy `STM` and
+    /// `EOS` forms, which are always K1
+    /// ```
+    /// use tetanus::keys::public::PublicKey;
+    /// use tetanus::keys::r1_private::R1PrivateKey;
+    /// use tetanus::types::chain::KeyType;
+    /// let pub_string = R1PrivateKey::new(vec![1; 32]).to_public().to_string(None);
+    /// let public: PublicKey = pub_string.parse().unwrap();
+    /// assert_eq!(KeyType::R1, public.key_type());
+    /// assert!("FOO5jixkNBqJXNtX9vy2GjaqpX2d5jXrcjRXgh1WU5fXZhnDJrLM8".parse::<PublicKey>().is_err());
+    /// // the last character changed, so the checksum does not match
+    /// let corrupted = "STM5jixkNBqJXNtX9vy2GjaqpX2d5jXrcjRXgh1WU5fXZhnDJrLM9".parse::<PublicKey>();
+    /// assert!(matches!(corrupted, Err(tetanus::types::keys::KeyError::InvalidChecksum)))
+    /// ```
+    fn from_str(wif: &str) -> Result<Self, Self::Err> {
+        let (key_string, key_type, encode_type) = if let Some(key_string) = wif.strip_prefix(KeyType::K1.prefix()) {
+            (key_string, KeyType::K1, EncodeType::K1)
+        } else if let Some(key_string) = wif.strip_prefix(KeyType::R1.prefix()) {
+            (key_string, KeyType::R1, EncodeType::R1)
+        } else if let Some(key_string) = wif.strip_prefix("STM").or_else(|| wif.strip_prefix("EOS")) {
+            (key_string, KeyType::K1, EncodeType::PubKey)
+        } else {
+            return Err(KeyError::UnknownPrefix(wif.to_string()))
+        };
+
+        // 33 byte compressed key followed by a 4 byte checksum
+        match bs58::decode(key_string).into_vec() {
+            Ok(decoded) if decoded.len() == 37 => {},
+            _ => return Err(KeyError::InvalidEncoding),
+        }
+
+        let key = decode_from_string(key_string.to_string(), Some(encode_type)).map_err(|error| match error {
+            WifError::InvalidChecksum => KeyError::InvalidChecksum,
+            _ => KeyError::InvalidEncoding,
+        })?;
+
+        Ok(PublicKey::new_typed(key, key_type))
+    }
+}
+
+impl From<PrivateKey> for PublicKey {
+    fn from(private: PrivateKey) -> Self {
+        private.to_public()
+    }
+}
+
+impl From<&PrivateKey> for PublicKey {
+    fn from(private: &PrivateKey) -> Self {
+        private.to_public()
+    }
+}
+
+impl PublicKey {
+    /// Returns the public key of `private`, the same as `PrivateKey::to_public`
+    /// and the `From<&PrivateKey>` impl
+    /// ```
+    /// use tetanus::keys::{private::PrivateKey, public::PublicKey};
+    /// let private = PrivateKey::from_login("test", "test", "owner");
+    /// let public: PublicKey = (&private).into();
+    /// assert_eq!(PublicKey::from_private(&private), public);
+    /// assert_eq!(private.to_public(), PublicKey::from(private))
+    /// ```
+    pub fn from_private(private: &PrivateKey) -> PublicKey {
+        private.to_public()
+    }
+
+    /// The 33 byte compressed key
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.key
+    }
+
+    /// Recovers the public key that produced `sig` over `message`
+    /// ```
+    /// use tetanus::keys::{private::PrivateKey, public::PublicKey};
+    /// let sig = PrivateKey::from_login("test", "test", "owner").sign_message("helloworld");
+    /// let public = PublicKey::recover(&sig, "helloworld").unwrap();
+    /// assert_eq!("STM5jixkNBqJXNtX9vy2GjaqpX2d5jXrcjRXgh1WU5fXZhnDJrLM8", public.to_string(None))
+    /// ```
+    pub fn recover(sig: &SignatureWrapper, message: &str) -> Result<PublicKey, SignatureError> {
+        PublicKey::recover_from(sig, message.into())
+    }
+
+    /// Recovers the public key that produced `sig` over an already computed
+    /// 32 byte `digest`, the digest is not hashed again
+    /// ```
+    /// use tetanus::keys::{private::PrivateKey, public::PublicKey};
+    /// use tetanus::utils::sha256;
+    /// let sig = PrivateKey::from_login("test", "test", "owner").sign_message("helloworld");
+    /// let public = PublicKey::recover_prehashed(&sig, sha256("helloworld")).unwrap();
+    /// assert_eq!("STM5jixkNBqJXNtX9vy2GjaqpX2d5jXrcjRXgh1WU5fXZhnDJrLM8", public.to_string(None))
+    /// ```
+    pub fn recover_prehashed(sig: &SignatureWrapper, digest: [u8; 32]) -> Result<PublicKey, SignatureError> {
+        PublicKey::recover_from(sig, H256(digest).into())
+    }
+
+    fn recover_from(sig: &SignatureWrapper, message: RecoveryMessage) -> Result<PublicKey, SignatureError> {
+        let sig = CanonicalSignature::try_from(sig.sig().as_slice())?;
+        let address = sig.recover(message)?;
+
+        Ok(PublicKey::new(address.0.to_vec()))
+    }
+}
@@ -0,0 +1,184 @@
+use alloc::{string::{String, ToString}, vec::Vec};
+use core::fmt;
+use p256::{
+    ecdsa::{Signature as P256Signature, SigningKey, VerifyingKey, signature::DigestSigner},
+    elliptic_curve::{bigint::U256 as FieldU256, ops::Reduce, DecompressPoint, sec1::ToEncodedPoint, subtle::Choice},
+    pkcs8::DecodePrivateKey,
+    AffinePoint, FieldBytes, ProjectivePoint, Scalar, SecretKey,
+};
+use primitive_types::{H256, U256};
+use subtle::ConstantTimeEq;
+use crate::{signatures::SignatureWrapper as Signature, utils::{decode_from_string, hash_message}};
+use crate::utils::{EncodeType, encode_to_string};
+use crate::types::{chain::KeyType, signature::{Signature as CanonicalSignature, SignatureError, SignatureType}};
+use crate::keys::public::PublicKey;
+use crate::hash::Sha256Proxy;
+
+
+/// A secp256r1 (P-256) private key as used by EOS mobile and WebAuthn wallets.
+/// Mirrors `PrivateKey`, but its strings use the `PVT_R1_` / `PUB_R1_` /
+/// `SIG_R1_` formats
+#[derive(Clone)]
+pub struct R1PrivateKey{ key: Vec<u8> }
+
+/// Compares in constant time, like `PrivateKey`
+impl PartialEq for R1PrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.key.ct_eq(&other.key).into()
+    }
+}
+
+impl Eq for R1PrivateKey {}
+
+/// Never shows the key bytes, like `PrivateKey`
+impl fmt::Debug for R1PrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("R1PrivateKey(<redacted>)")
+    }
+}
+
+impl R1PrivateKey {
+    /// Creates a new private key instance
+    pub fn new(key: Vec<u8>) -> R1PrivateKey {
+        assert!(key.len() == 32);
+        R1PrivateKey{ key }
+    }
+
+    /// Takes either a `PVT_R1_` wif string or a PEM encoded (SEC1 or PKCS#8)
+    /// P-256 private key and returns a new private key instance
+    /// ```
+    /// use tetanus::keys::r1_private::R1PrivateKey;
+    /// let private = R1PrivateKey::new(vec![1; 32]);
+    /// assert_eq!(private, R1PrivateKey::from_string(&private.to_string()))
+    /// ```
+    pub fn from_string(key: &str) -> R1PrivateKey {
+        if key.trim_start().starts_with("-----BEGIN") {
+            let secret = SecretKey::from_sec1_pem(key)
+                .or_else(|_| SecretKey::from_pkcs8_pem(key))
+                .unwrap();
+
+            return R1PrivateKey::new(secret.to_be_bytes().to_vec())
+        }
+
+        let wif = key.strip_prefix("PVT_R1_").unwrap_or(key);
+
+        R1PrivateKey::new(decode_from_string(wif.to_string(), Some(EncodeType::R1)).unwrap())
+    }
+
+    /// Converts the private key to its `PVT_R1_` wif string
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        String::from("PVT_R1_") + &encode_to_string(self.key.clone(), Some(EncodeType::R1))
+    }
+
+    /// Returns the R1 public key that corresponds to the private key
+    pub fn to_public(&self) -> PublicKey {
+        let private_key = SigningKey::from_bytes(self.key.as_slice()).unwrap();
+        let pub_key = private_key.verifying_key().to_encoded_point(true);
+
+        PublicKey::new_typed(pub_key.as_bytes().to_vec(), KeyType::R1)
+    }
+
+    /// Takes in a message then returns a `SIG_R1_` signature generated by the
+    /// private key instance
+    /// ```
+    /// use tetanus::keys::r1_private::R1PrivateKey;
+    /// let private = R1PrivateKey::new(vec![1; 32]);
+    /// assert!(private.sign_message("helloworld").to_string().starts_with("SIG_R1_"))
+    /// ```
+    pub fn sign_message(&self, message: &str) -> Signature {
+        let sig = self.sign_message_canonical(message).unwrap();
+
+        Signature::new_typed(sig.to_vec(), SignatureType::R1)
+    }
+
+    /// Signs the sha256 hash of `message`, the result is in low-S form with the
+    /// recovery id in the `31`/`32` notation shared with K1 signatures
+    pub fn sign_message_canonical(&self, message: &str) -> Result<CanonicalSignature, SignatureError> {
+        self.sign_prehashed(hash_message(message).0)
+    }
+
+    /// Signs an already computed 32 byte `digest` without hashing it again, see
+    /// `PrivateKey::sign_prehashed`
+    pub fn sign_prehashed(&self, digest: [u8; 32]) -> Result<CanonicalSignature, SignatureError> {
+        let private_key = SigningKey::from_bytes(self.key.as_slice())?;
+        let hash = H256(digest);
+
+        let sig: P256Signature = private_key.try_sign_digest(Sha256Proxy::from(hash))?;
+        let sig = sig.normalize_s().unwrap_or(sig);
+
+        let recovery_id = recovery_id(&sig, hash.as_bytes(), &private_key.verifying_key())
+            .ok_or(SignatureError::RecoveryError)?;
+
+        let r_bytes: FieldBytes = sig.r().into();
+        let s_bytes: FieldBytes = sig.s().into();
+        let r = U256::from_big_endian(r_bytes.as_slice());
+        let s = U256::from_big_endian(s_bytes.as_slice());
+
+        Ok(CanonicalSignature{ r, s, v: recovery_id as u64 + 31 })
+    }
+}
+
+/// The p256 crate has no recoverable signatures, so the recovery id is found by
+/// recovering the public key for both y parities and comparing against `public`
+fn recovery_id(sig: &P256Signature, hash: &[u8], public: &VerifyingKey) -> Option<u8> {
+    let r: Scalar = *sig.r();
+    let s: Scalar = *sig.s();
+    let z = <Scalar as Reduce<FieldU256>>::from_be_bytes_reduced(*FieldBytes::from_slice(hash));
+    let expected = public.to_encoded_point(true);
+
+    (0u8..2).find(|recovery_id| {
+        let big_r = AffinePoint::decompress(&r.to_bytes(), Choice::from(*recovery_id));
+        if big_r.is_none().into() {
+            return false
+        }
+
+        let big_r = ProjectivePoint::from(big_r.unwrap());
+        let recovered = (big_r * s - ProjectivePoint::GENERATOR * z) * r.invert().unwrap();
+
+        recovered.to_affine().to_encoded_point(true) == expected
+    })
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::keys::r1_private::*;
+    use p256::{ecdsa::signature::DigestVerifier, pkcs8::LineEnding};
+
+    #[test]
+    fn signature_verifies_and_recovers() {
+        let private = R1PrivateKey::new(vec![7; 32]);
+        let sig = private.sign_message_canonical("helloworld").unwrap();
+        let bytes = sig.to_vec();
+
+        let p256_sig = P256Signature::from_scalars(
+            *FieldBytes::from_slice(&bytes[..32]),
+            *FieldBytes::from_slice(&bytes[32..64]),
+        ).unwrap();
+        let verifying_key = SigningKey::from_bytes(&private.key).unwrap().verifying_key();
+
+        assert!(verifying_key.verify_digest(Sha256Proxy::from(hash_message("helloworld")), &p256_sig).is_ok());
+        assert_eq!(
+            Some(sig.v as u8 - 31),
+            recovery_id(&p256_sig, hash_message("helloworld").as_bytes(), &verifying_key)
+        );
+    }
+
+    #[test]
+    fn from_pem() {
+        let private = R1PrivateKey::new(vec![7; 32]);
+        let secret = SecretKey::from_be_bytes(&private.key).unwrap();
+        let pem = secret.to_pem(LineEnding::LF).unwrap();
+
+        assert_eq!(private, R1PrivateKey::from_string(&pem))
+    }
+
+    #[test]
+    fn debug_output_is_redacted() {
+        let private = R1PrivateKey::new((1..=32).collect());
+        let debug = format!("{:?}", private);
+        assert_eq!("R1PrivateKey(<redacted>)", debug);
+        assert!(!debug.contains(&format!("{:?}", private.key)));
+    }
+}
@@ -1,6 +1,23 @@
-pub mod keys;
-pub mod signatures;
-pub mod utils;
-pub mod types;
-pub mod hash;
-pub mod hive;
\ No newline at end of file
+//! The crate is split up by features:
+//!
+//! - `crypto` (default): the `hive` and `eos` transaction modules, on top of
+//!   the key, signature, hashing and encoding modules that are always built
+//! - `hive-client`: the async `HiveClient` rpc client and `reqwest`
+//! - `wasm`: the `wasm-bindgen` javascript bindings
+//! - `full`: all of the above, plus `wasm-client`, `parallel` and `tracing`
+//!
+//! With no features at all the crate is `no_std` and only needs `alloc`.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod keys;
+pub mod signatures;
+pub mod utils;
+pub mod types;
+pub mod hash;
+pub mod base58;
+#[cfg(feature = "crypto")]
+pub mod hive;
+#[cfg(feature = "crypto")]
+pub mod eos;
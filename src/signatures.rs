@@ -1,16 +1,33 @@
+use alloc::{string::String, vec, vec::Vec};
+use core::convert::TryFrom;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
-use crate::{types::signature::{Signature as CanonicalSig}, keys::public::PublicKey, types::chain::Chain};
+use crate::{types::signature::{Signature as CanonicalSig, SignatureType}, keys::public::PublicKey, types::chain::Chain};
 
 
-#[derive(Debug, Clone, PartialEq, Default)]
-#[wasm_bindgen]
-pub struct SignatureWrapper{ sig: Vec<u8> }
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub struct SignatureWrapper{ sig: Vec<u8>, sig_type: SignatureType }
 
-#[wasm_bindgen]
+/// The default is a placeholder of 65 zero bytes, so it has the right shape for
+/// anything that converts or encodes it, but it does not verify against any key
+/// and `is_valid` is false for it
+impl Default for SignatureWrapper {
+    fn default() -> Self {
+        SignatureWrapper::new(vec![0; 65])
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl SignatureWrapper {
-    /// Creates a new signature instance
+    /// Creates a new K1 signature instance
     pub fn new(sig: Vec<u8>) -> SignatureWrapper {
-        SignatureWrapper { sig }
+        SignatureWrapper { sig, sig_type: SignatureType::K1 }
+    }
+
+    /// Creates a new signature instance of the given type
+    pub fn new_typed(sig: Vec<u8>, sig_type: SignatureType) -> SignatureWrapper {
+        SignatureWrapper { sig, sig_type }
     }
 
     // Returns a clone of the stored inner signature buffer
@@ -18,6 +35,24 @@ impl SignatureWrapper {
         self.sig.clone()
     }
 
+    /// Whether the buffer has the shape of a real signature: 65 bytes, non zero
+    /// `r` and `s` and a recovery id in one of the known notations. It does
+    /// not check the signature against any message
+    /// ```
+    /// use tetanus::keys::private::PrivateKey;
+    /// use tetanus::signatures::SignatureWrapper;
+    /// assert!(PrivateKey::from_login("test", "test", "owner").sign_message("helloworld").is_valid());
+    /// assert!(!SignatureWrapper::default().is_valid())
+    /// ```
+    pub fn is_valid(&self) -> bool {
+        CanonicalSig::try_from(self.sig.as_slice()).is_ok_and(|sig| sig.is_valid())
+    }
+
+    /// Returns whether this is a K1 or an R1 signature
+    pub fn sig_type(&self) -> SignatureType {
+        self.sig_type
+    }
+
     /// Allows for a base58 string to be encoded to a legacy wif signature string
     /// ```
     /// use tetanus::keys::private::PrivateKey;
@@ -29,14 +64,16 @@ impl SignatureWrapper {
     /// let sig2 = private.sign_message(message);
     /// assert_eq!(sig_string.to_string(), sig2.to_string())
     /// ```
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         let sig = CanonicalSig::from(self);
 
-        sig.to_legacy(Some("SIG_K1_"))
+        sig.to_legacy(Some(self.sig_type))
     }
 
     /// Allows for a base58 string to be decoded into its original buffer from
-    /// a legacy wif signature string
+    /// a legacy wif signature string, the signature type is taken from the
+    /// `SIG_K1_` or `SIG_R1_` prefix
     /// ```
     /// use tetanus::keys::private::PrivateKey;
     /// use tetanus::signatures::SignatureWrapper;
@@ -50,9 +87,9 @@ impl SignatureWrapper {
     /// assert_eq!(sig, sig2)
     /// ```
     pub fn from_string(sig: &str) -> SignatureWrapper {
-        let signature = CanonicalSig::from_legacy(&sig, Some("SIG_K1_")).unwrap();
+        let (signature, sig_type) = CanonicalSig::from_legacy_typed(sig).unwrap();
 
-        SignatureWrapper::new(signature.into())
+        SignatureWrapper::new_typed(signature.into(), sig_type)
     }
 
     /// Allows a public key wif to be obtained from a base58 encoded signature string and its original message
@@ -70,3 +107,52 @@ impl SignatureWrapper {
         PublicKey::new(pub_address.0.to_vec()).to_string(chain)
     }
 }
+
+
+#[cfg(test)]
+mod test {
+    use crate::keys::private::PrivateKey;
+    use crate::signatures::*;
+    use crate::types::signature::SignatureError;
+    use crate::utils::WifError;
+
+    #[test]
+    fn default_is_an_invalid_placeholder() {
+        let sig = SignatureWrapper::default();
+
+        assert_eq!(65, sig.sig().len());
+        assert!(!sig.is_valid());
+        assert!(sig.to_string().starts_with("SIG_K1_"));
+        assert!(!SignatureWrapper::new(vec![]).is_valid());
+    }
+
+    #[test]
+    fn scalars_must_be_below_the_order() {
+        let sig = PrivateKey::from_login("test", "test", "owner").sign_message("helloworld");
+        assert!(sig.is_valid());
+
+        let mut bytes = sig.sig();
+        bytes[32..64].copy_from_slice(&[0xff; 32]);
+        let high_s = SignatureWrapper::new(bytes);
+        assert!(!high_s.is_valid());
+        assert!(matches!(CanonicalSig::from(&high_s).recover("helloworld"), Err(SignatureError::Malformed)));
+
+        let mut bytes = sig.sig();
+        bytes[64] = 4;
+        assert!(!SignatureWrapper::new(bytes).is_valid());
+    }
+
+    #[test]
+    fn legacy_strings_are_checksummed() {
+        let sig = PrivateKey::from_login("test", "test", "owner").sign_message("helloworld").to_string();
+        assert!(CanonicalSig::from_legacy(&sig, None).is_ok());
+
+        // swapping the last two characters keeps the length but breaks the checksum
+        let mut corrupted: Vec<char> = sig.chars().collect();
+        let len = corrupted.len();
+        corrupted.swap(len - 1, len - 2);
+        let corrupted: String = corrupted.into_iter().collect();
+        assert!(matches!(CanonicalSig::from_legacy(&corrupted, None), Err(SignatureError::Decoding(WifError::InvalidChecksum))));
+        assert!(matches!(CanonicalSig::from_legacy("SIG_K1_0OIl", None), Err(SignatureError::Decoding(WifError::InvalidBase58))));
+    }
+}
@@ -1,8 +1,30 @@
-use wasm_bindgen::prelude::*;
-
-#[wasm_bindgen]
-pub enum Chain {
-    Hive,
-    Steem,
-    Eos,
-}
\ No newline at end of file
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub enum Chain {
+    Hive,
+    Steem,
+    Eos,
+}
+
+/// The curve a key belongs to
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyType {
+    /// secp256k1
+    #[default]
+    K1,
+    /// secp256r1 (P-256)
+    R1,
+}
+
+impl KeyType {
+    /// The prefix of EOS public key strings of this type
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            KeyType::K1 => "PUB_K1_",
+            KeyType::R1 => "PUB_R1_",
+        }
+    }
+}
@@ -1,8 +1,11 @@
-use std::fmt;
+use alloc::string::String;
+use core::fmt;
 
 use k256::ecdsa::VerifyingKey;
+use subtle::ConstantTimeEq;
+use thiserror::Error;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PublicAddress(pub [u8; 33]);
 
 impl fmt::Display for PublicAddress {
@@ -14,7 +17,7 @@ impl fmt::Display for PublicAddress {
 
 impl From<&PublicAddress> for [u8; 33] {
     fn from(key: &PublicAddress) -> [u8; 33] {
-        key.into()
+        key.0
     }
 }
 
@@ -25,4 +28,67 @@ impl From<k256::elliptic_curve::PublicKey<k256::Secp256k1>> for PublicAddress {
     }
 }
 
-pub struct PrivateAddress(pub [u8; 32]);
\ No newline at end of file
+pub struct PrivateAddress(pub [u8; 32]);
+
+/// Compares in constant time, like `PrivateKey`
+impl PartialEq for PrivateAddress {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.ct_eq(&other.0).into()
+    }
+}
+
+impl Eq for PrivateAddress {}
+
+/// Never shows the key bytes, so addresses can sit in structs that get logged
+impl fmt::Debug for PrivateAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PrivateAddress(<redacted>)")
+    }
+}
+
+/// An error parsing a public key string.
+#[derive(Debug, Error)]
+pub enum KeyError {
+    /// The string did not start with any known chain or key type prefix
+    #[error("unknown public key prefix: {0}")]
+    UnknownPrefix(String),
+    /// The string was not base58, or did not hold a 33 byte key and checksum
+    #[error("invalid public key encoding")]
+    InvalidEncoding,
+    /// The checksum does not match the key
+    #[error("invalid public key checksum")]
+    InvalidChecksum,
+}
+
+/// An error parsing a private key wif.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PrivateKeyError {
+    /// The string was not base58, or did not hold a network byte, a 32 byte
+    /// key and a checksum
+    #[error("invalid private key encoding")]
+    InvalidEncoding,
+    /// The network byte was not the `0x80` every hive and eos wif starts with
+    #[error("unknown private key network byte {0:#04x}")]
+    UnknownNetwork(u8),
+    /// The checksum did not match the key
+    #[error("invalid private key checksum")]
+    InvalidChecksum,
+    /// The key is zero or not below the secp256k1 curve order
+    #[error("private key is out of range")]
+    OutOfRange,
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::types::keys::*;
+
+    #[test]
+    fn public_address_converts_to_bytes() {
+        let bytes: [u8; 33] = core::array::from_fn(|i| i as u8);
+        let address = PublicAddress(bytes);
+
+        assert_eq!(bytes, <[u8; 33]>::from(&address));
+        assert_eq!(format!("{:?}", bs58::encode(bytes).into_string()), address.to_string());
+    }
+}
@@ -1,7 +1,8 @@
 use primitive_types::{H256, U256};
-use crate::utils::{hash_message, encode_to_string, decode_from_string};
+use crate::utils::{hash_message, encode_to_string, decode_from_string, EncodeType, WifError};
 use crate::types::keys::PublicAddress;
 use crate::signatures::SignatureWrapper;
+use crate::hash::Sha256Proxy;
 use k256::{
     ecdsa::{
         recoverable::{Id as RecoveryId, Signature as RecoverableSignature},
@@ -11,12 +12,47 @@ use k256::{
     PublicKey as K256PublicKey,
 };
 use generic_array::GenericArray;
-use std::{convert::TryFrom, fmt};
+use alloc::{borrow::ToOwned, string::{String, ToString}, vec::Vec};
+use core::{convert::TryFrom, fmt, hash::{Hash, Hasher}};
 use thiserror::Error;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// The order `n` of the secp256k1 group, `r` and `s` are scalars below it
+const SECP256K1_ORDER: U256 = U256([0xBFD25E8CD0364141, 0xBAAEDCE6AF48A03B, 0xFFFFFFFFFFFFFFFE, 0xFFFFFFFFFFFFFFFF]);
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 pub struct Signature{ pub r: U256, pub s: U256, pub v: u64 }
 
+/// The curve a signature was produced on, EOS string encodings carry it in
+/// their `SIG_K1_` or `SIG_R1_` prefix
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SignatureType {
+    /// secp256k1, used by Hive and by most EOS keys
+    #[default]
+    K1,
+    /// secp256r1 (P-256), used by EOS mobile and WebAuthn keys
+    R1,
+}
+
+impl SignatureType {
+    /// The prefix of legacy signature strings of this type
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            SignatureType::K1 => "SIG_K1_",
+            SignatureType::R1 => "SIG_R1_",
+        }
+    }
+
+    fn encode_type(&self) -> EncodeType {
+        match self {
+            SignatureType::K1 => EncodeType::K1,
+            SignatureType::R1 => EncodeType::R1,
+        }
+    }
+}
+
 /// An error involving a signature.
 #[derive(Debug, Error)]
 pub enum SignatureError {
@@ -32,12 +68,28 @@ pub enum SignatureError {
     /// produced the signature did not match the expected address)
     #[error("Signature verification failed. Expected {0}, got {1}")]
     VerificationError(PublicAddress, PublicAddress),
-    /// Internal error during signature recovery
-    #[error(transparent)]
-    K256Error(#[from] K256SignatureError),
+    /// Internal error during signature recovery. Without `std` the ecdsa error
+    /// type does not implement `Error`, so it is displayed rather than exposed
+    /// as the source
+    #[error("{0}")]
+    K256Error(K256SignatureError),
     /// Error in recovering public key from signature
     #[error("Public key recovery error")]
     RecoveryError,
+    /// `r` or `s` is zero or not below the curve order, or `v` is not a
+    /// recovery id, see `Signature::is_valid`
+    #[error("malformed signature, r, s or v out of range")]
+    Malformed,
+    /// A legacy signature string that is not base58 or whose checksum does
+    /// not match
+    #[error(transparent)]
+    Decoding(#[from] WifError),
+}
+
+impl From<K256SignatureError> for SignatureError {
+    fn from(src: K256SignatureError) -> Self {
+        SignatureError::K256Error(src)
+    }
 }
 
 /// Recovery message data.
@@ -53,6 +105,19 @@ pub enum RecoveryMessage {
     Hash(H256),
 }
 
+impl Hash for Signature {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut r = [0u8; 32];
+        let mut s = [0u8; 32];
+        self.r.to_big_endian(&mut r);
+        self.s.to_big_endian(&mut s);
+
+        state.write(&r);
+        state.write(&s);
+        state.write_u64(self.v);
+    }
+}
+
 impl fmt::Display for Signature {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let sig = self.to_legacy(None);
@@ -76,6 +141,32 @@ impl Signature {
         Ok(())
     }
 
+    /// Verifies that the signature over an already computed `digest` was produced
+    /// by `public`. The digest is used as is, so it must have been hashed the
+    /// same way the signer hashed it
+    /// ```
+    /// use tetanus::keys::private::PrivateKey;
+    /// use tetanus::utils::sha256;
+    /// let sig = PrivateKey::from_login("test", "test", "owner").sign_message_canonical("helloworld").unwrap();
+    /// let public = sig.recover("helloworld").unwrap();
+    ///
+    /// assert!(sig.verify("helloworld", public.clone()).is_ok());
+    /// assert!(sig.verify_prehashed(sha256("helloworld"), &public).is_ok());
+    /// assert!(sig.verify_prehashed(sha256("goodbyeworld"), &public).is_err());
+    /// ```
+    pub fn verify_prehashed(&self, digest: [u8; 32], public: &PublicAddress) -> Result<(), SignatureError> {
+        let (recoverable_sig, _recovery_id) = self.as_signature()?;
+        let verify_key =
+            recoverable_sig.recover_verify_key_from_digest(Sha256Proxy::from(H256(digest)))?;
+
+        let recovered: PublicAddress = K256PublicKey::from(&verify_key).into();
+        if &recovered != public {
+            return Err(SignatureError::VerificationError(public.clone(), recovered))
+        }
+
+        Ok(())
+    }
+
     /// Recovers the Ethereum address which was used to sign the given message.
     ///
     /// Recovery signature data uses 'Electrum' notation, this means the `v`
@@ -98,8 +189,36 @@ impl Signature {
         Ok(public_key)
     }
 
+    /// Recovers the signer of a `PrivateKey::sign_message_with_prefix`
+    /// signature, `prefix` is prepended to `message` before hashing
+    pub fn recover_with_prefix(&self, message: &str, prefix: &str) -> Result<PublicAddress, SignatureError> {
+        self.recover(prefix.to_owned() + message)
+    }
+
+    /// Whether `r` and `s` are non zero scalars below the secp256k1 order and
+    /// `v` is a recovery id in one of the notations `recovery_id` accepts.
+    /// Verifying or recovering a signature that is not valid fails with
+    /// `SignatureError::Malformed`, this does not check it against a message
+    /// ```
+    /// use primitive_types::U256;
+    /// use tetanus::keys::private::PrivateKey;
+    /// use tetanus::types::signature::Signature;
+    /// assert!(PrivateKey::from_login("test", "test", "owner").sign_message_canonical("helloworld").unwrap().is_valid());
+    /// assert!(!Signature { r: U256::zero(), s: U256::one(), v: 31 }.is_valid());
+    /// assert!(!Signature { r: U256::one(), s: U256::MAX, v: 31 }.is_valid());
+    /// assert!(!Signature { r: U256::one(), s: U256::one(), v: 27 }.is_valid())
+    /// ```
+    pub fn is_valid(&self) -> bool {
+        let in_range = |scalar: U256| !scalar.is_zero() && scalar < SECP256K1_ORDER;
+
+        in_range(self.r) && in_range(self.s) && normalize_recovery_id(self.v) != 4
+    }
+
     /// Retrieves the recovery signature.
     fn as_signature(&self) -> Result<(RecoverableSignature, RecoveryId), SignatureError> {
+        if !self.is_valid() {
+            return Err(SignatureError::Malformed)
+        }
         let recovery_id = self.recovery_id()?;
         let signature = {
             let mut r_bytes = [0u8; 32];
@@ -121,23 +240,133 @@ impl Signature {
         Ok(RecoveryId::new(standard_v)?)
     }
 
+    /// The `r`, `s` and `v` components, with `v` as stored (e.g. `31` or `32`
+    /// for Hive signatures)
+    /// ```
+    /// use tetanus::keys::private::PrivateKey;
+    /// use tetanus::types::signature::Signature;
+    /// let sig = PrivateKey::from_login("test", "test", "owner").sign_message_canonical("helloworld").unwrap();
+    /// let (r, s, v) = sig.split();
+    /// assert_eq!(sig, Signature::from_rsv(r, s, v as u64).unwrap())
+    /// ```
+    pub fn split(&self) -> (U256, U256, u8) {
+        (self.r, self.s, self.v as u8)
+    }
+
+    /// A copy with `v` in the Ethereum notation, 27 or 28. This crate verifies
+    /// and recovers only the Hive (31 or 32) and plain (0 or 1) notations, so
+    /// convert back with `with_hive_v` before using it here. A `v` in no known
+    /// notation is copied as is
+    /// ```
+    /// use primitive_types::U256;
+    /// use tetanus::types::signature::Signature;
+    /// let sig = |v| Signature { r: U256::one(), s: U256::one(), v };
+    /// assert_eq!(27, sig(31).with_ethereum_v().v);
+    /// assert_eq!(28, sig(32).with_ethereum_v().v);
+    /// assert_eq!(27, sig(0).with_ethereum_v().v);
+    /// assert_eq!(28, sig(28).with_ethereum_v().v);
+    /// assert_eq!(5, sig(5).with_ethereum_v().v)
+    /// ```
+    pub fn with_ethereum_v(&self) -> Signature {
+        self.with_v_offset(27)
+    }
+
+    /// A copy with `v` in the Hive notation, 31 or 32, from any notation
+    /// including Ethereum's. A `v` in no known notation is copied as is
+    /// ```
+    /// use primitive_types::U256;
+    /// use tetanus::keys::private::PrivateKey;
+    /// use tetanus::types::signature::Signature;
+    /// let sig = |v| Signature { r: U256::one(), s: U256::one(), v };
+    /// assert_eq!(31, sig(27).with_hive_v().v);
+    /// assert_eq!(32, sig(28).with_hive_v().v);
+    /// assert_eq!(32, sig(1).with_hive_v().v);
+    /// assert_eq!(31, sig(31).with_hive_v().v);
+    ///
+    /// let hive = PrivateKey::from_login("test", "test", "owner").sign_message_canonical("helloworld").unwrap();
+    /// let ethereum = hive.with_ethereum_v();
+    /// assert!(ethereum.is_ethereum_format() && !hive.is_ethereum_format());
+    /// assert!(ethereum.recover("helloworld").is_err());
+    /// assert_eq!(hive.recover("helloworld").unwrap(), ethereum.with_hive_v().recover("helloworld").unwrap())
+    /// ```
+    pub fn with_hive_v(&self) -> Signature {
+        self.with_v_offset(31)
+    }
+
+    /// Whether `v` is in the Ethereum notation, 27 or 28. EIP-155 values,
+    /// which carry the chain id, are not counted
+    pub fn is_ethereum_format(&self) -> bool {
+        matches!(self.v, 27 | 28)
+    }
+
+    fn with_v_offset(&self, offset: u64) -> Signature {
+        let recovery_id = match self.v {
+            27 | 28 => self.v - 27,
+            v => normalize_recovery_id(v) as u64,
+        };
+        let v = if recovery_id < 2 { recovery_id + offset } else { self.v };
+
+        Signature { v, ..*self }
+    }
+
+    /// Builds a signature from its components, `v` may be in any notation
+    /// `recovery_id` understands and is rejected otherwise
+    /// ```
+    /// use primitive_types::U256;
+    /// use tetanus::types::signature::Signature;
+    /// assert!(Signature::from_rsv(U256::one(), U256::one(), 32).is_ok());
+    /// assert!(Signature::from_rsv(U256::one(), U256::one(), 27).is_err())
+    /// ```
+    pub fn from_rsv(r: U256, s: U256, v: u64) -> Result<Signature, SignatureError> {
+        let sig = Signature { r, s, v };
+        sig.recovery_id()?;
+
+        Ok(sig)
+    }
+
     pub fn from_legacy(sig: &str, prefix: Option<&str>) -> Result<Signature, SignatureError> {
         let sig_string = sig.strip_prefix(prefix.unwrap_or("SIG_K1_")).unwrap_or(sig);
 
-        let mut decoded_sig = decode_from_string(sig_string.to_string(), None);
+        let mut decoded_sig = decode_from_string(sig_string.to_string(), None)?;
         decoded_sig.rotate_left(1);
 
-        Ok(Signature::try_from(decoded_sig.as_slice())?)
+        Signature::try_from(decoded_sig.as_slice())
+    }
+
+    /// Parses a legacy signature string, detecting its type from the `SIG_K1_`
+    /// or `SIG_R1_` prefix. Strings without a prefix are read as K1
+    /// ```
+    /// use tetanus::types::signature::{Signature, SignatureType};
+    /// let sig_string = "SIG_K1_JvYLntg1nfTLFTMX9mXGJB95WnbceLKwcvWTc16tVVCX1eCvFKXAtcuRs8xtRqMhH8oHFYAoWUYg8n9iV5nuLxtHojE2eo";
+    /// let (sig, sig_type) = Signature::from_legacy_typed(sig_string).unwrap();
+    /// assert_eq!(SignatureType::K1, sig_type);
+    /// assert_eq!(sig_string, sig.to_legacy(Some(sig_type)));
+    ///
+    /// let r1_string = sig.to_legacy(Some(SignatureType::R1));
+    /// assert!(r1_string.starts_with("SIG_R1_"));
+    /// assert_eq!((sig, SignatureType::R1), Signature::from_legacy_typed(&r1_string).unwrap())
+    /// ```
+    pub fn from_legacy_typed(sig: &str) -> Result<(Signature, SignatureType), SignatureError> {
+        let (sig_string, sig_type) = match sig.strip_prefix(SignatureType::R1.prefix()) {
+            Some(sig_string) => (sig_string, SignatureType::R1),
+            None => (sig.strip_prefix(SignatureType::K1.prefix()).unwrap_or(sig), SignatureType::K1),
+        };
+
+        let mut decoded_sig = decode_from_string(sig_string.to_string(), Some(sig_type.encode_type()))?;
+        decoded_sig.rotate_left(1);
+
+        Ok((Signature::try_from(decoded_sig.as_slice())?, sig_type))
     }
 
     /// Returns a legacy base58 string compatible with eosio-ecc,
-    /// dhive, hivejs, etc
-    pub fn to_legacy(&self, prefix: Option<&str>) -> String {
-        let prefix = prefix.unwrap_or("");
-        // let signature = RecoverableSignature::from_bytes(&self.sig).unwrap();
+    /// dhive, hivejs, etc. With a `sig_type` the string is prefixed and
+    /// checksummed for that type, without one it is an unprefixed K1 string
+    pub fn to_legacy(&self, sig_type: Option<SignatureType>) -> String {
+        let prefix = sig_type.map(|t| t.prefix()).unwrap_or("");
+        let encode_type = sig_type.unwrap_or_default().encode_type();
         let mut current_buff = self.to_vec();
         current_buff.rotate_right(1);
-        let sig_string = encode_to_string(current_buff, None);
+        let sig_string = encode_to_string(current_buff, Some(encode_type));
 
         prefix.to_owned() + &sig_string
     }
@@ -147,6 +376,23 @@ impl Signature {
     pub fn to_vec(&self) -> Vec<u8> {
         self.into()
     }
+
+    /// Parses an owned 65 byte buffer, see the `TryFrom<&[u8]>` impl
+    /// ```
+    /// use tetanus::keys::private::PrivateKey;
+    /// use tetanus::types::signature::Signature;
+    /// let sig = PrivateKey::from_login("test", "test", "owner").sign_message_canonical("helloworld").unwrap();
+    /// assert_eq!(sig, Signature::from_vec(sig.to_vec()).unwrap());
+    /// assert!(Signature::from_vec(vec![0; 64]).is_err())
+    /// ```
+    pub fn from_vec(v: Vec<u8>) -> Result<Signature, SignatureError> {
+        Signature::try_from(v.as_slice())
+    }
+
+    /// The same as `from_vec`, named after `TryFrom` for consistency with it
+    pub fn try_from_vec(v: Vec<u8>) -> Result<Signature, SignatureError> {
+        Signature::from_vec(v)
+    }
 }
 
 fn normalize_recovery_id(v: u64) -> u8 {
@@ -167,15 +413,29 @@ impl<'a> TryFrom<&'a [u8]> for Signature {
     /// the first 32 bytes is the `r` value, the second 32 bytes the `s` value
     /// and the final byte is the `v` value in 'Electrum' notation.
     fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
-        if bytes.len() != 65 {
-            return Err(SignatureError::InvalidLength(bytes.len()))
-        }
+        let bytes = <[u8; 65]>::try_from(bytes)
+            .map_err(|_| SignatureError::InvalidLength(bytes.len()))?;
 
-        let v = bytes[64];
+        Ok(Signature::from(bytes))
+    }
+}
+
+impl From<[u8; 65]> for Signature {
+    /// Splits a raw signature into `r`, `s` and `v` the same way as the
+    /// `TryFrom<&[u8]>` impl, an array is always the right length so this
+    /// cannot fail
+    /// ```
+    /// use tetanus::keys::private::PrivateKey;
+    /// use tetanus::types::signature::Signature;
+    /// let sig = PrivateKey::from_login("test", "test", "owner").sign_message_canonical("helloworld").unwrap();
+    /// let bytes: [u8; 65] = sig.into();
+    /// assert_eq!(sig, Signature::from(bytes))
+    /// ```
+    fn from(bytes: [u8; 65]) -> Self {
         let r = U256::from_big_endian(&bytes[0..32]);
         let s = U256::from_big_endian(&bytes[32..64]);
 
-        Ok(Signature { r, s, v: v.into() })
+        Signature { r, s, v: bytes[64].into() }
     }
 }
 /* 
@@ -248,6 +508,25 @@ impl From<&str> for RecoveryMessage {
     }
 }
 
+impl From<&String> for RecoveryMessage {
+    fn from(s: &String) -> Self {
+        s.as_bytes().to_owned().into()
+    }
+}
+
+/// Byte string literals are message data. Note that an owned `[u8; 32]` is
+/// taken to be an already computed hash instead
+/// ```
+/// use tetanus::types::signature::RecoveryMessage;
+/// assert_eq!(RecoveryMessage::from("helloworld"), RecoveryMessage::from(b"helloworld"));
+/// assert_eq!(RecoveryMessage::from(&String::from("helloworld")), RecoveryMessage::from(b"helloworld"))
+/// ```
+impl<const N: usize> From<&[u8; N]> for RecoveryMessage {
+    fn from(s: &[u8; N]) -> Self {
+        s.to_vec().into()
+    }
+}
+
 impl From<String> for RecoveryMessage {
     fn from(s: String) -> Self {
         RecoveryMessage::Data(s.into_bytes())
@@ -1,10 +1,14 @@
+use alloc::{string::String, vec::Vec};
 use::sha2::{Sha256, Digest as OtherDigest};
 use primitive_types::H256;
 use::ripemd::{Ripemd160, Digest};
+use thiserror::Error;
+use crate::base58;
 
 #[derive(Debug, PartialEq)]
 pub enum EncodeType {
     K1,
+    R1,
     Sha256x2,
     PubKey,
 }
@@ -25,39 +29,71 @@ where
     Sha256::digest(bytes.as_ref()).into()
 }
 
-pub fn decode_from_string(input: String, encoding: Option<EncodeType>) -> Vec<u8> {
-    let encode_type = encoding.unwrap_or(EncodeType::K1);
-    let decoded_buffer = bs58::decode(input).into_vec().unwrap();
-
-    if encode_type == EncodeType::PubKey {
-        let key_buffer = &decoded_buffer[0..&decoded_buffer.len() - 4];
-        let checksum = &decoded_buffer[&decoded_buffer.len() - 4..];
-
-        assert!(key_buffer.len() == 32);
-        assert!(checksum.len() == 4);
-
-        key_buffer.to_vec()
-
-    } else if encode_type == EncodeType::Sha256x2 {
-        let key_buffer_with_network = &decoded_buffer[0..&decoded_buffer.len() - 4];
-        let checksum = &decoded_buffer[&decoded_buffer.len() - 4..];
-        let network_id = &key_buffer_with_network[..1];
-        let key_buffer = &key_buffer_with_network[1..];
-
-        assert!(checksum.len() == 4);
-        assert!(key_buffer.len() == 32);
-        assert!(network_id == &[0x80]);
+/// An error decoding a base58 key or signature string.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum WifError {
+    /// The string is not base58
+    #[error("invalid base58 string")]
+    InvalidBase58,
+    /// The decoded payload is not the size the encoding holds
+    #[error("invalid decoded length {0}")]
+    InvalidLength(usize),
+    /// A private key wif whose network byte is not `0x80`
+    #[error("unknown network byte {0:#04x}")]
+    UnknownNetwork(u8),
+    /// The trailing 4 bytes do not match the checksum of the payload
+    #[error("invalid checksum")]
+    InvalidChecksum,
+}
 
-        key_buffer.to_vec()
+/// The 4 byte checksum `encode_to_string` appends for `encode_type`, over
+/// `buffer` without any network byte
+fn checksum(buffer: &[u8], encode_type: &EncodeType) -> [u8; 4] {
+    let hash: Vec<u8> = match encode_type {
+        EncodeType::Sha256x2 => Sha256::digest(Sha256::digest(&[&[0x80], buffer].concat()).as_slice()).to_vec(),
+        EncodeType::PubKey => Ripemd160::digest(buffer).to_vec(),
+        EncodeType::K1 => Ripemd160::digest([buffer, b"K1"].concat()).to_vec(),
+        EncodeType::R1 => Ripemd160::digest([buffer, b"R1"].concat()).to_vec(),
+    };
+
+    [hash[0], hash[1], hash[2], hash[3]]
+}
 
-    } else {
-        let sig_buffer = &decoded_buffer[0..&decoded_buffer.len() - 4];
-        let checksum = &decoded_buffer[&decoded_buffer.len() - 4..];
-        assert!(sig_buffer.len() == 65);
-        assert!(checksum.len() == 4);
+/// Decodes a string made by `encode_to_string`, checking its checksum. The
+/// payload is returned without the checksum (and without the network byte
+/// of a private key wif)
+/// ```
+/// use tetanus::utils::{decode_from_string, encode_to_string, EncodeType, WifError};
+/// let encoded = encode_to_string(vec![1; 65], None);
+/// assert_eq!(Ok(vec![1; 65]), decode_from_string(encoded.clone(), None));
+/// assert_eq!(Err(WifError::InvalidChecksum), decode_from_string(encoded, Some(EncodeType::R1)))
+/// ```
+pub fn decode_from_string(input: String, encoding: Option<EncodeType>) -> Result<Vec<u8>, WifError> {
+    let encode_type = encoding.unwrap_or(EncodeType::K1);
+    let decoded_buffer = bs58::decode(input).into_vec().map_err(|_| WifError::InvalidBase58)?;
+    if decoded_buffer.len() < 4 {
+        return Err(WifError::InvalidLength(decoded_buffer.len()))
+    }
 
-        sig_buffer.to_vec()
+    let (payload, check) = decoded_buffer.split_at(decoded_buffer.len() - 4);
+    let payload = match encode_type {
+        EncodeType::PubKey if payload.len() != 33 => return Err(WifError::InvalidLength(payload.len())),
+        EncodeType::Sha256x2 => match payload {
+            [0x80, key @ ..] if key.len() == 32 => key,
+            [network, key @ ..] if key.len() == 32 => return Err(WifError::UnknownNetwork(*network)),
+            _ => return Err(WifError::InvalidLength(payload.len())),
+        },
+        // K1 and R1 share the suffixed checksum layout, which is used for
+        // private keys, public keys and signatures alike so the payload length
+        // is left to the caller
+        _ => payload,
+    };
+
+    if checksum(payload, &encode_type) != check {
+        return Err(WifError::InvalidChecksum)
     }
+
+    Ok(payload.to_vec())
 }
 
 pub fn encode_to_string(buffer: Vec<u8>, encoding: Option<EncodeType>) -> String {
@@ -72,7 +108,7 @@ pub fn encode_to_string(buffer: Vec<u8>, encoding: Option<EncodeType>) -> String
 
         let input = [buffer, hash[0..4].to_vec()].concat();
 
-        bs58::encode(input).into_string()
+        base58::encode(input)
 
     } else if encode_type == EncodeType::Sha256x2 {
         let network_id: &[u8] = &[0x80];
@@ -82,9 +118,9 @@ pub fn encode_to_string(buffer: Vec<u8>, encoding: Option<EncodeType>) -> String
 
         let with_checksum = [key_vec, checksum[0..4].to_vec()].concat();
 
-        return bs58::encode(with_checksum).into_string();
+        base58::encode(with_checksum)
     } else {
-        let check_bytes = b"K1";
+        let check_bytes: &[u8] = if encode_type == EncodeType::R1 { b"R1" } else { b"K1" };
 
         let check = [buffer.clone(), check_bytes.to_vec()].concat();
 
@@ -96,8 +132,64 @@ pub fn encode_to_string(buffer: Vec<u8>, encoding: Option<EncodeType>) -> String
 
         let checksum = &result[0..4];
 
-        let encoded_string = bs58::encode([buffer, checksum.to_vec()].concat()).into_string();
+        base58::encode([buffer, checksum.to_vec()].concat())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::*;
+
+    // the first 32 bytes of the `PrivateKey::from_seed` vector and its public key
+    const WIF: &str = "5HpjKrb7dH5kKQQzmbjB87Mxova7mek5bXUTWfndcX6tBoqUwzm";
+    const PUBLIC: &str = "5txDiyu3J4qWKD2XtAr6kZqCXofc9Uuve9NUcRkzGSZJVyWhSN";
+
+    fn corrupt(encoded: &str) -> String {
+        let last = if encoded.ends_with('1') { '2' } else { '1' };
+        [&encoded[..encoded.len() - 1], &last.to_string()].concat()
+    }
+
+    #[test]
+    fn round_trips_signatures() {
+        let signature: Vec<u8> = (0..65).collect();
+        for (encoding, check) in [(EncodeType::K1, EncodeType::K1), (EncodeType::R1, EncodeType::R1)] {
+            let encoded = encode_to_string(signature.clone(), Some(encoding));
+            assert_eq!(Ok(signature.clone()), decode_from_string(encoded.clone(), Some(check)));
+        }
+        assert_eq!(encode_to_string(signature.clone(), None), encode_to_string(signature, Some(EncodeType::K1)));
+    }
 
-        return encoded_string
+    #[test]
+    fn round_trips_private_keys() {
+        let key: Vec<u8> = (1..=32).collect();
+        assert_eq!(WIF, encode_to_string(key.clone(), Some(EncodeType::Sha256x2)));
+        assert_eq!(Ok(key), decode_from_string(WIF.to_string(), Some(EncodeType::Sha256x2)));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn round_trips_public_keys() {
+        let key = decode_from_string(PUBLIC.to_string(), Some(EncodeType::PubKey)).unwrap();
+        assert_eq!(33, key.len());
+        assert_eq!(PUBLIC, encode_to_string(key, Some(EncodeType::PubKey)));
+    }
+
+    #[test]
+    fn bad_inputs_are_rejected() {
+        assert_eq!(Err(WifError::InvalidBase58), decode_from_string("0OIl".to_string(), None));
+        assert_eq!(Err(WifError::InvalidLength(3)), decode_from_string(crate::base58::encode([1, 2, 3]), None));
+
+        // valid checksums of the wrong encoding
+        let signature = encode_to_string(vec![1; 65], Some(EncodeType::K1));
+        assert_eq!(Err(WifError::InvalidChecksum), decode_from_string(signature.clone(), Some(EncodeType::R1)));
+        assert_eq!(Err(WifError::InvalidLength(65)), decode_from_string(signature, Some(EncodeType::PubKey)));
+        assert_eq!(Err(WifError::InvalidChecksum), decode_from_string(PUBLIC.to_string(), Some(EncodeType::K1)));
+
+        assert_eq!(Err(WifError::InvalidChecksum), decode_from_string(corrupt(WIF), Some(EncodeType::Sha256x2)));
+        assert_eq!(Err(WifError::InvalidChecksum), decode_from_string(corrupt(PUBLIC), Some(EncodeType::PubKey)));
+        assert_eq!(Err(WifError::InvalidLength(32)), decode_from_string(encode_to_string(vec![1; 31], Some(EncodeType::Sha256x2)), Some(EncodeType::Sha256x2)));
+
+        // the network byte is checked before the checksum
+        let testnet = encode_to_string([&[0xef][..], &[1; 32]].concat(), Some(EncodeType::PubKey));
+        assert_eq!(Err(WifError::UnknownNetwork(0xef)), decode_from_string(testnet, Some(EncodeType::Sha256x2)));
+    }
+}
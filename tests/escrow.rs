@@ -0,0 +1,129 @@
+//! Walks an escrow through its whole lifecycle, transfer, approval by both the
+//! receiver and the agent, a dispute and the agent's release, checking each
+//! stage builds, validates, packs and signs as a transaction by the right party
+#![cfg(feature = "crypto")]
+
+use serde_json::json;
+use tetanus::hive::operations::*;
+use tetanus::hive::transactions::{Transaction, HIVE_CHAIN_ID};
+use tetanus::hive::types::{AssetSymbol, HiveAsset};
+use tetanus::keys::{private::PrivateKey, public::PublicKey};
+
+const ESCROW_ID: u32 = 23456789;
+
+fn base() -> Transaction {
+    serde_json::from_value(json!({
+        "ref_block_num": 34294,
+        "ref_block_prefix": 3707022213u32,
+        "expiration": "2024-01-01T00:01:00",
+        "operations": [],
+        "extensions": []
+    }))
+    .unwrap()
+}
+
+/// Builds a transaction for `operation`, signs it with `signer`'s active key and
+/// checks the signature recovers to that key
+fn submit(operation: impl Into<Operation>, signer: &str) -> Transaction {
+    let tx = base().add_operation(operation).with_chain_id(HIVE_CHAIN_ID);
+    tx.validate().unwrap();
+
+    let key = PrivateKey::from_login(signer, "password", "active");
+    let sig = key.sign_transaction(&tx).unwrap();
+    assert_eq!(key.to_public(), PublicKey::recover_prehashed(&sig, tx.digest().unwrap()).unwrap());
+
+    tx
+}
+
+fn transfer() -> EscrowTransferOperation {
+    EscrowTransferOperation {
+        from: "alice".to_string(),
+        to: "bob".to_string(),
+        agent: "carol".to_string(),
+        escrow_id: ESCROW_ID,
+        hbd_amount: HiveAsset::new(5000, AssetSymbol::Hbd),
+        hive_amount: HiveAsset::new(2500, AssetSymbol::Hive),
+        fee: HiveAsset::new(100, AssetSymbol::Hive),
+        ratification_deadline: "2024-01-02T00:00:00".to_string(),
+        escrow_expiration: "2024-01-31T00:00:00".to_string(),
+        json_meta: r#"{"terms":"delivery by 2024-01-15"}"#.to_string(),
+    }
+}
+
+fn approve(who: &str) -> EscrowApproveOperation {
+    EscrowApproveOperation {
+        from: "alice".to_string(),
+        to: "bob".to_string(),
+        agent: "carol".to_string(),
+        who: who.to_string(),
+        escrow_id: ESCROW_ID,
+        approve: true,
+    }
+}
+
+#[test]
+fn escrow_lifecycle() {
+    let transfer = submit(transfer(), "alice");
+    let receiver_approval = submit(approve("bob"), "bob");
+    let agent_approval = submit(approve("carol"), "carol");
+    let dispute = submit(
+        EscrowDisputeOperation {
+            from: "alice".to_string(),
+            to: "bob".to_string(),
+            agent: "carol".to_string(),
+            who: "bob".to_string(),
+            escrow_id: ESCROW_ID,
+        },
+        "bob",
+    );
+    // once disputed only the agent can release, here refunding part of it
+    let release = submit(
+        EscrowReleaseOperation {
+            from: "alice".to_string(),
+            to: "bob".to_string(),
+            agent: "carol".to_string(),
+            who: "carol".to_string(),
+            receiver: "alice".to_string(),
+            escrow_id: ESCROW_ID,
+            hbd_amount: HiveAsset::new(5000, AssetSymbol::Hbd),
+            hive_amount: HiveAsset::new(0, AssetSymbol::Hive),
+        },
+        "carol",
+    );
+
+    let stages = [transfer, receiver_approval, agent_approval, dispute, release];
+    for (i, tx) in stages.iter().enumerate() {
+        // each stage survives a round trip through json unchanged
+        let json = serde_json::to_value(tx).unwrap();
+        let parsed: Transaction = serde_json::from_value(json).unwrap();
+        assert_eq!(tx.to_bytes().unwrap(), parsed.to_bytes().unwrap(), "stage {}", i);
+
+        for other in &stages[i + 1..] {
+            assert_ne!(tx.id().unwrap(), other.id().unwrap());
+        }
+    }
+}
+
+#[test]
+fn escrow_rules_are_checked_per_stage() {
+    let expired = EscrowTransferOperation { escrow_expiration: "2024-01-01T12:00:00".to_string(), ..transfer() };
+    let ratified = EscrowTransferOperation { ratification_deadline: "2024-02-01T00:00:00".to_string(), ..transfer() };
+    assert!(base().add_operation(expired).validate().is_err());
+    assert!(base().add_operation(ratified).validate().is_err());
+
+    // the sender can't approve on behalf of the other parties
+    assert!(base().add_operation(approve("alice")).validate().is_err());
+
+    let refund_to_agent = EscrowReleaseOperation {
+        from: "alice".to_string(),
+        to: "bob".to_string(),
+        agent: "carol".to_string(),
+        who: "carol".to_string(),
+        receiver: "carol".to_string(),
+        escrow_id: ESCROW_ID,
+        hbd_amount: HiveAsset::new(0, AssetSymbol::Hbd),
+        hive_amount: HiveAsset::new(2500, AssetSymbol::Hive),
+    };
+    assert!(base().add_operation(refund_to_agent.clone()).validate().is_err());
+    assert!(refund_to_agent.validate().is_err());
+}
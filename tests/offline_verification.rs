@@ -0,0 +1,60 @@
+//! Signs a transfer from a two key account the way a multisig flow would, each
+//! signer on their own copy of the transaction, then merges the signatures and
+//! checks them offline against the account's keys before it would be broadcast
+#![cfg(feature = "crypto")]
+
+use serde_json::json;
+use tetanus::hive::transactions::{Transaction, TransactionError, HIVE_CHAIN_ID};
+use tetanus::keys::private::PrivateKey;
+
+fn transfer() -> Transaction {
+    serde_json::from_value::<Transaction>(json!({
+        "ref_block_num": 34294,
+        "ref_block_prefix": 3707022213u32,
+        "expiration": "2024-01-01T00:01:00",
+        "operations": [["transfer", { "from": "treasury", "to": "bob", "amount": "10.000 HIVE", "memo": "payout" }]],
+        "extensions": []
+    }))
+    .unwrap()
+    .with_chain_id(HIVE_CHAIN_ID)
+}
+
+fn key(signer: &str) -> PrivateKey {
+    PrivateKey::from_login(signer, "password", "active")
+}
+
+#[test]
+fn fully_signed_transaction_verifies() {
+    let mut tx = transfer();
+    for signer in ["alice", "carol"] {
+        tx.add_signature(key(signer).sign_transaction(&transfer()).unwrap());
+    }
+    let alice = key("alice").to_public().to_string(None);
+    let carol = key("carol").to_public().to_string(None);
+
+    assert_eq!(vec![true, true], tx.verify_signatures(&[&alice, &carol]).unwrap());
+    assert_eq!(vec![true, false], tx.verify_signatures(&[&alice]).unwrap());
+    assert!(tx.verify_signatures(&[]).unwrap().iter().all(|valid| !valid));
+}
+
+#[test]
+fn signatures_for_another_transaction_fail() {
+    let mallory = key("mallory");
+    let other = transfer().with_chain_id([0; 32]);
+
+    let mut tx = transfer();
+    tx.add_signature(key("alice").sign_transaction(&tx).unwrap());
+    tx.add_signature(mallory.sign_transaction(&other).unwrap());
+
+    let keys = [key("alice").to_public().to_string(None), mallory.to_public().to_string(None)];
+    assert_eq!(vec![true, false], tx.verify_signatures(&[&keys[0], &keys[1]]).unwrap());
+    assert!(transfer().verify_signatures(&[&keys[0]]).unwrap().is_empty());
+}
+
+#[test]
+fn invalid_keys_are_rejected() {
+    let tx = transfer();
+
+    let error = tx.verify_signatures(&["STM1"]).unwrap_err();
+    assert!(matches!(error, TransactionError::InvalidValue { field, .. } if field == "public_keys"));
+}
@@ -0,0 +1,225 @@
+//! Checks transaction packing, ids, digests and signatures against the fixed
+//! vectors in `golden/transaction_vectors.json`. Each vector has a transaction
+//! with its packed bytes, id, digest over `HIVE_CHAIN_ID` and the signature of
+//! `VECTOR_KEY` over that digest, with the recovery id first as nodes send it.
+//! The values were computed outside this crate, with a separate implementation
+//! of hived's serializer and RFC 6979 signing.
+//!
+//! The `live` tests check the same things against transactions that are
+//! already on chain. They need a node, so they are ignored by default and run
+//! with
+//!
+//! `cargo test --features hive-client --test vectors -- --ignored`
+//!
+//! `HIVE_NODE` picks the node (the public testnet by default) and
+//! `HIVE_VECTOR_COUNT` how many transactions are checked.
+#![cfg(feature = "crypto")]
+
+use serde::Deserialize;
+use serde_json::Value;
+use tetanus::hive::transactions::{Transaction, HIVE_CHAIN_ID};
+use tetanus::keys::{private::PrivateKey, public::PublicKey};
+use tetanus::signatures::SignatureWrapper;
+
+const VECTORS: &str = include_str!("golden/transaction_vectors.json");
+/// The key the vector signatures were made with
+const VECTOR_KEY: &str = "5KQwrPbwdL6PhXujxW37FSSQZ1JiwsST4cqQzDeyXtP79zkvFD3";
+
+#[derive(Deserialize)]
+struct FixedVector {
+    transaction: Value,
+    packed: String,
+    id: String,
+    digest: String,
+    signature: String,
+}
+
+fn fixed_vectors() -> Vec<(Transaction, FixedVector)> {
+    let vectors: Vec<FixedVector> = serde_json::from_str(VECTORS).unwrap();
+
+    vectors.into_iter().map(|vector| (serde_json::from_value(vector.transaction.clone()).unwrap(), vector)).collect()
+}
+
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Nodes send signatures as hex with the recovery id first, the wrapper keeps it
+/// last
+fn signature_from_hex(hex: &str) -> SignatureWrapper {
+    let mut bytes = from_hex(hex);
+    bytes.rotate_left(1);
+
+    SignatureWrapper::new(bytes)
+}
+
+#[test]
+fn packed_bytes_match_the_vectors() {
+    for (tx, vector) in fixed_vectors() {
+        assert_eq!(vector.packed, to_hex(&tx.to_bytes().unwrap()), "transaction {}", vector.id);
+        assert_eq!(tx, Transaction::from_bytes(&from_hex(&vector.packed)).unwrap());
+    }
+}
+
+#[test]
+fn ids_match_the_vectors() {
+    for (tx, vector) in fixed_vectors() {
+        assert_eq!(vector.id, tx.id().unwrap());
+    }
+}
+
+#[test]
+fn digests_match_the_vectors() {
+    for (tx, vector) in fixed_vectors() {
+        assert_eq!(vector.digest, to_hex(&tx.with_chain_id(HIVE_CHAIN_ID).digest().unwrap()), "transaction {}", vector.id);
+    }
+}
+
+#[test]
+fn sign_transaction_matches_the_vectors() {
+    let private = PrivateKey::from_string(VECTOR_KEY);
+
+    for (tx, vector) in fixed_vectors() {
+        let tx = tx.with_chain_id(HIVE_CHAIN_ID);
+        let sig = private.sign_transaction(&tx).unwrap();
+
+        assert_eq!(signature_from_hex(&vector.signature), sig, "transaction {}", vector.id);
+        assert_eq!(private.to_public(), PublicKey::recover_prehashed(&sig, tx.digest().unwrap()).unwrap());
+    }
+}
+
+#[cfg(all(feature = "hive-client", not(target_arch = "wasm32")))]
+mod live {
+    use serde_json::{json, Value};
+    use tetanus::hive::client::{HiveClient, HiveClientTrait};
+    use tetanus::hive::transactions::Transaction;
+    use tetanus::keys::public::PublicKey;
+    use tetanus::signatures::SignatureWrapper;
+    use super::{from_hex, signature_from_hex, to_hex};
+
+    const DEFAULT_NODE: &str = "https://testnet.openhive.network";
+    const DEFAULT_COUNT: usize = 5;
+    const MAX_BLOCKS_SCANNED: u32 = 2000;
+
+    /// A transaction as included in a block, with what the chain reports for it
+    struct Vector {
+        json: Value,
+        tx: Transaction,
+        id: String,
+        signatures: Vec<SignatureWrapper>,
+    }
+
+    struct Chain {
+        client: HiveClient,
+        chain_id: [u8; 32],
+        address_prefix: String,
+    }
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(future)
+    }
+
+    async fn connect() -> Chain {
+        let url = std::env::var("HIVE_NODE").unwrap_or_else(|_| DEFAULT_NODE.to_string());
+        let client = HiveClient::new(&url);
+        let config = client.request("condenser_api.get_config", json!([])).await.unwrap();
+
+        let chain_id = from_hex(config["HIVE_CHAIN_ID"].as_str().unwrap()).try_into().unwrap();
+        let address_prefix = config["HIVE_ADDRESS_PREFIX"].as_str().unwrap().to_string();
+
+        Chain { client, chain_id, address_prefix }
+    }
+
+    /// Walks back from the head block collecting transactions whose operations are
+    /// all ones `Transaction::to_bytes` can pack
+    async fn vectors(chain: &Chain) -> Vec<Vector> {
+        let count = std::env::var("HIVE_VECTOR_COUNT").ok().and_then(|count| count.parse().ok()).unwrap_or(DEFAULT_COUNT);
+        let head = chain.client.get_dynamic_global_properties().await.unwrap().head_block_number;
+        let mut vectors = vec![];
+
+        for block_num in (head.saturating_sub(MAX_BLOCKS_SCANNED)..head).rev() {
+            let block = match chain.client.get_block(block_num).await.unwrap() {
+                Some(block) => block,
+                None => continue,
+            };
+
+            for (json, id) in block.transactions.into_iter().zip(block.transaction_ids) {
+                let tx: Transaction = serde_json::from_value(json.clone()).unwrap();
+                if tx.to_bytes().is_err() {
+                    continue
+                }
+
+                let signatures = json["signatures"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|sig| signature_from_hex(sig.as_str().unwrap()))
+                    .collect();
+
+                vectors.push(Vector { json, tx, id, signatures });
+                if vectors.len() == count {
+                    return vectors
+                }
+            }
+        }
+
+        panic!("found only {} packable transactions in the last {} blocks", vectors.len(), MAX_BLOCKS_SCANNED)
+    }
+
+    #[test]
+    #[ignore]
+    fn packed_bytes_match_the_node() {
+        block_on(async {
+            let chain = connect().await;
+
+            for vector in vectors(&chain).await {
+                let mut unsigned = vector.json.clone();
+                unsigned["signatures"] = json!([]);
+                let reference = chain.client.request("condenser_api.get_transaction_hex", json!([unsigned])).await.unwrap();
+
+                // the node appends the (empty) signature array
+                let expected = to_hex(&[vector.tx.to_bytes().unwrap(), vec![0]].concat());
+                assert_eq!(reference.as_str().unwrap(), expected, "transaction {}", vector.id);
+            }
+        })
+    }
+
+    #[test]
+    #[ignore]
+    fn ids_match_the_block() {
+        block_on(async {
+            let chain = connect().await;
+
+            for vector in vectors(&chain).await {
+                assert_eq!(vector.id, vector.tx.id().unwrap());
+            }
+        })
+    }
+
+    #[test]
+    #[ignore]
+    fn signatures_recover_to_account_keys() {
+        block_on(async {
+            let chain = connect().await;
+
+            for vector in vectors(&chain).await {
+                let digest = vector.tx.with_chain_id(chain.chain_id).digest().unwrap();
+
+                for sig in &vector.signatures {
+                    let public = PublicKey::recover_prehashed(sig, digest).unwrap();
+                    let key = chain.address_prefix.clone() + &public.to_string(None)[3..];
+
+                    let accounts = chain.client.request("condenser_api.get_key_references", json!([[key]])).await.unwrap();
+                    assert_ne!(json!([[]]), accounts, "transaction {} signed by unknown key {}", vector.id, key);
+                }
+            }
+        })
+    }
+}
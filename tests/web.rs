@@ -1,16 +1,142 @@
-use tetanus::keys::private::*;
-use wasm_bindgen_test::*;
-
-#[wasm_bindgen_test]
-fn get_private_wif() {
-    let wif = PrivateKey::from_login("test", "test", "owner").to_string();
-
-    assert_eq!("5K8AruCpTY6gVeQRMd5UpeuoVR2YheRCjUDAVFrfiahZU4bBccj", wif)
-}
-
-#[wasm_bindgen_test]
-fn login_equals_new() {
-    let private1 = PrivateKey::new(vec![172, 77, 224, 92, 161, 163, 181, 53, 80, 219, 255, 168, 223, 31, 231, 32, 238, 108, 150, 219, 77, 153, 8, 68, 240, 148, 105, 203, 131, 235, 219, 82]);
-    let private2 = PrivateKey::from_login("test", "test", "owner");
-    assert_eq!(private1, private2)
-}
\ No newline at end of file
+use tetanus::keys::private::*;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+fn get_private_wif() {
+    let wif = PrivateKey::from_login("test", "test", "owner").to_string();
+
+    assert_eq!("5K8AruCpTY6gVeQRMd5UpeuoVR2YheRCjUDAVFrfiahZU4bBccj", wif)
+}
+
+#[wasm_bindgen_test]
+fn login_equals_new() {
+    let private1 = PrivateKey::new(vec![172, 77, 224, 92, 161, 163, 181, 53, 80, 219, 255, 168, 223, 31, 231, 32, 238, 108, 150, 219, 77, 153, 8, 68, 240, 148, 105, 203, 131, 235, 219, 82]);
+    let private2 = PrivateKey::from_login("test", "test", "owner");
+    assert_eq!(private1, private2)
+}
+#[cfg(all(feature = "wasm-client", target_arch = "wasm32"))]
+mod client {
+    use serde::Serialize;
+    use serde_json::{json, Value};
+    use tetanus::hive::client::WasmHiveClient;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen_futures::JsFuture;
+    use wasm_bindgen_test::*;
+
+    // Replaces the global `fetch` so requests never leave the test process,
+    // reqwest parses the response url so it is copied over from the request
+    #[wasm_bindgen(inline_js = "export function mock_fetch(body) {
+        globalThis.fetch = async (request) => {
+            const response = new Response(body, { status: 200, headers: { 'content-type': 'application/json' } });
+            Object.defineProperty(response, 'url', { value: request.url });
+            return response;
+        };
+    }")]
+    extern "C" {
+        fn mock_fetch(body: &str);
+    }
+
+    async fn request(method: &str, params: Value) -> Result<Value, JsValue> {
+        let client = WasmHiveClient::new("https://api.hive.blog");
+        let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+        let params = params.serialize(&serializer).unwrap();
+        let result = JsFuture::from(client.request(method.to_string(), params)).await?;
+
+        Ok(serde_wasm_bindgen::from_value(result).unwrap())
+    }
+
+    #[wasm_bindgen_test]
+    async fn query_resolves_with_result() {
+        mock_fetch(r#"{"jsonrpc":"2.0","result":{"head_block_number":1000},"id":1}"#);
+
+        let result = request("condenser_api.get_dynamic_global_properties", json!([])).await.unwrap();
+
+        assert_eq!(json!({ "head_block_number": 1000 }), result)
+    }
+
+    #[wasm_bindgen_test]
+    async fn broadcast_rejects_with_rpc_error() {
+        mock_fetch(r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"missing required posting authority"},"id":1}"#);
+
+        let error = request("condenser_api.broadcast_transaction", json!([{}])).await.unwrap_err();
+
+        assert_eq!(Some("rpc error -32000: missing required posting authority".to_string()), error.as_string())
+    }
+}
+
+#[cfg(feature = "wasm")]
+mod builders {
+    use serde_json::json;
+    use tetanus::hive::operations::Operation;
+    use tetanus::hive::transactions::Transaction;
+    use tetanus::hive::wasm::*;
+    use wasm_bindgen_test::*;
+
+    fn transaction() -> Transaction {
+        serde_json::from_value(json!({
+            "ref_block_num": 1, "ref_block_prefix": 2, "expiration": "2024-01-01T00:00:00", "operations": [], "extensions": []
+        })).unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn transfer_builds_operation() {
+        let mut op = WasmTransferOperation::new();
+        op.set_from("alice");
+        op.set_to("bob");
+        op.set_amount("1.000 HIVE");
+        op.set_memo("thanks");
+
+        let op = Operation::from(op.build());
+        assert_eq!("transfer", op.name());
+        assert_eq!(json!({ "from": "alice", "to": "bob", "amount": "1.000 HIVE", "memo": "thanks" }), json!(op.data()));
+        assert!(op.validate().is_ok());
+    }
+
+    #[wasm_bindgen_test]
+    fn vote_and_comment_build_operations() {
+        let mut vote = WasmVoteOperation::new();
+        vote.set_voter("alice");
+        vote.set_author("bob");
+        vote.set_permlink("a-post");
+        vote.set_weight(-2500);
+        assert_eq!(json!({ "voter": "alice", "author": "bob", "permlink": "a-post", "weight": -2500 }), json!(Operation::from(vote.build()).data()));
+
+        let mut comment = WasmCommentOperation::new();
+        comment.set_parent_permlink("hive");
+        comment.set_author("alice");
+        comment.set_permlink("a-post");
+        comment.set_title("A post");
+        comment.set_body("...");
+        comment.set_json_metadata("{}");
+        let comment = Operation::from(comment.build());
+        assert_eq!(json!(""), comment.data()["parent_author"]);
+        assert_eq!(json!("A post"), comment.data()["title"]);
+        assert!(comment.validate().is_ok());
+    }
+
+    #[wasm_bindgen_test]
+    fn custom_json_is_typed() {
+        let mut op = WasmCustomJsonOperation::new();
+        op.add_required_posting_auth("alice");
+        op.set_id("follow");
+        op.set_json(r#"["follow",{"follower":"alice","following":"bob","what":["blog"]}]"#);
+
+        let op = Operation::from(op.build());
+        assert!(matches!(&op, Operation::CustomJson(custom) if custom.required_posting_auths == ["alice"]));
+        assert!(op.validate().is_ok());
+    }
+
+    #[wasm_bindgen_test]
+    fn built_operations_are_signed() {
+        let mut transfer = WasmTransferOperation::new();
+        transfer.set_from("alice");
+        transfer.set_to("bob");
+        transfer.set_amount("0.001 HBD");
+
+        let mut tx = transaction();
+        tx.push_operation(&transfer.build());
+        assert_eq!(r#"Transaction { ref_block: 1/2, expiration: 2024-01-01T00:00:00, ops: [transfer], sigs: 0 }"#, tx.to_string());
+        assert!(tx.validate().is_ok());
+        assert!(tx.to_bytes().is_ok());
+    }
+}